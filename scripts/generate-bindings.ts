@@ -0,0 +1,477 @@
+#!/usr/bin/env bun
+/**
+ * Generate TypeScript bindings for the Tauri commands
+ *
+ * Reads the commands registered in src-tauri/src/lib.rs, their Rust
+ * signatures, and every serde type they reference, and writes typed
+ * `invoke` wrappers to src/tauri-bindings.ts. Commands reject with the
+ * error string from their `Result<T, String>`, exported as `CommandError`.
+ *
+ * Usage:
+ *   bun run scripts/generate-bindings.ts           # Write the bindings
+ *   bun run scripts/generate-bindings.ts --check   # Fail if they are stale
+ */
+
+import * as path from "path";
+import * as fs from "fs";
+import { fileURLToPath } from "url";
+
+const PROJECT_ROOT = path.join(path.dirname(fileURLToPath(import.meta.url)), "..");
+const RUST_SRC = path.join(PROJECT_ROOT, "src-tauri", "src");
+const OUTPUT = path.join(PROJECT_ROOT, "src", "tauri-bindings.ts");
+
+// Parameters Tauri injects rather than reading from the invoke payload
+const INJECTED_PARAMS = new Set(["AppHandle", "Window", "WebviewWindow", "Webview", "State"]);
+
+interface Field {
+  name: string;
+  type: string;
+  optional: boolean;
+  doc: string[];
+}
+
+interface Variant {
+  name: string;
+  fields: Field[] | null;
+  tuple: string | null;
+  doc: string[];
+  /** Serialized name from a variant-level `#[serde(rename = "...")]` */
+  rename: string | null;
+}
+
+interface TypeDef {
+  name: string;
+  doc: string[];
+  serde: Record<string, string | true>;
+  fields?: Field[];
+  variants?: Variant[];
+  alias?: string;
+}
+
+interface Command {
+  module: string;
+  name: string;
+  doc: string[];
+  params: { name: string; type: string }[];
+  result: string;
+}
+
+/** Split on commas that are not nested in <>, (), or [] */
+function splitTopLevel(input: string): string[] {
+  const parts: string[] = [];
+  let depth = 0;
+  let current = "";
+  for (const ch of input) {
+    if ("<([".includes(ch)) depth++;
+    if (">)]".includes(ch)) depth--;
+    if (ch === "," && depth === 0) {
+      parts.push(current.trim());
+      current = "";
+    } else {
+      current += ch;
+    }
+  }
+  if (current.trim()) parts.push(current.trim());
+  return parts;
+}
+
+/** Parse `#[serde(...)]` arguments into a map */
+function parseSerde(attrs: string[]): Record<string, string | true> {
+  const serde: Record<string, string | true> = {};
+  for (const attr of attrs) {
+    const match = /^#\[serde\((.*)\)\]$/s.exec(attr);
+    if (!match) continue;
+    for (const arg of splitTopLevel(match[1])) {
+      const [key, value] = arg.split("=").map((s) => s.trim());
+      serde[key] = value ? value.replace(/^"|"$/g, "") : true;
+    }
+  }
+  return serde;
+}
+
+function renameCase(name: string, rule: string | true | undefined): string {
+  const words = name
+    .replace(/([a-z0-9])([A-Z])/g, "$1_$2")
+    .split("_")
+    .filter(Boolean)
+    .map((w) => w.toLowerCase());
+  switch (rule) {
+    case "snake_case":
+      return words.join("_");
+    case "camelCase":
+      return words.map((w, i) => (i === 0 ? w : w[0].toUpperCase() + w.slice(1))).join("");
+    case "kebab-case":
+      return words.join("-");
+    case "lowercase":
+      return name.toLowerCase();
+    case "SCREAMING_SNAKE_CASE":
+      return words.join("_").toUpperCase();
+    default:
+      return name;
+  }
+}
+
+/** Rust argument names become camelCase keys in the invoke payload */
+function camelCase(name: string): string {
+  return name.replace(/_([a-z0-9])/g, (_, c: string) => c.toUpperCase());
+}
+
+/** Read `///` comments, attributes, and the item that follows them */
+function* items(source: string): Generator<{ doc: string[]; attrs: string[]; body: string }> {
+  const lines = source.split("\n");
+  let doc: string[] = [];
+  let attrs: string[] = [];
+  for (let i = 0; i < lines.length; i++) {
+    const line = lines[i].trim();
+    if (line.startsWith("///")) {
+      doc.push(line.replace(/^\/\/\/ ?/, ""));
+      continue;
+    }
+    if (line.startsWith("#[")) {
+      let attr = line;
+      while (!attr.endsWith("]") && i + 1 < lines.length) attr += " " + lines[++i].trim();
+      attrs.push(attr);
+      continue;
+    }
+    const item = /^pub(?:\(crate\))? (struct|enum|type|async fn|fn) /.exec(line);
+    if (item) {
+      // Collect the item up to its closing brace or semicolon
+      let body = line;
+      let depth = (line.match(/{/g) ?? []).length - (line.match(/}/g) ?? []).length;
+      while (i + 1 < lines.length && (depth > 0 || !/[;{}]\s*$/.test(body))) {
+        const next = lines[++i];
+        body += "\n" + next;
+        depth += (next.match(/{/g) ?? []).length - (next.match(/}/g) ?? []).length;
+        if (item[1].includes("fn") && body.includes("{")) break;
+      }
+      yield { doc, attrs, body };
+    }
+    if (line !== "") {
+      doc = [];
+      attrs = [];
+    }
+  }
+}
+
+/**
+ * Parse `name: Type,` fields with their docs and serde attributes
+ *
+ * Fields are optional when they may be omitted from the frontend's input:
+ * `Option`s, and fields with serde defaults when `deserialize` is set.
+ */
+function parseFields(body: string, renameAll: string | true | undefined, deserialize: boolean, allDefault = false): Field[] {
+  const fields: Field[] = [];
+  let doc: string[] = [];
+  let attrs: string[] = [];
+  let pending = "";
+  for (const raw of body.split("\n")) {
+    const line = raw.trim();
+    if (line.startsWith("///")) {
+      doc.push(line.replace(/^\/\/\/ ?/, ""));
+      continue;
+    }
+    if (line.startsWith("#[")) {
+      attrs.push(line);
+      continue;
+    }
+    if (line.startsWith("//") || line === "") continue;
+    pending += (pending ? " " : "") + line;
+    const match = /^(?:pub(?:\(crate\))? )?(\w+):\s*(.+?),?$/.exec(pending);
+    if (!match || !pending.endsWith(",")) {
+      if (!/[<(,]$/.test(pending)) pending = "";
+      continue;
+    }
+    const serde = parseSerde(attrs);
+    if (!serde.skip && !serde.skip_serializing) {
+      const type = match[2].replace(/,$/, "");
+      fields.push({
+        name: typeof serde.rename === "string" ? serde.rename : renameCase(match[1], renameAll),
+        type,
+        optional: (deserialize && (allDefault || serde.default !== undefined)) || type.startsWith("Option<"),
+        doc,
+      });
+    }
+    pending = "";
+    doc = [];
+    attrs = [];
+  }
+  return fields;
+}
+
+function parseTypes(source: string, types: Map<string, TypeDef>) {
+  for (const { doc, attrs, body } of items(source)) {
+    const derive = attrs.find((a) => a.startsWith("#[derive("));
+    const decl = /^pub(?:\(crate\))? (struct|enum|type) (\w+)/.exec(body.trim());
+    if (!decl) continue;
+    const name = decl[2];
+
+    if (decl[1] === "type") {
+      const alias = /=\s*(.+);/.exec(body);
+      if (alias) types.set(name, { name, doc, serde: {}, alias: alias[1].trim() });
+      continue;
+    }
+    if (!derive || !/Serialize|Deserialize/.test(derive)) continue;
+
+    const serde = parseSerde(attrs);
+    // Types that are also serialized always carry every field in responses
+    const deserialize = derive.includes("Deserialize") && !derive.includes("Serialize");
+    const inner = body.slice(body.indexOf("{") + 1, body.lastIndexOf("}"));
+
+    if (decl[1] === "struct") {
+      types.set(name, { name, doc, serde, fields: parseFields(inner, serde.rename_all, deserialize, serde.default !== undefined) });
+      continue;
+    }
+
+    // Enum variants: unit, struct-like, or single-field tuple
+    const variants: Variant[] = [];
+    let vdoc: string[] = [];
+    let vrename: string | null = null;
+    const lines = inner.split("\n");
+    for (let i = 0; i < lines.length; i++) {
+      const line = lines[i].trim();
+      if (line.startsWith("///")) {
+        vdoc.push(line.replace(/^\/\/\/ ?/, ""));
+        continue;
+      }
+      const rename = /^#\[serde\(.*\brename = "([^"]+)"/.exec(line);
+      if (rename) vrename = rename[1];
+      if (line.startsWith("#[") || line.startsWith("//") || line === "") continue;
+      const variant = /^(\w+)\s*([{(,]?)/.exec(line);
+      if (!variant) continue;
+      if (variant[2] === "{") {
+        let block = line.slice(line.indexOf("{") + 1);
+        while (!block.includes("}") && i + 1 < lines.length) block += "\n" + lines[++i];
+        block = block.slice(0, block.indexOf("}"));
+        // The last field may omit its trailing comma
+        const fieldBlock = block.includes("\n") ? block.replace(/([^,\s])\s*$/, "$1,") : block.split(",").map((f) => f.trim() + ",").join("\n");
+        variants.push({ name: variant[1], fields: parseFields(fieldBlock, undefined, deserialize), tuple: null, doc: vdoc, rename: vrename });
+      } else if (variant[2] === "(") {
+        const tuple = /\((.*)\)/.exec(line);
+        variants.push({ name: variant[1], fields: null, tuple: tuple ? tuple[1] : "unknown", doc: vdoc, rename: vrename });
+      } else {
+        variants.push({ name: variant[1], fields: null, tuple: null, doc: vdoc, rename: vrename });
+      }
+      vdoc = [];
+      vrename = null;
+    }
+    types.set(name, { name, doc, serde, variants });
+  }
+}
+
+function parseCommands(source: string, module: string, commands: Map<string, Command>) {
+  for (const { doc, attrs, body } of items(source)) {
+    if (!attrs.some((a) => a.startsWith("#[tauri::command"))) continue;
+    const signature = body.slice(0, body.indexOf("{"));
+    const decl = /fn (\w+)(?:<[^>]*>)?\s*\(([\s\S]*)\)\s*(?:->\s*([\s\S]+?))?\s*(?:where[\s\S]*)?$/.exec(signature.trim());
+    if (!decl) continue;
+
+    const params = splitTopLevel(decl[2])
+      .map((param) => /^(?:mut )?(\w+):\s*(.+)$/s.exec(param.trim()))
+      .filter((m): m is RegExpExecArray => m !== null)
+      .filter((m) => !INJECTED_PARAMS.has(m[2].replace(/^(?:tauri::)?(\w+).*$/s, "$1")))
+      .map((m) => ({ name: camelCase(m[1]), type: m[2].trim() }));
+
+    let result = decl[3]?.trim() ?? "()";
+    const okType = /^Result<([\s\S]+),\s*String>$/.exec(result);
+    if (okType) result = okType[1].trim();
+
+    commands.set(`${module}::${decl[1]}`, { module, name: decl[1], doc, params, result });
+  }
+}
+
+/** Map a Rust type to TypeScript, recording referenced named types */
+function tsType(rust: string, used: Set<string>): string {
+  const type = rust.trim().replace(/^&(?:'\w+ )?(?:mut )?/, "").replace(/\s+/g, " ");
+  if (type === "()") return "null";
+  if (/^\(.*\)$/.test(type)) {
+    return `[${splitTopLevel(type.slice(1, -1)).map((t) => tsType(t, used)).join(", ")}]`;
+  }
+  if (type.startsWith("[") && type.endsWith("]")) return `${tsType(type.slice(1, -1).split(";")[0], used)}[]`;
+
+  const generic = /^(?:[\w:]+::)?(\w+)<(.+)>$/.exec(type);
+  if (generic) {
+    const args = splitTopLevel(generic[2]);
+    switch (generic[1]) {
+      case "Option":
+        return `${tsType(args[0], used)} | null`;
+      case "Vec":
+      case "VecDeque":
+      case "HashSet":
+      case "BTreeSet": {
+        const inner = tsType(args[0], used);
+        return inner.includes("|") ? `(${inner})[]` : `${inner}[]`;
+      }
+      case "HashMap":
+      case "BTreeMap":
+        return `Record<${tsType(args[0], used)}, ${tsType(args[1], used)}>`;
+      case "Box":
+      case "Arc":
+      case "Cow":
+        return tsType(args[args.length - 1], used);
+      default:
+        return "unknown";
+    }
+  }
+
+  // Raw IPC responses arrive as bytes
+  if (/^(?:tauri::)?ipc::Response$/.test(type)) return "ArrayBuffer";
+
+  const name = type.split("::").pop() ?? type;
+  switch (name) {
+    case "String":
+    case "str":
+    case "PathBuf":
+    case "Path":
+    case "char":
+      return "string";
+    case "bool":
+      return "boolean";
+    case "u8":
+    case "u16":
+    case "u32":
+    case "u64":
+    case "usize":
+    case "i8":
+    case "i16":
+    case "i32":
+    case "i64":
+    case "isize":
+    case "f32":
+    case "f64":
+      return "number";
+    case "Value":
+    case "JsonValue":
+      return "unknown";
+    default:
+      used.add(name);
+      return name;
+  }
+}
+
+function docComment(doc: string[], indent = ""): string {
+  if (doc.length === 0) return "";
+  if (doc.length === 1) return `${indent}/** ${doc[0]} */\n`;
+  return `${indent}/**\n${doc.map((l) => `${indent} *${l ? " " + l : ""}`).join("\n")}\n${indent} */\n`;
+}
+
+function renderFields(fields: Field[], used: Set<string>, indent: string): string {
+  return fields
+    .map((f) => `${docComment(f.doc, indent)}${indent}${f.name}${f.optional ? "?" : ""}: ${tsType(f.type, used)};`)
+    .join("\n");
+}
+
+function renderType(def: TypeDef, used: Set<string>): string {
+  const header = docComment(def.doc);
+  if (def.alias !== undefined) return `${header}export type ${def.name} = ${tsType(def.alias, used)};`;
+  if (def.fields) {
+    if (def.serde.transparent && def.fields.length === 1) {
+      return `${header}export type ${def.name} = ${tsType(def.fields[0].type, used)};`;
+    }
+    return `${header}export interface ${def.name} {\n${renderFields(def.fields, used, "  ")}\n}`;
+  }
+
+  const variants = def.variants ?? [];
+  const tag = typeof def.serde.tag === "string" ? def.serde.tag : null;
+  const content = typeof def.serde.content === "string" ? def.serde.content : null;
+  const untagged = def.serde.untagged === true;
+  const rendered = variants.map((v) => {
+    const value = v.rename ?? renameCase(v.name, def.serde.rename_all);
+    if (v.fields === null && v.tuple === null) {
+      return untagged ? "null" : tag ? `{ ${tag}: "${value}" }` : `"${value}"`;
+    }
+    const body = v.fields
+      ? `{ ${v.fields.map((f) => `${f.name}${f.optional ? "?" : ""}: ${tsType(f.type, used)}`).join("; ")} }`
+      : tsType(v.tuple ?? "unknown", used);
+    if (untagged) return body;
+    if (tag && content) return `{ ${tag}: "${value}"; ${content}: ${body} }`;
+    if (tag && v.fields) return `{ ${tag}: "${value}"${v.fields.length ? "; " : ""}${body.slice(2, -2)} }`;
+    return `{ ${value}: ${body} }`;
+  });
+  return `${header}export type ${def.name} =\n${rendered.map((r) => `  | ${r}`).join("\n")};`;
+}
+
+function registeredCommands(): { module: string; name: string }[] {
+  const lib = fs.readFileSync(path.join(RUST_SRC, "lib.rs"), "utf8");
+  const handler = /generate_handler!\[([\s\S]*?)\]/.exec(lib);
+  if (!handler) throw new Error("generate_handler! not found in lib.rs");
+  return handler[1]
+    .split("\n")
+    .map((line) => line.replace(/\/\/.*$/, "").trim().replace(/,$/, ""))
+    .filter(Boolean)
+    .map((entry) => {
+      const [module, name] = entry.split("::");
+      return { module, name };
+    });
+}
+
+function generate(): string {
+  const types = new Map<string, TypeDef>();
+  const commands = new Map<string, Command>();
+  for (const file of fs.readdirSync(RUST_SRC).filter((f) => f.endsWith(".rs")).sort()) {
+    const source = fs.readFileSync(path.join(RUST_SRC, file), "utf8");
+    parseTypes(source, types);
+    parseCommands(source, file.replace(/\.rs$/, ""), commands);
+  }
+
+  const used = new Set<string>();
+  const wrappers: string[] = [];
+  for (const { module, name } of registeredCommands()) {
+    const command = commands.get(`${module}::${name}`);
+    if (!command) throw new Error(`Command ${module}::${name} not found in ${module}.rs`);
+    // Trailing `Option` arguments may be left out
+    let required = command.params.length;
+    while (required > 0 && command.params[required - 1].type.startsWith("Option<")) required--;
+    const params = command.params
+      .map((p, i) => `${p.name}${i >= required ? "?" : ""}: ${tsType(p.type, used)}`)
+      .join(", ");
+    const args = command.params.length ? `, { ${command.params.map((p) => p.name).join(", ")} }` : "";
+    wrappers.push(
+      `${docComment(command.doc, "  ")}  ${camelCase(name)}: (${params}): Promise<${tsType(command.result, used)}> =>\n` +
+        `    invoke("${name}"${args}),`,
+    );
+  }
+
+  // Emit referenced types and everything they reference in turn
+  const emitted = new Set<string>();
+  const rendered: string[] = [];
+  const pending = [...used];
+  while (pending.length) {
+    const name = pending.shift()!;
+    if (emitted.has(name)) continue;
+    emitted.add(name);
+    const def = types.get(name);
+    const nested = new Set<string>();
+    rendered.push(def ? renderType(def, nested) : `/** Not generated: ${name} has no serde definition in src-tauri */\nexport type ${name} = unknown;`);
+    pending.push(...nested);
+  }
+  rendered.sort((a, b) => /export (?:type|interface) (\w+)/.exec(a)![1].localeCompare(/export (?:type|interface) (\w+)/.exec(b)![1]));
+
+  return [
+    "// Generated by scripts/generate-bindings.ts from src-tauri/src. Do not edit.",
+    "",
+    'import { invoke } from "@tauri-apps/api/core";',
+    "",
+    "/** Commands reject with the message from their Rust `Err(String)` */",
+    "export type CommandError = string;",
+    "",
+    rendered.join("\n\n"),
+    "",
+    "export const commands = {",
+    wrappers.join("\n"),
+    "};",
+    "",
+  ].join("\n");
+}
+
+const output = generate();
+if (process.argv.includes("--check")) {
+  const current = fs.existsSync(OUTPUT) ? fs.readFileSync(OUTPUT, "utf8") : "";
+  if (current !== output) {
+    console.error("src/tauri-bindings.ts is out of date; run `bun run generate:bindings`");
+    process.exit(1);
+  }
+  console.log("Tauri bindings are up to date");
+} else {
+  fs.writeFileSync(OUTPUT, output);
+  console.log(`Wrote ${path.relative(PROJECT_ROOT, OUTPUT)}`);
+}
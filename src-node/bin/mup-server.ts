@@ -10,6 +10,7 @@
  * 4. Handles graceful shutdown
  */
 
+import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
 import { parseArgs } from "util";
@@ -21,10 +22,20 @@ import { VERSION } from "@/version";
 
 // Sentinel for port announcement (parsed by sidecar.rs)
 const PORT_ANNOUNCE_PREFIX = "MUX_SERVER_PORT:";
+// Sentinel for socket announcement when listening on a Unix socket or named pipe
+const SOCKET_ANNOUNCE_PREFIX = "MUX_SERVER_SOCKET:";
+// Printed while another server holds MUX_HOME (parsed by sidecar.rs)
+const WAITING_ANNOUNCE = "MUX_SERVER_WAITING";
+
+// Held for the server's lifetime so two servers never share MUX_HOME
+// (server.lock is the discovery lockfile written by ServerLockfile)
+const HOME_LOCK_NAME = "data.lock";
+const HOME_LOCK_POLL_MS = 100;
 
 interface ServerOptions {
   host: string;
   port: number;
+  socket?: string;
   muxHome?: string;
 }
 
@@ -41,6 +52,10 @@ function parseCommandLineArgs(): ServerOptions {
         short: "p",
         default: "0", // 0 = random available port
       },
+      socket: {
+        type: "string",
+        short: "s",
+      },
       "mux-home": {
         type: "string",
         short: "m",
@@ -53,6 +68,7 @@ function parseCommandLineArgs(): ServerOptions {
   return {
     host: values.host ?? "127.0.0.1",
     port: parseInt(values.port ?? "0", 10) || 0,
+    socket: values.socket,
     muxHome: values["mux-home"],
   };
 }
@@ -72,6 +88,79 @@ function getMuxHome(customPath?: string): string {
   return path.join(os.homedir(), ".mux");
 }
 
+function isAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (error) {
+    // EPERM means the process exists but belongs to someone else
+    return (error as NodeJS.ErrnoException).code === "EPERM";
+  }
+}
+
+function lockAgeMs(lockPath: string): number {
+  try {
+    return Date.now() - fs.statSync(lockPath).mtimeMs;
+  } catch {
+    return 0;
+  }
+}
+
+/**
+ * Take the MUX_HOME lock, waiting for the server that holds it to exit.
+ *
+ * During a blue/green restart the standby server starts while the old one
+ * is still serving; it announces that it is waiting so the app can drain
+ * and stop the old server, and only then opens the data directory.
+ */
+async function acquireHomeLock(muxHome: string): Promise<() => void> {
+  fs.mkdirSync(muxHome, { recursive: true });
+  const lockPath = path.join(muxHome, HOME_LOCK_NAME);
+  let announced = false;
+
+  for (;;) {
+    try {
+      fs.writeFileSync(lockPath, String(process.pid), { flag: "wx" });
+      break;
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code !== "EEXIST") {
+        throw error;
+      }
+    }
+
+    let holder: number;
+    try {
+      holder = parseInt(fs.readFileSync(lockPath, "utf8").trim(), 10);
+    } catch {
+      // Released between our attempt and the read
+      continue;
+    }
+    // An empty file may be a lock whose pid hasn't been written yet
+    const stale = Number.isFinite(holder) ? !isAlive(holder) : lockAgeMs(lockPath) > 1000;
+    if (stale) {
+      // Left behind by a server that crashed
+      fs.rmSync(lockPath, { force: true });
+      continue;
+    }
+
+    if (!announced) {
+      console.log(WAITING_ANNOUNCE);
+      announced = true;
+    }
+    await new Promise((resolve) => setTimeout(resolve, HOME_LOCK_POLL_MS));
+  }
+
+  return () => {
+    try {
+      if (fs.readFileSync(lockPath, "utf8").trim() === String(process.pid)) {
+        fs.rmSync(lockPath, { force: true });
+      }
+    } catch {
+      // Already gone
+    }
+  };
+}
+
 async function main(): Promise<void> {
   const options = parseCommandLineArgs();
   const muxHome = getMuxHome(options.muxHome);
@@ -79,6 +168,9 @@ async function main(): Promise<void> {
   log.info(`Mux Server v${VERSION.version}`);
   log.info(`MUX_HOME: ${muxHome}`);
 
+  const releaseHomeLock = await acquireHomeLock(muxHome);
+  process.on("exit", releaseHomeLock);
+
   // Initialize config
   const config = new Config(muxHome);
 
@@ -88,17 +180,24 @@ async function main(): Promise<void> {
   await container.initialize();
 
   // Create oRPC server
-  log.info(`Starting oRPC server on ${options.host}:${options.port}...`);
+  log.info(`Starting oRPC server on ${options.socket ?? `${options.host}:${options.port}`}...`);
   const server = await createOrpcServer({
     host: options.host,
     port: options.port,
+    socketPath: options.socket,
+    // Set by the Tauri app so only it can call the API
+    authToken: process.env.MUX_SERVER_AUTH_TOKEN,
     context: container.toORPCContext() as any,
     serveStatic: false, // Tauri serves frontend
   });
 
   // Announce port to stdout (parsed by sidecar.rs)
   // Must be the first non-log output on its own line
-  console.log(`${PORT_ANNOUNCE_PREFIX}${server.port}`);
+  if (options.socket) {
+    console.log(`${SOCKET_ANNOUNCE_PREFIX}${options.socket}`);
+  } else {
+    console.log(`${PORT_ANNOUNCE_PREFIX}${server.port}`);
+  }
 
   log.info(`Server listening at ${server.baseUrl}`);
   log.info(`WebSocket at ${server.wsUrl}`);
@@ -0,0 +1,114 @@
+import { describe, expect, test } from "bun:test";
+import express from "express";
+import * as http from "http";
+import type { AddressInfo } from "net";
+import * as zlib from "zlib";
+import { COMPRESSION_THRESHOLD_HEADER, responseCompression } from "./compression";
+
+const LARGE = { text: "x".repeat(64 * 1024) };
+
+async function startServer() {
+  const app = express();
+  app.use(responseCompression);
+  app.get("/large", (_req, res) => {
+    res.json(LARGE);
+  });
+  app.get("/small", (_req, res) => {
+    res.json({ ok: true });
+  });
+  app.get("/write-head", (_req, res) => {
+    res.writeHead(200, { "Content-Type": "application/json" });
+    res.end(JSON.stringify(LARGE));
+  });
+  app.get("/stream", (_req, res) => {
+    res.setHeader("Content-Type", "text/event-stream");
+    res.write("data: 1\n\n");
+    res.end();
+  });
+
+  const server = app.listen(0, "127.0.0.1");
+  await new Promise<void>((resolve) => server.once("listening", () => resolve()));
+  const { port } = server.address() as AddressInfo;
+  return {
+    port,
+    close: () => new Promise<void>((resolve) => server.close(() => resolve())),
+  };
+}
+
+/** Raw GET, so the body isn't decompressed for us */
+function get(
+  port: number,
+  path: string,
+  headers: http.OutgoingHttpHeaders
+): Promise<{ headers: http.IncomingHttpHeaders; body: Buffer }> {
+  return new Promise((resolve, reject) => {
+    const req = http.get({ host: "127.0.0.1", port, path, headers }, (res) => {
+      const chunks: Buffer[] = [];
+      res.on("data", (chunk: Buffer) => chunks.push(chunk));
+      res.on("end", () => resolve({ headers: res.headers, body: Buffer.concat(chunks) }));
+      res.on("error", reject);
+    });
+    req.on("error", reject);
+  });
+}
+
+describe("responseCompression", () => {
+  test("compresses large JSON with the preferred encoding", async () => {
+    const server = await startServer();
+    try {
+      const br = await get(server.port, "/large", { "Accept-Encoding": "br, gzip" });
+      expect(br.headers["content-encoding"]).toBe("br");
+      expect(JSON.parse(zlib.brotliDecompressSync(br.body).toString("utf8"))).toEqual(LARGE);
+
+      const gzip = await get(server.port, "/large", { "Accept-Encoding": "gzip" });
+      expect(gzip.headers["content-encoding"]).toBe("gzip");
+      expect(Number(gzip.headers["content-length"])).toBe(gzip.body.byteLength);
+      expect(JSON.parse(zlib.gunzipSync(gzip.body).toString("utf8"))).toEqual(LARGE);
+    } finally {
+      await server.close();
+    }
+  });
+
+  test("leaves bodies under the threshold uncompressed", async () => {
+    const server = await startServer();
+    try {
+      const small = await get(server.port, "/small", { "Accept-Encoding": "gzip" });
+      expect(small.headers["content-encoding"]).toBeUndefined();
+      expect(JSON.parse(small.body.toString("utf8"))).toEqual({ ok: true });
+
+      const raised = await get(server.port, "/large", {
+        "Accept-Encoding": "gzip",
+        [COMPRESSION_THRESHOLD_HEADER]: String(1024 * 1024),
+      });
+      expect(raised.headers["content-encoding"]).toBeUndefined();
+    } finally {
+      await server.close();
+    }
+  });
+
+  test("compresses responses whose headers were set with writeHead", async () => {
+    const server = await startServer();
+    try {
+      const res = await get(server.port, "/write-head", { "Accept-Encoding": "gzip" });
+      expect(res.headers["content-encoding"]).toBe("gzip");
+      expect(JSON.parse(zlib.gunzipSync(res.body).toString("utf8"))).toEqual(LARGE);
+    } finally {
+      await server.close();
+    }
+  });
+
+  test("passes streams and clients without compression through", async () => {
+    const server = await startServer();
+    try {
+      const stream = await get(server.port, "/stream", { "Accept-Encoding": "gzip" });
+      expect(stream.headers["content-encoding"]).toBeUndefined();
+      expect(stream.body.toString("utf8")).toBe("data: 1\n\n");
+
+      const identity = await get(server.port, "/large", { "Accept-Encoding": "identity" });
+      expect(identity.headers["content-encoding"]).toBeUndefined();
+      expect(JSON.parse(identity.body.toString("utf8"))).toEqual(LARGE);
+    } finally {
+      await server.close();
+    }
+  });
+});
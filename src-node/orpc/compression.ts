@@ -0,0 +1,116 @@
+import * as zlib from "zlib";
+import type express from "express";
+
+/** Header the desktop bridge uses to set the smallest body worth compressing. */
+export const COMPRESSION_THRESHOLD_HEADER = "x-compression-threshold";
+
+/** Used when the client doesn't send a threshold. */
+const DEFAULT_THRESHOLD_BYTES = 32 * 1024;
+
+/** Buffered (non-streaming) response types that are worth compressing. */
+const COMPRESSIBLE_TYPES = ["application/json", "application/msgpack"];
+
+function toBuffer(chunk: unknown, encoding: unknown): Buffer {
+  if (Buffer.isBuffer(chunk)) return chunk;
+  if (chunk instanceof Uint8Array) return Buffer.from(chunk);
+  return Buffer.from(String(chunk), typeof encoding === "string" ? (encoding as BufferEncoding) : "utf8");
+}
+
+function compress(body: Buffer, encoding: "br" | "gzip"): Promise<Buffer> {
+  return new Promise((resolve, reject) => {
+    const done = (error: Error | null, result: Buffer) => (error ? reject(error) : resolve(result));
+    if (encoding === "br") {
+      // Low quality keeps large transcripts fast to encode on the loopback
+      zlib.brotliCompress(body, { params: { [zlib.constants.BROTLI_PARAM_QUALITY]: 4 } }, done);
+    } else {
+      zlib.gzip(body, { level: zlib.constants.Z_BEST_SPEED }, done);
+    }
+  });
+}
+
+/**
+ * Response compression for the desktop bridge.
+ *
+ * JSON and MessagePack responses at or above the threshold are compressed
+ * with brotli or gzip, whichever the client prefers. Streaming responses pass
+ * through untouched, and so does anything already encoded. Compressed request
+ * bodies are inflated by the body parsers.
+ */
+export function responseCompression(
+  req: express.Request,
+  res: express.Response,
+  next: express.NextFunction
+): void {
+  res.vary("Accept-Encoding");
+  const encoding = req.acceptsEncodings(["br", "gzip", "identity"]);
+  if (encoding !== "br" && encoding !== "gzip") {
+    next();
+    return;
+  }
+
+  const requested = Number(req.headers[COMPRESSION_THRESHOLD_HEADER]);
+  const threshold = Number.isFinite(requested) && requested >= 0 ? requested : DEFAULT_THRESHOLD_BYTES;
+
+  // Handlers that call writeHead (the oRPC node adapter does) would lock the
+  // headers before the body is seen; until the body is sent, record them as
+  // ordinary headers instead so Content-Encoding can still be set
+  const originalWriteHead = res.writeHead.bind(res) as (...args: unknown[]) => express.Response;
+  let deferHead = true;
+  res.writeHead = ((statusCode: number, ...args: unknown[]) => {
+    if (!deferHead) return originalWriteHead(statusCode, ...args);
+    res.statusCode = statusCode;
+    const headers = args.find((arg) => typeof arg === "object" && arg !== null);
+    if (headers && !Array.isArray(headers)) {
+      for (const [name, value] of Object.entries(headers as Record<string, unknown>)) {
+        if (value !== undefined) res.setHeader(name, value as string | number | readonly string[]);
+      }
+    }
+    return res;
+  }) as typeof res.writeHead;
+
+  const originalWrite = res.write.bind(res) as (chunk: unknown, ...args: unknown[]) => boolean;
+  const originalEnd = res.end.bind(res) as (...args: unknown[]) => express.Response;
+  const chunks: Buffer[] = [];
+  const compressible = () => {
+    const type = String(res.getHeader("content-type") ?? "");
+    return !res.getHeader("content-encoding") && COMPRESSIBLE_TYPES.some((t) => type.startsWith(t));
+  };
+
+  res.write = ((chunk: unknown, ...args: unknown[]) => {
+    if (!compressible()) {
+      deferHead = false;
+      return originalWrite(chunk, ...args);
+    }
+    chunks.push(toBuffer(chunk, args[0]));
+    return true;
+  }) as typeof res.write;
+
+  res.end = ((chunk?: unknown, ...args: unknown[]) => {
+    deferHead = false;
+    if (!compressible()) return originalEnd(chunk, ...args);
+    if (chunk !== undefined && typeof chunk !== "function") {
+      chunks.push(toBuffer(chunk, args[0]));
+    }
+
+    const body = Buffer.concat(chunks);
+    if (body.byteLength < threshold) {
+      res.setHeader("Content-Length", body.byteLength);
+      return originalEnd(body);
+    }
+
+    compress(body, encoding).then(
+      (compressed) => {
+        res.setHeader("Content-Encoding", encoding);
+        res.setHeader("Content-Length", compressed.byteLength);
+        originalEnd(compressed);
+      },
+      () => {
+        res.setHeader("Content-Length", body.byteLength);
+        originalEnd(body);
+      }
+    );
+    return res;
+  }) as typeof res.end;
+
+  next();
+}
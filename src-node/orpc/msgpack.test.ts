@@ -0,0 +1,103 @@
+import { describe, expect, test } from "bun:test";
+import { decode, encode } from "@msgpack/msgpack";
+import { os } from "@orpc/server";
+import { RPCHandler } from "@orpc/server/node";
+import express from "express";
+import type { AddressInfo } from "net";
+import {
+  MSGPACK_CONTENT_TYPE,
+  MSGPACK_RESPONSE_HEADER,
+  encodeMsgpackResponse,
+  msgpackNegotiation,
+} from "./msgpack";
+
+const router = {
+  echo: os.handler(({ input }) => ({ echoed: input })),
+};
+
+async function startServer() {
+  const handler = new RPCHandler(router, { rootInterceptors: [encodeMsgpackResponse] });
+  const app = express();
+  app.use("/orpc", express.raw({ type: MSGPACK_CONTENT_TYPE }), msgpackNegotiation);
+  app.use("/orpc", async (req, res, next) => {
+    const { matched } = await handler.handle(req, res, { prefix: "/orpc", context: {} });
+    if (matched) return;
+    next();
+  });
+
+  const server = app.listen(0, "127.0.0.1");
+  await new Promise<void>((resolve) => server.once("listening", () => resolve()));
+  const { port } = server.address() as AddressInfo;
+  return {
+    url: `http://127.0.0.1:${port}/orpc/echo`,
+    close: () => new Promise<void>((resolve) => server.close(() => resolve())),
+  };
+}
+
+describe("msgpackNegotiation", () => {
+  test("leaves JSON requests and responses alone", async () => {
+    const server = await startServer();
+    try {
+      const res = await fetch(server.url, {
+        method: "POST",
+        headers: { "Content-Type": "application/json", Accept: "application/json" },
+        body: JSON.stringify({ json: { value: 1 } }),
+      });
+      expect(res.status).toBe(200);
+      expect(res.headers.get("content-type")).toStartWith("application/json");
+      const body = (await res.json()) as { json: unknown };
+      expect(body.json).toEqual({ echoed: { value: 1 } });
+    } finally {
+      await server.close();
+    }
+  });
+
+  test("decodes MessagePack requests and encodes the response object", async () => {
+    const server = await startServer();
+    try {
+      const res = await fetch(server.url, {
+        method: "POST",
+        headers: { "Content-Type": MSGPACK_CONTENT_TYPE, Accept: MSGPACK_CONTENT_TYPE },
+        body: encode({ json: { text: "héllo", items: [1, 2, 3] } }),
+      });
+      expect(res.status).toBe(200);
+      expect(res.headers.get("content-type")).toStartWith(MSGPACK_CONTENT_TYPE);
+      const body = decode(new Uint8Array(await res.arrayBuffer())) as { json: unknown };
+      expect(body.json).toEqual({ echoed: { text: "héllo", items: [1, 2, 3] } });
+    } finally {
+      await server.close();
+    }
+  });
+
+  test("rejects an invalid MessagePack body", async () => {
+    const server = await startServer();
+    try {
+      const res = await fetch(server.url, {
+        method: "POST",
+        headers: { "Content-Type": MSGPACK_CONTENT_TYPE },
+        body: new Uint8Array([0xc1]),
+      });
+      expect(res.status).toBe(400);
+    } finally {
+      await server.close();
+    }
+  });
+
+  test("ignores a client-supplied MessagePack mark", async () => {
+    const server = await startServer();
+    try {
+      const res = await fetch(server.url, {
+        method: "POST",
+        headers: {
+          "Content-Type": "application/json",
+          Accept: "application/json",
+          [MSGPACK_RESPONSE_HEADER]: "1",
+        },
+        body: JSON.stringify({ json: { value: 2 } }),
+      });
+      expect(res.headers.get("content-type")).toStartWith("application/json");
+    } finally {
+      await server.close();
+    }
+  });
+});
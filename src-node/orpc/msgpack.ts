@@ -0,0 +1,73 @@
+import { decode, encode } from "@msgpack/msgpack";
+import type express from "express";
+
+export const MSGPACK_CONTENT_TYPE = "application/msgpack";
+
+/** Set by `msgpackNegotiation` on requests whose response should be MessagePack. */
+export const MSGPACK_RESPONSE_HEADER = "x-mux-msgpack-response";
+
+/**
+ * MessagePack negotiation for the desktop bridge.
+ *
+ * Request bodies sent as `application/msgpack` are decoded so the oRPC handler
+ * sees the same value a JSON body would produce. When the client prefers
+ * MessagePack in `Accept`, the request is marked for `encodeMsgpackResponse`,
+ * which encodes the handler's response object without going through JSON.
+ */
+export function msgpackNegotiation(
+  req: express.Request,
+  res: express.Response,
+  next: express.NextFunction
+): void {
+  if (req.is(MSGPACK_CONTENT_TYPE) && Buffer.isBuffer(req.body)) {
+    try {
+      req.body = decode(req.body);
+    } catch {
+      res.status(400).json({ error: "Invalid MessagePack body" });
+      return;
+    }
+    req.headers["content-type"] = "application/json";
+  }
+
+  res.vary("Accept");
+  // Only this middleware decides; a client can't set the mark itself
+  delete req.headers[MSGPACK_RESPONSE_HEADER];
+  if (req.accepts(["application/json", MSGPACK_CONTENT_TYPE]) === MSGPACK_CONTENT_TYPE) {
+    req.headers[MSGPACK_RESPONSE_HEADER] = "1";
+  }
+  next();
+}
+
+/** Whether a response body is a plain value (not a stream, file or form). */
+function isPlainBody(body: unknown): body is object {
+  if (typeof body !== "object" || body === null) return false;
+  if (body instanceof Blob || body instanceof FormData || body instanceof URLSearchParams) {
+    return false;
+  }
+  return !(Symbol.asyncIterator in body);
+}
+
+/**
+ * oRPC root interceptor that sends a marked request's response as MessagePack.
+ *
+ * The serialized output object is encoded directly; streaming, file and form
+ * responses are left to the handler.
+ */
+export async function encodeMsgpackResponse<
+  T extends { matched: boolean; response?: { body?: unknown } },
+>(options: { request: { headers: Record<string, unknown> }; next: () => Promise<T> }): Promise<T> {
+  const result = await options.next();
+  if (!options.request.headers[MSGPACK_RESPONSE_HEADER] || !result.response) return result;
+
+  const body = result.response.body;
+  if (!isPlainBody(body)) return result;
+
+  const encoded = encode(body);
+  return {
+    ...result,
+    response: {
+      ...result.response,
+      body: new Blob([encoded], { type: MSGPACK_CONTENT_TYPE }),
+    },
+  };
+}
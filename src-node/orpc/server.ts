@@ -22,7 +22,14 @@ import type { ORPCContext } from "@/node/orpc/context";
 import { extractWsHeaders, safeEq } from "@/node/orpc/authMiddleware";
 import { VERSION } from "@/version";
 import { formatOrpcError } from "@/node/orpc/formatOrpcError";
+import { responseCompression } from "@/node/orpc/compression";
+import {
+  MSGPACK_CONTENT_TYPE,
+  encodeMsgpackResponse,
+  msgpackNegotiation,
+} from "@/node/orpc/msgpack";
 import { log } from "@/node/services/log";
+import { onPushEvent } from "@/node/services/pushEvents";
 import { attachStreamErrorHandler, isIgnorableStreamError } from "@/node/utils/streamErrors";
 
 type AliveWebSocket = WebSocket & { isAlive?: boolean };
@@ -36,6 +43,8 @@ export interface OrpcServerOptions {
   host?: string;
   /** Port to bind to (default: 0 for random available port) */
   port?: number;
+  /** Unix socket (or Windows named pipe) path to listen on instead of host:port */
+  socketPath?: string;
   /** oRPC context with services */
   context: ORPCContext;
   /** Whether to serve static files and SPA fallback (default: false) */
@@ -129,6 +138,7 @@ function escapeHtml(input: string): string {
 export async function createOrpcServer({
   host = "127.0.0.1",
   port = 0,
+  socketPath,
   authToken,
   context,
   serveStatic = false,
@@ -200,6 +210,27 @@ export async function createOrpcServer({
     res.json({ ...VERSION, mode: "server" });
   });
 
+  // Runtime log level control (used by the desktop shell)
+  app.post("/control/log-level", (req, res) => {
+    if (authToken?.trim()) {
+      const presentedToken = extractBearerToken(req.header("authorization"));
+      if (!presentedToken || !safeEq(presentedToken, authToken.trim())) {
+        res.status(401).json({ error: "Invalid or missing auth token" });
+        return;
+      }
+    }
+
+    const level = (req.body as { level?: unknown } | undefined)?.level;
+    if (level !== "error" && level !== "warn" && level !== "info" && level !== "debug") {
+      res.status(400).json({ error: "Invalid log level" });
+      return;
+    }
+
+    log.setLevel(level);
+    log.info(`Log level set to ${level}`);
+    res.json({ level });
+  });
+
   function getStringParamFromQueryOrBody(req: express.Request, key: string): string | null {
     const queryValue = req.query[key];
     if (typeof queryValue === "string") return queryValue;
@@ -725,8 +756,16 @@ export async function createOrpcServer({
   // oRPC HTTP handler
   const orpcHandler = new RPCHandler(orpcRouter, {
     interceptors: [onError(onOrpcError)],
+    rootInterceptors: [encodeMsgpackResponse],
   });
 
+  // Compressed responses for clients that accept them (request bodies are
+  // inflated by the body parsers)
+  app.use("/orpc", responseCompression);
+
+  // MessagePack bodies and responses for clients that ask for them
+  app.use("/orpc", express.raw({ type: MSGPACK_CONTENT_TYPE, limit: "50mb" }), msgpackNegotiation);
+
   // Mount ORPC handler on /orpc and all subpaths
   app.use("/orpc", async (req, res, next) => {
     const { matched } = await orpcHandler.handle(req, res, {
@@ -786,14 +825,46 @@ export async function createOrpcServer({
   });
 
   // oRPC WebSocket handler
-  const wsServer = new WebSocketServer({ server: httpServer, path: "/orpc/ws" });
+  const wsServer = new WebSocketServer({ noServer: true });
 
   attachStreamErrorHandler(wsServer, "orpc-ws-server", { logger: log });
 
+  // Push events for the desktop shell (see services/pushEvents)
+  const eventsWsServer = new WebSocketServer({ noServer: true });
+
+  attachStreamErrorHandler(eventsWsServer, "events-ws-server", { logger: log });
+
+  // Both servers share the HTTP server, so upgrades are routed by path here
+  // rather than letting each server reject the other's requests.
+  httpServer.on("upgrade", (req, socket, head) => {
+    const pathname = new URL(req.url ?? "/", "http://localhost").pathname;
+
+    if (pathname === "/orpc/ws") {
+      wsServer.handleUpgrade(req, socket, head, (ws) => wsServer.emit("connection", ws, req));
+      return;
+    }
+
+    if (pathname === "/events") {
+      if (authToken?.trim()) {
+        const presentedToken = extractBearerToken(req.headers.authorization);
+        if (!presentedToken || !safeEq(presentedToken, authToken.trim())) {
+          socket.end("HTTP/1.1 401 Unauthorized\r\n\r\n");
+          return;
+        }
+      }
+      eventsWsServer.handleUpgrade(req, socket, head, (ws) =>
+        eventsWsServer.emit("connection", ws, req)
+      );
+      return;
+    }
+
+    socket.destroy();
+  });
+
   // WebSocket heartbeat: proactively terminate half-open connections (common with NAT/proxy setups).
   // When a client is unresponsive, closing the socket forces the browser to reconnect.
   const heartbeatInterval = setInterval(() => {
-    for (const ws of wsServer.clients) {
+    for (const ws of [...wsServer.clients, ...eventsWsServer.clients]) {
       const socket = ws as AliveWebSocket;
       if (socket.isAlive === false) {
         ws.terminate();
@@ -837,6 +908,37 @@ export async function createOrpcServer({
     void orpcWsHandler.upgrade(ws, { context: { ...context, headers } });
   });
 
+  eventsWsServer.on("connection", (ws) => {
+    const terminate = () => {
+      try {
+        ws.terminate();
+      } catch {
+        // Best-effort.
+      }
+    };
+
+    attachStreamErrorHandler(ws, "events-ws-connection", {
+      logger: log,
+      onIgnorable: terminate,
+      onUnexpected: terminate,
+    });
+    const socket = ws as AliveWebSocket;
+    socket.isAlive = true;
+    ws.on("pong", () => {
+      socket.isAlive = true;
+    });
+
+    const unsubscribe = onPushEvent((event) => {
+      if (ws.readyState !== ws.OPEN) return;
+      try {
+        ws.send(JSON.stringify(event));
+      } catch (error) {
+        log.warn("Failed to send push event", { topic: event.topic, error });
+      }
+    });
+    ws.on("close", unsubscribe);
+  });
+
   // Start listening
   await new Promise<void>((resolve, reject) => {
     const onListenError = (error: Error) => {
@@ -845,21 +947,30 @@ export async function createOrpcServer({
     };
 
     httpServer.once("error", onListenError);
-    httpServer.listen(port, host, () => {
+    const onListening = () => {
       httpServer.removeListener("error", onListenError);
       resolve();
-    });
+    };
+    if (socketPath) {
+      httpServer.listen(socketPath, onListening);
+    } else {
+      httpServer.listen(port, host, onListening);
+    }
   });
 
-  // Get actual port (useful when port=0)
+  // Get actual port (useful when port=0); socket servers have no port
   const address = httpServer.address();
-  if (!address || typeof address === "string") {
+  if (!address || (typeof address === "string" && !socketPath)) {
     throw new Error("Failed to get server address");
   }
-  const actualPort = address.port;
+  const actualPort = typeof address === "string" ? 0 : address.port;
 
   // Wildcard addresses (0.0.0.0, ::) are not routable - convert to loopback for lockfile
-  const connectableHost = host === "0.0.0.0" || host === "::" ? "127.0.0.1" : host;
+  const connectableHost = socketPath
+    ? "localhost"
+    : host === "0.0.0.0" || host === "::"
+      ? "127.0.0.1"
+      : host;
   const connectableHostForUrl = formatHostForUrl(connectableHost);
 
   return {
@@ -873,14 +984,17 @@ export async function createOrpcServer({
     docsUrl: `http://${connectableHostForUrl}:${actualPort}/api/docs`,
     close: async () => {
       clearInterval(heartbeatInterval);
-      for (const ws of wsServer.clients) {
+      for (const ws of [...wsServer.clients, ...eventsWsServer.clients]) {
         ws.terminate();
       }
 
-      // Close WebSocket server first
+      // Close WebSocket servers first
       await new Promise<void>((resolve) => {
         wsServer.close(() => resolve());
       });
+      await new Promise<void>((resolve) => {
+        eventsWsServer.close(() => resolve());
+      });
       // Then close HTTP server
       httpServer.closeIdleConnections?.();
       httpServer.closeAllConnections?.();
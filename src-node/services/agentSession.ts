@@ -61,6 +61,7 @@ import { isExecLikeEditingCapableInResolvedChain } from "@/common/utils/agentToo
 import { readAgentDefinition } from "@/node/services/agentDefinitions/agentDefinitionsService";
 import { resolveAgentInheritanceChain } from "@/node/services/agentDefinitions/resolveAgentInheritanceChain";
 import { MessageQueue } from "./messageQueue";
+import { publishChatActivity } from "./chatPushEvents";
 import type { StreamEndEvent } from "@/common/types/stream";
 import { CompactionHandler } from "./compactionHandler";
 import type { TelemetryService } from "./telemetryService";
@@ -1804,6 +1805,8 @@ export class AgentSession {
       workspaceId: this.workspaceId,
       message,
     } satisfies AgentSessionChatEvent);
+    // Desktop shells show agent activity even while the chat isn't open
+    publishChatActivity(this.workspaceId, message);
   }
 
   isStreamStarting(): boolean {
@@ -0,0 +1,88 @@
+import { describe, test, expect, beforeEach, afterEach } from "bun:test";
+import type { WorkspaceChatMessage } from "@/common/orpc/types";
+import {
+  activityToastId,
+  CHAT_ACTIVITY_TOPIC,
+  OVERLAY_TOAST_DISMISS_TOPIC,
+  OVERLAY_TOAST_TOPIC,
+  publishChatActivity,
+} from "./chatPushEvents";
+import { onPushEvent, type PushEvent } from "./pushEvents";
+
+const WORKSPACE_ID = "ws-1";
+
+function streamStart(replay?: boolean): WorkspaceChatMessage {
+  return {
+    type: "stream-start",
+    workspaceId: WORKSPACE_ID,
+    messageId: "msg-1",
+    model: "anthropic:claude-sonnet-4-5",
+    historySequence: 1,
+    startTime: Date.now(),
+    replay,
+  } as unknown as WorkspaceChatMessage;
+}
+
+describe("publishChatActivity", () => {
+  let events: PushEvent[];
+  let unsubscribe: () => void;
+
+  beforeEach(() => {
+    events = [];
+    unsubscribe = onPushEvent((event) => events.push(event));
+  });
+
+  afterEach(() => {
+    unsubscribe();
+  });
+
+  test("stream-start publishes activity and an overlay toast", () => {
+    publishChatActivity(WORKSPACE_ID, streamStart());
+
+    expect(events.map((event) => event.topic)).toEqual([CHAT_ACTIVITY_TOPIC, OVERLAY_TOAST_TOPIC]);
+    expect(events[0].payload).toEqual({ workspaceId: WORKSPACE_ID, streaming: true });
+    expect(events[1].payload).toMatchObject({
+      id: activityToastId(WORKSPACE_ID),
+      title: "Agent is working",
+    });
+  });
+
+  test("replayed stream-start publishes nothing", () => {
+    publishChatActivity(WORKSPACE_ID, streamStart(true));
+
+    expect(events).toEqual([]);
+  });
+
+  test("stream-end and stream-abort dismiss the toast", () => {
+    for (const type of ["stream-end", "stream-abort"] as const) {
+      events = [];
+      publishChatActivity(WORKSPACE_ID, {
+        type,
+        workspaceId: WORKSPACE_ID,
+        messageId: "msg-1",
+        metadata: { model: "anthropic:claude-sonnet-4-5" },
+        parts: [],
+      } as unknown as WorkspaceChatMessage);
+
+      expect(events.map((event) => event.topic)).toEqual([
+        CHAT_ACTIVITY_TOPIC,
+        OVERLAY_TOAST_DISMISS_TOPIC,
+      ]);
+      expect(events[0].payload).toEqual({ workspaceId: WORKSPACE_ID, streaming: false });
+      expect(events[1].payload).toEqual({ id: activityToastId(WORKSPACE_ID) });
+    }
+  });
+
+  test("other chat events publish nothing", () => {
+    publishChatActivity(WORKSPACE_ID, {
+      type: "stream-delta",
+      workspaceId: WORKSPACE_ID,
+      messageId: "msg-1",
+      delta: "hi",
+      tokens: 1,
+      timestamp: Date.now(),
+    } as unknown as WorkspaceChatMessage);
+
+    expect(events).toEqual([]);
+  });
+});
@@ -0,0 +1,62 @@
+import type { WorkspaceChatMessage } from "@/common/orpc/types";
+import { isStreamAbort, isStreamEnd, isStreamError, isStreamStart } from "@/common/orpc/types";
+import { publishPushEvent } from "./pushEvents";
+
+/** Topic announcing when a workspace's agent starts and stops streaming. */
+export const CHAT_ACTIVITY_TOPIC = "chat-activity";
+
+/** Topics the desktop shell's activity overlay listens to. */
+export const OVERLAY_TOAST_TOPIC = "overlay-toast";
+export const OVERLAY_TOAST_DISMISS_TOPIC = "overlay-toast-dismiss";
+
+/**
+ * How long an activity toast may stay up without being dismissed. Streams
+ * dismiss their toast when they finish; this only bounds a lost dismissal.
+ */
+const ACTIVITY_TOAST_TIMEOUT_MS = 30 * 60 * 1000;
+
+export interface ChatActivityEvent {
+  workspaceId: string;
+  streaming: boolean;
+}
+
+/** Overlay toast id for a workspace's agent activity. */
+export function activityToastId(workspaceId: string): string {
+  return `chat:${workspaceId}`;
+}
+
+/**
+ * Publish push events for a chat event: connected shells learn when a
+ * workspace's agent starts and stops streaming, and show a progress toast
+ * while it is working.
+ */
+export function publishChatActivity(workspaceId: string, message: WorkspaceChatMessage): void {
+  const id = activityToastId(workspaceId);
+
+  // Replays re-send a running stream's start to a new subscriber
+  if (isStreamStart(message)) {
+    if (message.replay) {
+      return;
+    }
+    publishPushEvent(CHAT_ACTIVITY_TOPIC, {
+      workspaceId,
+      streaming: true,
+    } satisfies ChatActivityEvent);
+    publishPushEvent(OVERLAY_TOAST_TOPIC, {
+      id,
+      title: "Agent is working",
+      message: message.model,
+      progress: null,
+      timeout_ms: ACTIVITY_TOAST_TIMEOUT_MS,
+    });
+    return;
+  }
+
+  if (isStreamEnd(message) || isStreamAbort(message) || isStreamError(message)) {
+    publishPushEvent(CHAT_ACTIVITY_TOPIC, {
+      workspaceId,
+      streaming: false,
+    } satisfies ChatActivityEvent);
+    publishPushEvent(OVERLAY_TOAST_DISMISS_TOPIC, { id });
+  }
+}
@@ -1,5 +1,5 @@
 import { describe, expect, test, beforeEach, afterEach, mock } from "bun:test";
-import { ExperimentsService } from "./experimentsService";
+import { ExperimentsService, parseForcedExperiments } from "./experimentsService";
 import { EXPERIMENT_IDS } from "@/common/constants/experiments";
 import type { TelemetryService } from "./telemetryService";
 import type { PostHog } from "posthog-node";
@@ -121,4 +121,36 @@ describe("ExperimentsService", () => {
 
     expect(service.isExperimentEnabled(EXPERIMENT_IDS.SYSTEM_1)).toBe(false);
   });
+
+  test("forces experiments listed in MUX_FEATURE_FLAGS on, even with telemetry disabled", async () => {
+    const telemetryService = {
+      getPostHogClient: mock(() => null),
+      getDistinctId: mock(() => null),
+      setFeatureFlagVariant: mock(() => undefined),
+    } as unknown as TelemetryService;
+
+    const service = new ExperimentsService({
+      telemetryService,
+      muxHome: tempDir,
+      forcedExperiments: [EXPERIMENT_IDS.SYSTEM_1],
+    });
+    await service.initialize();
+
+    expect(service.getExperimentValue(EXPERIMENT_IDS.SYSTEM_1)).toEqual({
+      value: true,
+      source: "env",
+    });
+    expect(service.isExperimentEnabled(EXPERIMENT_IDS.SYSTEM_1)).toBe(true);
+    expect(service.isExperimentEnabled(EXPERIMENT_IDS.MUX_GOVERNOR)).toBe(false);
+  });
+
+  test("parses MUX_FEATURE_FLAGS, skipping blanks and unknown experiments", () => {
+    const forced = parseForcedExperiments(
+      ` ${EXPERIMENT_IDS.SYSTEM_1}, ,not-an-experiment,${EXPERIMENT_IDS.MUX_GOVERNOR}`
+    );
+    expect([...forced].sort()).toEqual(
+      [EXPERIMENT_IDS.MUX_GOVERNOR, EXPERIMENT_IDS.SYSTEM_1].sort()
+    );
+    expect(parseForcedExperiments(undefined).size).toBe(0);
+  });
 });
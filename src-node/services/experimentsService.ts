@@ -30,6 +30,24 @@ function isRecord(value: unknown): value is Record<string, unknown> {
   return typeof value === "object" && value !== null;
 }
 
+/**
+ * Experiments forced on by the desktop app's sidecar settings, passed as a
+ * comma-separated list in MUX_FEATURE_FLAGS.
+ */
+export function parseForcedExperiments(raw: string | undefined): Set<ExperimentId> {
+  const forced = new Set<ExperimentId>();
+  for (const flag of (raw ?? "").split(",")) {
+    const id = flag.trim();
+    if (!id) continue;
+    if (id in EXPERIMENTS) {
+      forced.add(id as ExperimentId);
+    } else {
+      log.warn("Ignoring unknown experiment in MUX_FEATURE_FLAGS", { id });
+    }
+  }
+  return forced;
+}
+
 /**
  * Backend experiments service.
  *
@@ -40,12 +58,14 @@ function isRecord(value: unknown): value is Record<string, unknown> {
  * - Never block user flows on network calls (use cached values and refresh in background)
  * - Fail closed (unknown = control/disabled)
  * - Avoid calling PostHog when telemetry is disabled
+ * - Experiments listed in MUX_FEATURE_FLAGS are on regardless of PostHog
  */
 export class ExperimentsService {
   private readonly telemetryService: TelemetryService;
   private readonly muxHome: string;
   private readonly cacheFilePath: string;
   private readonly cacheTtlMs: number;
+  private readonly forced: Set<ExperimentId>;
 
   private readonly cachedVariants = new Map<ExperimentId, CachedVariant>();
   private readonly refreshInFlight = new Map<ExperimentId, Promise<void>>();
@@ -56,11 +76,16 @@ export class ExperimentsService {
     telemetryService: TelemetryService;
     muxHome?: string;
     cacheTtlMs?: number;
+    /** Defaults to the experiments listed in MUX_FEATURE_FLAGS */
+    forcedExperiments?: ExperimentId[];
   }) {
     this.telemetryService = options.telemetryService;
     this.muxHome = options.muxHome ?? getMuxHome();
     this.cacheFilePath = path.join(this.muxHome, CACHE_FILE_NAME);
     this.cacheTtlMs = options.cacheTtlMs ?? DEFAULT_CACHE_TTL_MS;
+    this.forced = options.forcedExperiments
+      ? new Set(options.forcedExperiments)
+      : parseForcedExperiments(process.env.MUX_FEATURE_FLAGS);
   }
 
   async initialize(): Promise<void> {
@@ -110,6 +135,10 @@ export class ExperimentsService {
   getExperimentValue(experimentId: ExperimentId): ExperimentValue {
     assert(experimentId in EXPERIMENTS, `Unknown experimentId: ${experimentId}`);
 
+    if (this.forced.has(experimentId)) {
+      return { value: true, source: "env" };
+    }
+
     if (!this.isRemoteEvaluationEnabled()) {
       return { value: null, source: "disabled" };
     }
@@ -0,0 +1,40 @@
+/**
+ * Client for the native control endpoint hosted by the Tauri shell.
+ *
+ * The shell passes the endpoint through MUX_NATIVE_CONTROL_URL and
+ * MUX_NATIVE_CONTROL_TOKEN; outside the desktop app neither is set and
+ * requests are reported as unavailable.
+ */
+
+/** Actions the shell may perform, subject to its allowlist. */
+export type NativeAction =
+  | { action: "notify"; params: { title: string; body?: string } }
+  | { action: "focus_window"; params?: Record<string, never> }
+  | { action: "reveal_path"; params: { path: string } };
+
+/** Whether the backend runs under a shell that accepts native actions. */
+export function isNativeControlAvailable(): boolean {
+  return Boolean(process.env.MUX_NATIVE_CONTROL_URL && process.env.MUX_NATIVE_CONTROL_TOKEN);
+}
+
+/** Ask the shell to perform a native action. Throws if it refuses or fails. */
+export async function requestNativeAction(request: NativeAction): Promise<void> {
+  const url = process.env.MUX_NATIVE_CONTROL_URL;
+  const token = process.env.MUX_NATIVE_CONTROL_TOKEN;
+  if (!url || !token) {
+    throw new Error("Native control is not available");
+  }
+
+  const response = await fetch(`${url}/actions/${request.action}`, {
+    method: "POST",
+    headers: {
+      Authorization: `Bearer ${token}`,
+      "Content-Type": "application/json",
+    },
+    body: JSON.stringify(request.params ?? {}),
+  });
+  if (!response.ok) {
+    const body = (await response.json().catch(() => null)) as { error?: string } | null;
+    throw new Error(body?.error ?? `Native action ${request.action} failed (${response.status})`);
+  }
+}
@@ -0,0 +1,33 @@
+import { EventEmitter } from "events";
+
+/**
+ * A message pushed to desktop shells connected to the `/events` WebSocket.
+ * The Tauri shell re-emits it to the frontend as `backend-event:{topic}`.
+ */
+export interface PushEvent {
+  topic: string;
+  payload: unknown;
+}
+
+/** Topics become Tauri event names, which only allow these characters. */
+const TOPIC_PATTERN = /^[A-Za-z0-9\-/:_]+$/;
+
+const emitter = new EventEmitter();
+// One listener per connected shell; there is no meaningful cap.
+emitter.setMaxListeners(0);
+
+/** Publish an event to every connected push client. */
+export function publishPushEvent(topic: string, payload: unknown): void {
+  if (!TOPIC_PATTERN.test(topic)) {
+    throw new Error(`Invalid push event topic: ${topic}`);
+  }
+  emitter.emit("event", { topic, payload } satisfies PushEvent);
+}
+
+/** Subscribe to published events. Returns an unsubscribe function. */
+export function onPushEvent(listener: (event: PushEvent) => void): () => void {
+  emitter.on("event", listener);
+  return () => {
+    emitter.off("event", listener);
+  };
+}
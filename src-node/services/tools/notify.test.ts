@@ -8,7 +8,9 @@
  * Full integration testing with actual system notifications requires running in Electron.
  */
 
-import { describe, it, expect, beforeEach } from "bun:test";
+import { describe, it, expect, beforeEach, afterEach } from "bun:test";
+import * as http from "http";
+import type { AddressInfo } from "net";
 import { createNotifyTool } from "./notify";
 import { createTestToolConfig, TestTempDir } from "./testHelpers";
 import type { ToolConfiguration } from "@/common/utils/tools/tools";
@@ -126,4 +128,69 @@ describe("notify tool", () => {
       expect(result.ui_only?.notify?.workspaceId).toBe("test-workspace-123");
     }
   });
+
+  describe("with the desktop shell's native control endpoint", () => {
+    let server: http.Server;
+    let requests: Array<{ url?: string; authorization?: string; body: string }>;
+    let status: number;
+
+    beforeEach(async () => {
+      requests = [];
+      status = 200;
+      server = http.createServer((req, res) => {
+        let body = "";
+        req.on("data", (chunk: Buffer) => (body += chunk.toString()));
+        req.on("end", () => {
+          requests.push({ url: req.url, authorization: req.headers.authorization, body });
+          res.writeHead(status, { "Content-Type": "application/json" });
+          res.end(status === 200 ? "{}" : JSON.stringify({ error: "refused" }));
+        });
+      });
+      await new Promise<void>((resolve) => server.listen(0, "127.0.0.1", resolve));
+      const { port } = server.address() as AddressInfo;
+      process.env.MUX_NATIVE_CONTROL_URL = `http://127.0.0.1:${port}`;
+      process.env.MUX_NATIVE_CONTROL_TOKEN = "test-token";
+    });
+
+    afterEach(async () => {
+      delete process.env.MUX_NATIVE_CONTROL_URL;
+      delete process.env.MUX_NATIVE_CONTROL_TOKEN;
+      await new Promise<void>((resolve) => server.close(() => resolve()));
+    });
+
+    it("notifies through the shell", async () => {
+      const tool = createNotifyTool(config);
+      const execute = tool.execute as (args: {
+        title: string;
+        message?: string;
+      }) => Promise<NotifyToolResult>;
+
+      const result = await execute({ title: "Build finished", message: "All green" });
+
+      expect(result.success).toBe(true);
+      if (result.success) {
+        expect(result.ui_only?.notify?.notifiedVia).toBe("native");
+      }
+      expect(requests).toHaveLength(1);
+      expect(requests[0].url).toBe("/actions/notify");
+      expect(requests[0].authorization).toBe("Bearer test-token");
+      expect(JSON.parse(requests[0].body)).toEqual({ title: "Build finished", body: "All green" });
+    });
+
+    it("falls back to the browser when the shell refuses", async () => {
+      status = 403;
+      const tool = createNotifyTool(config);
+      const execute = tool.execute as (args: {
+        title: string;
+        message?: string;
+      }) => Promise<NotifyToolResult>;
+
+      const result = await execute({ title: "Build finished" });
+
+      expect(result.success).toBe(true);
+      if (result.success) {
+        expect(result.ui_only?.notify?.notifiedVia).toBe("browser");
+      }
+    });
+  });
 });
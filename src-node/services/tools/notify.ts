@@ -5,15 +5,18 @@
  * operating system's native notification system (macOS Notification Center,
  * Windows Toast notifications, Linux notification daemon).
  *
- * Uses Electron's cross-platform Notification API when available, with graceful
- * fallback for non-Electron environments. Clicking a notification navigates
- * the user to the workspace that sent it.
+ * Uses Electron's cross-platform Notification API when available, then the
+ * desktop shell's native control endpoint, with graceful fallback to browser
+ * notifications. Clicking an Electron notification navigates the user to the
+ * workspace that sent it.
  */
 
 import { tool } from "ai";
 import type { ToolFactory } from "@/common/utils/tools/tools";
 import { TOOL_DEFINITIONS } from "@/common/utils/tools/toolDefinitions";
 import type { NotifyToolResult } from "@/common/types/tools";
+import { isNativeControlAvailable, requestNativeAction } from "@/node/services/nativeControl";
+import { log } from "@/node/services/log";
 
 /** Maximum notification body length (macOS limit is 256 bytes) */
 const MAX_NOTIFICATION_BODY_LENGTH = 200;
@@ -102,6 +105,23 @@ async function sendElectronNotification(
   }
 }
 
+/**
+ * Send a system notification through the desktop shell (Tauri).
+ * Returns true if the shell showed it.
+ */
+async function sendNativeNotification(title: string, body?: string): Promise<boolean> {
+  if (!isNativeControlAvailable()) {
+    return false;
+  }
+  try {
+    await requestNativeAction({ action: "notify", params: { title, body } });
+    return true;
+  } catch (error) {
+    log.debug("Native notification failed; falling back to browser", { error });
+    return false;
+  }
+}
+
 /**
  * Notify tool factory for AI assistant
  * Creates a tool that sends system notifications to the user
@@ -148,7 +168,21 @@ export const createNotifyTool: ToolFactory = (config) => {
         };
       }
 
-      // Electron unavailable - signal frontend to handle browser notification
+      if (await sendNativeNotification(truncatedTitle, truncatedMessage)) {
+        return {
+          success: true,
+          title: truncatedTitle,
+          message: truncatedMessage,
+          ui_only: {
+            notify: {
+              notifiedVia: "native",
+              workspaceId: config.workspaceId,
+            },
+          },
+        };
+      }
+
+      // No native notifier - signal frontend to handle browser notification
       // This is not an error; the notification will be delivered via Web Notifications API
       return {
         success: true,
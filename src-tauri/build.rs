@@ -1,3 +1,54 @@
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::path::Path;
+
 fn main() {
+    embed_sidecar_checksums();
     tauri_build::build()
 }
+
+/// Embed SHA-256 checksums of the bundled backend binaries so they can be
+/// verified before spawning
+fn embed_sidecar_checksums() {
+    println!("cargo:rerun-if-changed=binaries");
+    println!(
+        "cargo:rustc-env=MUP_BUILD_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+
+    let mut entries = String::new();
+    if let Ok(dir) = std::fs::read_dir("binaries") {
+        for entry in dir.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_binary = name.starts_with("mup-server-")
+                && !name.ends_with(".ts")
+                && !name.ends_with(".md");
+            if !is_binary {
+                continue;
+            }
+
+            let bytes = std::fs::read(entry.path()).expect("failed to read sidecar binary");
+            let digest = Sha256::digest(&bytes);
+            let hex = digest.iter().fold(String::new(), |mut s, b| {
+                let _ = write!(s, "{:02x}", b);
+                s
+            });
+            let _ = writeln!(entries, "    ({:?}, {:?}),", name, hex);
+        }
+    }
+
+    // Release builds refuse to spawn a backend without a checksum
+    if entries.is_empty() && std::env::var("PROFILE").as_deref() == Ok("release") {
+        println!("cargo:warning=no backend binaries in binaries/; the release build won't start a backend");
+    }
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("sidecar_checksums.rs");
+    std::fs::write(
+        out,
+        format!(
+            "/// SHA-256 of each bundled backend binary, by file name\npub const SIDECAR_CHECKSUMS: &[(&str, &str)] = &[\n{}];\n",
+            entries
+        ),
+    )
+    .expect("failed to write sidecar checksums");
+}
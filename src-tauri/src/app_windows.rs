@@ -0,0 +1,247 @@
+// Detached app windows
+//
+// Besides the main window, MUP can open chats and terminals in windows of
+// their own. Each is created from a label (`chat-<id>` or `terminal-<id>`,
+// which the `app-windows` capability grants the same access as the main
+// window) and an app-relative route such as `terminal.html?...`. A
+// window's position and size are saved under its label when it closes
+// and reused the next time a window with that label opens.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::{displays, profiles};
+
+/// Saved geometry, in the profile data directory
+const STATE_FILE: &str = "app-windows.json";
+
+/// Label prefixes of detached windows
+const LABEL_PREFIXES: [&str; 2] = ["chat-", "terminal-"];
+
+/// Longest label accepted
+const MAX_LABEL_CHARS: usize = 64;
+
+/// Default window size, in logical pixels
+const DEFAULT_WIDTH: f64 = 900.0;
+const DEFAULT_HEIGHT: f64 = 640.0;
+
+/// Routes of open detached windows, by label
+static ROUTES: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Serializes reads and writes of the state file
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// How a new window looks
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct AppWindowOptions {
+    pub title: Option<String>,
+    /// Logical size, used when no size was saved for the label
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub always_on_top: bool,
+}
+
+/// An open detached window
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AppWindowInfo {
+    pub label: String,
+    pub route: String,
+    pub title: String,
+    pub visible: bool,
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct SavedGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+fn check_label(label: &str) -> Result<(), String> {
+    let valid = LABEL_PREFIXES
+        .iter()
+        .any(|prefix| label.len() > prefix.len() && label.starts_with(prefix))
+        && label.chars().count() <= MAX_LABEL_CHARS
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid window label {:?}: expected chat-<id> or terminal-<id>",
+            label
+        ))
+    }
+}
+
+/// Routes are pages of the app itself, never other origins or files
+fn check_route(route: &str) -> Result<(), String> {
+    let path = route.split(['?', '#']).next().unwrap_or_default();
+    if route.contains("://") || route.starts_with("//") || path.split('/').any(|part| part == "..") {
+        return Err(format!("Invalid window route: {}", route));
+    }
+    Ok(())
+}
+
+fn load_state(app: &AppHandle) -> HashMap<String, SavedGeometry> {
+    profiles::data_dir(app)
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(dir.join(STATE_FILE)).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Remember a window's geometry under its label
+fn save_geometry(window: &WebviewWindow) -> Result<(), String> {
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(());
+    }
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to read window position: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {}", e))?;
+    let geometry = SavedGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let app = window.app_handle();
+    let _guard = STATE_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let mut state = load_state(app);
+    state.insert(window.label().to_string(), geometry);
+    let contents = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+    std::fs::write(profiles::data_dir(app)?.join(STATE_FILE), contents)
+        .map_err(|e| format!("Failed to save window state: {}", e))
+}
+
+/// Save the geometry of every open detached window (e.g. before quitting)
+pub fn save_all(app: &AppHandle) {
+    for (label, window) in app.webview_windows() {
+        if check_label(&label).is_ok() {
+            if let Err(e) = save_geometry(&window) {
+                log::warn!("Failed to save state of window {}: {}", label, e);
+            }
+        }
+    }
+}
+
+/// Open a detached chat or terminal window, or focus it if it is open
+#[tauri::command]
+pub async fn create_app_window(
+    app: AppHandle,
+    label: String,
+    route: String,
+    options: Option<AppWindowOptions>,
+) -> Result<AppWindowInfo, String> {
+    check_label(&label)?;
+    check_route(&route)?;
+    let options = options.unwrap_or_default();
+
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return info(&window);
+    }
+
+    let saved = load_state(&app).get(&label).copied();
+    let builder = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(route.clone().into()));
+    let window = profiles::with_webview_storage(&app, builder)
+        .title(options.title.as_deref().unwrap_or("mux"))
+        .inner_size(
+            options.width.unwrap_or(DEFAULT_WIDTH),
+            options.height.unwrap_or(DEFAULT_HEIGHT),
+        )
+        .decorations(false)
+        .always_on_top(options.always_on_top)
+        .visible(false)
+        .build()
+        .map_err(|e| format!("Failed to create window {}: {}", label, e))?;
+
+    if let Some(geometry) = saved {
+        // Skip positions on a display that is no longer connected
+        if displays::is_on_screen(&app, geometry.x, geometry.y) {
+            let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+        }
+        let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    }
+
+    // Per-window handlers: keep its geometry, and forget it once it is gone
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::CloseRequested { .. } => {
+            if let Err(e) = save_geometry(&handle) {
+                log::warn!("Failed to save state of window {}: {}", handle.label(), e);
+            }
+        }
+        WindowEvent::Destroyed => {
+            if let Ok(mut routes) = ROUTES.lock() {
+                if let Some(routes) = routes.as_mut() {
+                    routes.remove(handle.label());
+                }
+            }
+        }
+        _ => {}
+    });
+
+    if let Ok(mut routes) = ROUTES.lock() {
+        routes.get_or_insert_with(HashMap::new).insert(label.clone(), route);
+    }
+
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    info(&window)
+}
+
+fn info(window: &WebviewWindow) -> Result<AppWindowInfo, String> {
+    let route = ROUTES
+        .lock()
+        .ok()
+        .and_then(|routes| routes.as_ref()?.get(window.label()).cloned())
+        .unwrap_or_default();
+    Ok(AppWindowInfo {
+        label: window.label().to_string(),
+        route,
+        title: window.title().map_err(|e| e.to_string())?,
+        visible: window.is_visible().map_err(|e| e.to_string())?,
+        focused: window.is_focused().map_err(|e| e.to_string())?,
+    })
+}
+
+/// List open detached windows, by label
+#[tauri::command]
+pub async fn list_app_windows(app: AppHandle) -> Result<Vec<AppWindowInfo>, String> {
+    let mut windows = app
+        .webview_windows()
+        .values()
+        .filter(|window| check_label(window.label()).is_ok())
+        .map(info)
+        .collect::<Result<Vec<_>, _>>()?;
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(windows)
+}
+
+/// Close a detached window, saving its geometry
+#[tauri::command]
+pub async fn close_app_window(app: AppHandle, label: String) -> Result<(), String> {
+    check_label(&label)?;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window not found: {}", label))?;
+    window
+        .close()
+        .map_err(|e| format!("Failed to close window {}: {}", label, e))
+}
@@ -0,0 +1,226 @@
+// mux-asset:// protocol
+//
+// Serves images and videos from trusted project roots to the webview so the
+// chat UI can display files referenced by agents without base64-encoding
+// them over IPC. URLs have the form `mux-asset://localhost/<encoded path>`
+// (`http://mux-asset.localhost/...` on Windows). Only files inside roots
+// registered with `allow_asset_root` are served, and byte ranges are
+// supported so videos can seek. Responses are built in memory, so ranges
+// are served in chunks of at most `MAX_RANGE_BYTES` and whole files only
+// up to `MAX_BODY_BYTES`; media elements always request ranges.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{Runtime, UriSchemeContext, UriSchemeResponder};
+
+/// Scheme name registered with the webview
+pub const SCHEME: &str = "mux-asset";
+
+/// Largest chunk returned for a range request
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Largest file returned whole to a request without a range
+const MAX_BODY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Canonicalized project roots files may be served from
+static TRUSTED_ROOTS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+/// MIME type for a file, by extension
+fn mime_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "ogv" => "video/ogg",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "txt" | "log" | "md" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Resolve a request path to a file inside a trusted root
+fn resolve_path(uri_path: &str) -> Result<PathBuf, StatusCode> {
+    let decoded = percent_encoding::percent_decode_str(uri_path)
+        .decode_utf8()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    // "/C:/Users/..." on Windows
+    #[cfg(windows)]
+    let decoded: &str = decoded.trim_start_matches('/');
+    #[cfg(not(windows))]
+    let decoded: &str = &decoded;
+
+    // Canonicalizing resolves `..` and symlinks before the scope check
+    let path = Path::new(decoded)
+        .canonicalize()
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let roots = TRUSTED_ROOTS.read().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !roots.iter().any(|root| path.starts_with(root)) {
+        log::warn!("Refusing asset outside trusted roots: {}", path.display());
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    if !path.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(path)
+}
+
+/// Parse a single `bytes=` range against a file length
+///
+/// Returns None for an unsatisfiable range. Only the first range of a
+/// multi-range request is honored, and at most `MAX_RANGE_BYTES` of it.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+
+    let last = len.checked_sub(1)?;
+    let (start, end) = if start.is_empty() {
+        // Suffix range: the last N bytes
+        let suffix: u64 = end.parse().ok()?;
+        (len.saturating_sub(suffix), last)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = match end {
+            "" => last,
+            end => end.parse::<u64>().ok()?.min(last),
+        };
+        (start, end)
+    };
+    let end = end.min(start.saturating_add(MAX_RANGE_BYTES - 1));
+
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn error_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Build the response for a single asset request
+fn serve(request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>, StatusCode> {
+    let path = resolve_path(request.uri().path())?;
+    let mut file = File::open(&path).map_err(|_| StatusCode::NOT_FOUND)?;
+    let len = file
+        .metadata()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .len();
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type(&path))
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let range = match request.headers().get(header::RANGE) {
+        Some(value) => {
+            let value = value.to_str().map_err(|_| StatusCode::BAD_REQUEST)?;
+            match parse_range(value, len) {
+                Some(range) => Some(range),
+                None => {
+                    return builder
+                        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                        .body(Vec::new())
+                        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let response = match range {
+        Some((start, end)) => {
+            let mut body = Vec::with_capacity((end - start + 1) as usize);
+            file.seek(SeekFrom::Start(start))
+                .and_then(|_| file.take(end - start + 1).read_to_end(&mut body))
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(body)
+        }
+        None if len > MAX_BODY_BYTES => {
+            log::warn!("Refusing to serve {} whole ({} bytes) without a range", path.display(), len);
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        None => {
+            let mut body = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut body)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+            builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(body)
+        }
+    };
+
+    response.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Protocol handler; file IO runs off the main thread
+pub fn handle<R: Runtime>(
+    _ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let response = serve(&request).unwrap_or_else(error_response);
+        responder.respond(response);
+    });
+}
+
+/// Allow assets under a project root to be served
+#[tauri::command]
+pub async fn allow_asset_root(path: PathBuf) -> Result<(), String> {
+    let root = path
+        .canonicalize()
+        .map_err(|e| format!("Invalid asset root {}: {}", path.display(), e))?;
+    if !root.is_dir() {
+        return Err(format!("Asset root is not a directory: {}", root.display()));
+    }
+    // A filesystem root would expose every file on the volume
+    if root.parent().is_none() {
+        return Err(format!("Asset root is too broad: {}", root.display()));
+    }
+
+    let mut roots = TRUSTED_ROOTS.write().map_err(|e| e.to_string())?;
+    if !roots.contains(&root) {
+        roots.push(root);
+    }
+    Ok(())
+}
+
+/// Stop serving assets under a project root
+#[tauri::command]
+pub async fn revoke_asset_root(path: PathBuf) -> Result<(), String> {
+    let root = path.canonicalize().unwrap_or(path);
+    let mut roots = TRUSTED_ROOTS.write().map_err(|e| e.to_string())?;
+    roots.retain(|r| *r != root);
+    Ok(())
+}
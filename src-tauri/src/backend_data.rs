@@ -0,0 +1,171 @@
+// Backend data directory migration
+//
+// Users with small system drives can move the backend's data (MUX_HOME) to
+// another location. Migration stops the sidecar, moves the directory
+// (copying when it crosses filesystems), records the new location in
+// settings, and starts the sidecar again.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{profiles, settings, sidecar, storage};
+
+/// Backend data directory name under the home directory (backend default)
+const DEFAULT_HOME_DIR: &str = ".mux";
+
+/// Payload of the `backend-data-migrated` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MigratedPayload {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Directory the backend currently stores its data in
+pub fn current_home(app: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(dir) = settings::current().sidecar.data_dir {
+        return Ok(dir);
+    }
+    if let Some(dir) = profiles::backend_home(app)? {
+        return Ok(dir);
+    }
+    // The backend reads MUX_HOME from its environment, inherited from ours
+    if let Some(home) = std::env::var_os("MUX_HOME").filter(|home| !home.is_empty()) {
+        return Ok(PathBuf::from(home));
+    }
+    
+    app.path()
+        .home_dir()
+        .map(|home| home.join(DEFAULT_HOME_DIR))
+        .map_err(|e| format!("Failed to get home directory: {}", e))
+}
+
+/// Total size of the files under `path`
+fn dir_size(path: &Path) -> u64 {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| match entry.file_type() {
+                    Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+                    Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    Err(_) => 0,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Recursively copy a directory
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let kind = entry.file_type()?;
+        
+        if kind.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if cfg!(unix) && kind.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+        } else {
+            // Elsewhere symlinks are copied as the files they point to
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Move a directory, falling back to copy-and-delete across filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    
+    storage::ensure_free_space(to, dir_size(from))?;
+    
+    if let Err(e) = copy_dir(from, to) {
+        // Leave the original intact
+        let _ = std::fs::remove_dir_all(to);
+        return Err(format!("Failed to copy backend data: {}", e));
+    }
+    
+    if let Err(e) = std::fs::remove_dir_all(from) {
+        log::warn!("Backend data copied but old directory could not be removed: {}", e);
+    }
+    Ok(())
+}
+
+fn check_target(from: &Path, to: &Path) -> Result<(), String> {
+    if !to.is_absolute() {
+        return Err("Backend data directory must be an absolute path".to_string());
+    }
+    if to.starts_with(from) || from.starts_with(to) {
+        return Err("New backend data directory must not contain or be inside the current one".to_string());
+    }
+    
+    let occupied = std::fs::read_dir(to)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if occupied {
+        return Err(format!("{} is not empty", to.display()));
+    }
+    Ok(())
+}
+
+/// Move the backend's data to `new_dir` and restart the backend there
+#[tauri::command]
+pub async fn migrate_backend_data(app: AppHandle, new_dir: PathBuf) -> Result<(), String> {
+    if profiles::is_guest() {
+        return Err("Guest sessions cannot move backend data".to_string());
+    }
+    
+    let from = current_home(&app)?;
+    check_target(&from, &new_dir)?;
+    
+    log::info!(
+        "Migrating backend data from {} to {}",
+        from.display(),
+        new_dir.display()
+    );
+    
+    sidecar::terminate_sidecar().await?;
+    
+    let moved = if from.exists() {
+        let (from, to) = (from.clone(), new_dir.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            // An empty target directory would make rename fail
+            let _ = std::fs::remove_dir(&to);
+            move_dir(&from, &to)
+        })
+        .await
+        .map_err(|e| format!("Migration task failed: {}", e))?
+    } else {
+        std::fs::create_dir_all(&new_dir)
+            .map_err(|e| format!("Failed to create {}: {}", new_dir.display(), e))
+    };
+    
+    // Only point the backend at the new location once the data is there
+    if moved.is_ok() {
+        let mut current = settings::current();
+        current.sidecar.data_dir = Some(new_dir.clone());
+        settings::save(&app, current)?;
+    }
+    
+    // Restart either way so a failed move doesn't leave the app without a backend
+    sidecar::restart_backend(app.clone(), None).await?;
+    moved?;
+    
+    let payload = MigratedPayload { from, to: new_dir };
+    if let Err(e) = app.emit("backend-data-migrated", payload) {
+        log::error!("Failed to emit backend-data-migrated event: {}", e);
+    }
+    Ok(())
+}
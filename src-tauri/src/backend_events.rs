@@ -0,0 +1,147 @@
+// Backend push events
+//
+// Keeps a WebSocket open to the backend's `/events` endpoint and re-emits
+// each message as a Tauri event named `backend-event:{topic}`, so the
+// frontend receives pushed updates (e.g. chat activity) without opening its
+// own socket to the backend's dynamic port. The connection is re-established
+// with backoff whenever it drops, including across backend restarts.
+
+use futures_util::{Stream, StreamExt};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+use crate::{orpc_bridge, sidecar};
+
+/// Path of the backend's push event WebSocket
+const EVENTS_PATH: &str = "/events";
+
+/// Delay before the first reconnect; doubled up to `MAX_BACKOFF`
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Message format sent by the backend
+#[derive(Debug, serde::Deserialize)]
+struct PushEvent {
+    topic: String,
+    #[serde(default)]
+    payload: JsonValue,
+}
+
+/// Whether a topic is usable in a Tauri event name
+fn valid_topic(topic: &str) -> bool {
+    !topic.is_empty()
+        && topic
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'))
+}
+
+fn dispatch(app: &AppHandle, text: &str) {
+    let event = match serde_json::from_str::<PushEvent>(text) {
+        Ok(event) => event,
+        Err(e) => {
+            log::warn!("Ignoring malformed backend event: {}", e);
+            return;
+        }
+    };
+    if !valid_topic(&event.topic) {
+        log::warn!("Ignoring backend event with invalid topic: {}", event.topic);
+        return;
+    }
+
+    let name = format!("backend-event:{}", event.topic);
+    if let Err(e) = app.emit(&name, event.payload) {
+        log::error!("Failed to emit {} event: {}", name, e);
+    }
+}
+
+/// Forward messages until the socket closes
+async fn pump<S>(app: &AppHandle, mut socket: S) -> Result<(), String>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    while let Some(message) = socket.next().await {
+        match message.map_err(|e| format!("Event socket error: {}", e))? {
+            Message::Text(text) => dispatch(app, &text),
+            Message::Close(_) => break,
+            // Pings are answered by tungstenite while reading
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Open one connection and run it to completion
+async fn connect_and_pump(app: &AppHandle, backoff: &mut Duration) -> Result<(), String> {
+    let url = format!(
+        "{}{}",
+        orpc_bridge::get_backend_url()?.replacen("http://", "ws://", 1),
+        EVENTS_PATH
+    );
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| format!("Invalid event socket URL: {}", e))?;
+    if let Some(token) = sidecar::get_auth_token() {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| format!("Invalid auth token: {}", e))?;
+        request.headers_mut().insert("Authorization", value);
+    }
+
+    #[cfg(unix)]
+    if let Some(path) = sidecar::get_sidecar_socket() {
+        let stream = tokio::net::UnixStream::connect(&path)
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+        let (socket, _) = tokio_tungstenite::client_async(request, stream)
+            .await
+            .map_err(|e| format!("Event socket handshake failed: {}", e))?;
+        log::info!("Connected to backend events");
+        *backoff = INITIAL_BACKOFF;
+        return pump(app, socket).await;
+    }
+
+    #[cfg(windows)]
+    if let Some(path) = sidecar::get_sidecar_socket() {
+        let stream = tokio::net::windows::named_pipe::ClientOptions::new()
+            .open(&path)
+            .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+        let (socket, _) = tokio_tungstenite::client_async(request, stream)
+            .await
+            .map_err(|e| format!("Event socket handshake failed: {}", e))?;
+        log::info!("Connected to backend events");
+        *backoff = INITIAL_BACKOFF;
+        return pump(app, socket).await;
+    }
+
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| format!("Event socket handshake failed: {}", e))?;
+    log::info!("Connected to backend events");
+    *backoff = INITIAL_BACKOFF;
+    pump(app, socket).await
+}
+
+/// Start the push event connection; it runs for the lifetime of the app
+pub fn init(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            // Nothing to connect to while the backend is down
+            if orpc_bridge::wait_until_ready().await.is_err() {
+                continue;
+            }
+
+            match connect_and_pump(&app, &mut backoff).await {
+                Ok(()) => log::info!("Backend event socket closed"),
+                Err(e) => log::warn!("{}", e),
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+}
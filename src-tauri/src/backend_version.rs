@@ -0,0 +1,172 @@
+// Backend version handshake
+//
+// After the sidecar announces its port, query its /version endpoint and check
+// the reported version against the range this build was compiled to support.
+// A stale bundled server (e.g. after a partial update) is reported to the
+// frontend via a `backend-version-mismatch` event.
+
+use serde_json::Value as JsonValue;
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Emitter};
+
+use crate::version::Version;
+use crate::{orpc_bridge, sidecar_update};
+
+/// Oldest backend version this build supports (inclusive)
+const MIN_BACKEND_VERSION: &str = "0.17.0";
+
+/// First backend version this build no longer supports (exclusive)
+const MAX_BACKEND_VERSION: &str = "0.18.0";
+
+/// Version information reported by the backend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BackendVersion {
+    pub version: Option<String>,
+    pub git_commit: Option<String>,
+    pub git_describe: Option<String>,
+    pub build_time: Option<String>,
+    pub compatible: bool,
+}
+
+/// Payload of the `backend-version-mismatch` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VersionMismatchPayload {
+    pub app_version: String,
+    pub backend: BackendVersion,
+    pub min_version: &'static str,
+    pub max_version: &'static str,
+}
+
+static BACKEND_VERSION: OnceLock<RwLock<Option<BackendVersion>>> = OnceLock::new();
+
+fn version_cell() -> &'static RwLock<Option<BackendVersion>> {
+    BACKEND_VERSION.get_or_init(|| RwLock::new(None))
+}
+
+/// Numeric parts of "1.2.3", "v1.2.3", or "v1.2.3-4-gabcdef"; git describe
+/// suffixes aren't pre-releases, so only the core is compared
+pub(crate) fn parse_version(raw: &str) -> Option<(u64, u64, u64)> {
+    Version::parse(raw).map(|version| version.core())
+}
+
+/// Check a parsed version against the supported range
+fn is_compatible(version: (u64, u64, u64)) -> bool {
+    let min = parse_version(MIN_BACKEND_VERSION).unwrap_or((0, 0, 0));
+    let max = parse_version(MAX_BACKEND_VERSION).unwrap_or((u64::MAX, 0, 0));
+    version >= min && version < max
+}
+
+/// Whether this build supports a backend version
+pub(crate) fn is_supported(version: &str) -> bool {
+    parse_version(version).is_some_and(is_compatible)
+}
+
+fn string_field(body: &JsonValue, key: &str) -> Option<String> {
+    body.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// Whether a `git describe` string names a release ("v0.17.2",
+/// "0.17.2-3-gabc1234") rather than a bare commit hash, which may be all
+/// digits and would otherwise parse as a major version
+fn is_release_describe(describe: &str) -> bool {
+    describe.starts_with('v') || describe.contains('.')
+}
+
+/// Build a BackendVersion from the /version response body
+///
+/// Development builds only report a commit hash; those are treated as
+/// compatible since there is nothing to compare.
+fn from_response(body: &JsonValue) -> BackendVersion {
+    let version = string_field(body, "version");
+    let git_describe = string_field(body, "git_describe");
+
+    let parsed = version
+        .as_deref()
+        .or(git_describe.as_deref().filter(|describe| is_release_describe(describe)))
+        .and_then(parse_version);
+
+    BackendVersion {
+        version: parsed.map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch)),
+        git_commit: string_field(body, "git_commit"),
+        git_describe,
+        build_time: string_field(body, "buildTime"),
+        compatible: parsed.map(is_compatible).unwrap_or(true),
+    }
+}
+
+/// Query the running backend's /version endpoint
+pub async fn query() -> Result<BackendVersion, String> {
+    let client = orpc_bridge::ensure_client()?;
+    let url = format!("{}/version", orpc_bridge::get_backend_url()?);
+
+    let response = client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query backend version: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Backend version endpoint returned {}", response.status()));
+    }
+
+    let body = response
+        .json::<JsonValue>()
+        .await
+        .map_err(|e| format!("Failed to parse backend version response: {}", e))?;
+
+    Ok(from_response(&body))
+}
+
+/// Query the backend's version and emit a mismatch event if incompatible
+pub async fn handshake(app: AppHandle) {
+    let backend = match query().await {
+        Ok(backend) => backend,
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    };
+    log::info!("Backend version: {:?}", backend);
+
+    if let Ok(mut guard) = version_cell().write() {
+        *guard = Some(backend.clone());
+    }
+    sidecar_update::verify_backend(&app, &backend);
+
+    if !backend.compatible {
+        log::error!(
+            "Backend version {:?} is outside the supported range {}..{}",
+            backend.version,
+            MIN_BACKEND_VERSION,
+            MAX_BACKEND_VERSION
+        );
+
+        let payload = VersionMismatchPayload {
+            app_version: app.package_info().version.to_string(),
+            backend,
+            min_version: MIN_BACKEND_VERSION,
+            max_version: MAX_BACKEND_VERSION,
+        };
+        if let Err(e) = app.emit("backend-version-mismatch", payload) {
+            log::error!("Failed to emit backend-version-mismatch event: {}", e);
+        }
+    }
+}
+
+/// Forget the cached version (called when the backend terminates)
+pub fn clear() {
+    if let Ok(mut guard) = version_cell().write() {
+        *guard = None;
+    }
+}
+
+/// Get the version reported by the running backend
+#[tauri::command]
+pub async fn get_backend_version() -> Result<BackendVersion, String> {
+    version_cell()
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .ok_or_else(|| "Backend version not known yet".to_string())
+}
@@ -0,0 +1,199 @@
+// Companion CLI for the desktop app
+//
+//   mup open [path]                          open a project
+//   mup chat [--send] [--project <path>] <prompt>
+//                                            start a chat
+//   mup terminal [--cwd <path>]              open a terminal
+//   mup settings [section]                   open settings
+//
+// Requests go to the running app over its local socket. If the app isn't
+// running it is started with the equivalent mux:// link instead.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use mup_lib::cli_ipc::{self, CliRequest, CliResponse, DeepLinkPayload};
+
+const USAGE: &str = "Usage:
+  mup open [path]
+  mup chat [--send] [--project <path>] <prompt>
+  mup terminal [--cwd <path>]
+  mup settings [section]";
+
+/// Resolve a path argument against the current directory
+fn directory(path: &str) -> Result<String, String> {
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to read current directory: {}", e))?;
+    let path = cwd
+        .join(path)
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path, e))?;
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Take the value following a flag
+fn flag_value(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(format!("{} needs a value", flag));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+fn parse_args(mut args: Vec<String>) -> Result<DeepLinkPayload, String> {
+    if args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let command = args.remove(0);
+    match command.as_str() {
+        "open" => Ok(DeepLinkPayload::OpenProject {
+            path: directory(args.first().map(String::as_str).unwrap_or("."))?,
+            callback_id: None,
+        }),
+        "chat" => {
+            let project = flag_value(&mut args, "--project")?;
+            let auto_send = match args.iter().position(|arg| arg == "--send") {
+                Some(index) => {
+                    args.remove(index);
+                    true
+                }
+                None => false,
+            };
+            let prompt = args.join(" ");
+            Ok(DeepLinkPayload::NewChat {
+                project: None,
+                project_path: Some(directory(project.as_deref().unwrap_or("."))?),
+                project_id: None,
+                prompt: (!prompt.trim().is_empty()).then_some(prompt),
+                section_id: None,
+                auto_send,
+                callback_id: None,
+            })
+        }
+        "terminal" => {
+            let cwd = flag_value(&mut args, "--cwd")?;
+            Ok(DeepLinkPayload::NewTerminal {
+                cwd: Some(directory(cwd.as_deref().unwrap_or("."))?),
+                callback_id: None,
+            })
+        }
+        "settings" => Ok(DeepLinkPayload::OpenSettings {
+            section: args.first().cloned(),
+        }),
+        "help" | "--help" | "-h" => Err(USAGE.to_string()),
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+    }
+}
+
+#[cfg(unix)]
+fn connect(path: &Path) -> std::io::Result<std::os::unix::net::UnixStream> {
+    std::os::unix::net::UnixStream::connect(path)
+}
+
+#[cfg(windows)]
+fn connect(path: &Path) -> std::io::Result<std::fs::File> {
+    std::fs::OpenOptions::new().read(true).write(true).open(path)
+}
+
+/// Send a request to the running app; `None` if it isn't running
+fn send(payload: &DeepLinkPayload) -> Option<Result<(), String>> {
+    #[cfg(unix)]
+    if let Err(e) = cli_ipc::secure_socket_dir() {
+        return Some(Err(e));
+    }
+    let mut stream = connect(&cli_ipc::socket_path()).ok()?;
+    let request = CliRequest {
+        payload: payload.clone(),
+    };
+    let mut exchange = || -> Result<CliResponse, String> {
+        let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+        line.push('\n');
+        stream
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+
+        let mut response = String::new();
+        BufReader::new(&mut stream)
+            .read_line(&mut response)
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        serde_json::from_str(&response).map_err(|e| format!("Invalid response: {}", e))
+    };
+
+    Some(exchange().and_then(|response| {
+        if response.ok {
+            Ok(())
+        } else {
+            Err(response.error.unwrap_or_else(|| "Request failed".to_string()))
+        }
+    }))
+}
+
+/// The app binary: `MUP_APP` if set, else the one installed alongside this
+/// CLI (following a symlink such as /usr/local/bin/mup back into the
+/// install)
+fn app_binary() -> Option<PathBuf> {
+    if let Some(app) = std::env::var_os("MUP_APP") {
+        return Some(PathBuf::from(app));
+    }
+    let exe = std::env::current_exe().ok()?.canonicalize().ok()?;
+    let app = exe.with_file_name(format!("mup{}", std::env::consts::EXE_SUFFIX));
+    app.is_file().then_some(app)
+}
+
+/// Hand a link to whatever the OS has registered for mux://
+fn open_url(url: &str) -> std::io::Result<std::process::Child> {
+    #[cfg(target_os = "macos")]
+    let command = std::process::Command::new("open").arg(url).spawn();
+    #[cfg(windows)]
+    let command = std::process::Command::new("rundll32")
+        .args(["url.dll,FileProtocolHandler", url])
+        .spawn();
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let command = std::process::Command::new("xdg-open").arg(url).spawn();
+    command
+}
+
+/// Start the app with the request as a launch argument
+fn launch(payload: &DeepLinkPayload) -> Result<(), String> {
+    let url = payload.to_url();
+    match app_binary() {
+        Some(app) => std::process::Command::new(&app)
+            .arg(url)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to start {}: {}", app.display(), e)),
+        // Installed separately from the app: let the OS find it
+        None => open_url(&url)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to start the app (set MUP_APP to its path): {}", e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let payload = match parse_args(std::env::args().skip(1).collect()) {
+        Ok(payload) => payload,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match send(&payload) {
+        Some(result) => result,
+        None => launch(&payload),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("mup: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
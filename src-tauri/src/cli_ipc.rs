@@ -0,0 +1,173 @@
+// Companion CLI socket
+//
+// The `mup-cli` binary (`mup open .`, `mup chat "..."`) talks to the
+// running app over a local socket: a Unix domain socket readable only by
+// the user, or a named pipe on Windows. Each connection sends one request,
+// a deep link payload as a line of JSON, and gets one JSON line back once
+// the app has acted on the link (opened the project, created the chat or
+// terminal) or refused it. Requests go through the same validation,
+// confirmation and audit log as mux:// links.
+
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::deeplink;
+use crate::deeplink_audit::DeepLinkSource;
+use crate::single_instance;
+
+pub use crate::deeplink::DeepLinkPayload;
+
+/// Longest request line accepted
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+/// One CLI request
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CliRequest {
+    pub payload: DeepLinkPayload,
+}
+
+/// The app's answer to a request
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CliResponse {
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Socket (or pipe) name shared by the app and the CLI
+pub fn socket_path() -> PathBuf {
+    #[cfg(windows)]
+    {
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        PathBuf::from(format!(r"\\.\pipe\mup-{}", user))
+    }
+
+    #[cfg(not(windows))]
+    {
+        // XDG_RUNTIME_DIR is per-user; elsewhere (macOS, or Linux without
+        // a session) a private directory in the temp dir is used
+        let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            // SAFETY: getuid has no preconditions and cannot fail
+            None => std::env::temp_dir().join(format!("mup-{}", unsafe { libc::getuid() })),
+        };
+        dir.join("mup.sock")
+    }
+}
+
+/// Create the socket's directory, or check that an existing one belongs to
+/// this user and is closed to everyone else, so another user can't plant
+/// or watch the socket
+#[cfg(unix)]
+pub fn secure_socket_dir() -> Result<(), String> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let path = socket_path();
+    let dir = path.parent().ok_or("Socket path has no directory")?;
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("Failed to create {}: {}", dir.display(), e)),
+    }
+
+    let metadata = std::fs::symlink_metadata(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    // SAFETY: getuid has no preconditions and cannot fail
+    let uid = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(format!("{} is not a private directory owned by this user", dir.display()));
+    }
+    Ok(())
+}
+
+async fn respond<S: AsyncRead + AsyncWrite + Unpin>(app: &AppHandle, stream: S) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let read = (&mut reader).take(MAX_REQUEST_BYTES).read_line(&mut line).await;
+
+    let result = match read {
+        Ok(_) => match serde_json::from_str::<CliRequest>(&line) {
+            Ok(request) => {
+                single_instance::focus_main_window(app);
+                deeplink::forward_and_wait(app, &request.payload.to_url(), DeepLinkSource::Cli)
+                    .await
+                    .map(|_| ())
+            }
+            Err(e) => Err(format!("Invalid request: {}", e)),
+        },
+        Err(e) => Err(format!("Failed to read request: {}", e)),
+    };
+
+    let response = CliResponse {
+        ok: result.is_ok(),
+        error: result.err(),
+    };
+    if let Ok(mut body) = serde_json::to_string(&response) {
+        body.push('\n');
+        let _ = reader.get_mut().write_all(body.as_bytes()).await;
+    }
+}
+
+#[cfg(unix)]
+async fn serve(app: AppHandle) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    secure_socket_dir()?;
+    let path = socket_path();
+    // Only one instance runs, so a socket left behind is stale
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path)
+        .map_err(|e| format!("Failed to listen on {}: {}", path.display(), e))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to secure {}: {}", path.display(), e))?;
+
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("Failed to accept CLI connection: {}", e))?;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { respond(&app, stream).await });
+    }
+}
+
+#[cfg(windows)]
+async fn serve(app: AppHandle) -> Result<(), String> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = socket_path();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(&name)
+        .map_err(|e| format!("Failed to create pipe {}: {}", name.display(), e))?;
+
+    loop {
+        server
+            .connect()
+            .await
+            .map_err(|e| format!("Failed to accept CLI connection: {}", e))?;
+        let client = server;
+        server = ServerOptions::new()
+            .reject_remote_clients(true)
+            .create(&name)
+            .map_err(|e| format!("Failed to create pipe {}: {}", name.display(), e))?;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { respond(&app, client).await });
+    }
+}
+
+/// Remove the socket file on exit
+pub fn shutdown() {
+    #[cfg(unix)]
+    let _ = std::fs::remove_file(socket_path());
+}
+
+/// Start listening for the companion CLI
+pub fn init(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(app).await {
+            log::warn!("Companion CLI unavailable: {}", e);
+        }
+    });
+}
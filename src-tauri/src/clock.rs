@@ -0,0 +1,103 @@
+// System clock sanity check
+//
+// Compares the local clock against the Date header returned by the update
+// endpoint's host. A badly skewed clock breaks TLS and API signature checks
+// with errors that are baffling to users, so warn about it up front.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Skew beyond which the user is warned
+const MAX_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Host used when no updater endpoint is configured
+const FALLBACK_URL: &str = "https://github.com";
+
+/// Payload of the `clock-skew-warning` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ClockSkewPayload {
+    /// Local time minus server time, in seconds (positive = local is ahead)
+    pub skew_secs: i64,
+    pub local_time_secs: u64,
+    pub server_time_secs: u64,
+    pub reference_url: String,
+}
+
+/// Origin of the first configured updater endpoint
+fn reference_url(app: &AppHandle) -> String {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("endpoints"))
+        .and_then(|endpoints| endpoints.get(0))
+        .and_then(|endpoint| endpoint.as_str())
+        .and_then(|endpoint| url::Url::parse(endpoint).ok())
+        .map(|url| url.origin().ascii_serialization())
+        .filter(|origin| origin != "null")
+        .unwrap_or_else(|| FALLBACK_URL.to_string())
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Fetch the server's Date header
+async fn server_time(url: &str) -> Result<SystemTime, String> {
+    let response = reqwest::Client::new()
+        .head(url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+
+    let date = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| format!("{} returned no Date header", url))?;
+
+    httpdate::parse_http_date(date).map_err(|e| format!("Invalid Date header '{}': {}", date, e))
+}
+
+/// Check the clock in the background and emit `clock-skew-warning` if skewed
+pub fn start_check(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let url = reference_url(&app);
+
+        let server = match server_time(&url).await {
+            Ok(time) => time,
+            Err(e) => {
+                // Offline is not a clock problem
+                log::debug!("Skipping clock check: {}", e);
+                return;
+            }
+        };
+        let local = SystemTime::now();
+
+        let skew_secs = unix_secs(local) as i64 - unix_secs(server) as i64;
+        if skew_secs.unsigned_abs() <= MAX_SKEW.as_secs() {
+            return;
+        }
+
+        log::warn!(
+            "System clock differs from {} by {} seconds",
+            url,
+            skew_secs
+        );
+
+        let payload = ClockSkewPayload {
+            skew_secs,
+            local_time_secs: unix_secs(local),
+            server_time_secs: unix_secs(server),
+            reference_url: url,
+        };
+        if let Err(e) = app.emit("clock-skew-warning", payload) {
+            log::error!("Failed to emit clock-skew-warning event: {}", e);
+        }
+    });
+}
@@ -0,0 +1,318 @@
+// Terminal command history
+//
+// Commands run in terminals are recorded from shell-integration markers:
+// OSC 133 (A prompt, B input, C executing, D;<exit> finished) and the
+// VS Code-style OSC 633 equivalents, where 633;E carries the command line.
+// Shells that only emit 133 have their command line taken from the echoed
+// input between B and C. Entries are stored per working directory in a
+// SQLite database in the profile's data directory, so history survives
+// restarts and can be searched by project.
+
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::profiles;
+use crate::prompt::escape_regex;
+
+/// Database file in the profile data directory
+const DB_FILE: &str = "command-history.db";
+
+/// Oldest entries are pruned beyond this many rows
+const MAX_ENTRIES: i64 = 100_000;
+
+/// Pruning runs once every this many inserts
+const PRUNE_EVERY: u32 = 1_000;
+
+/// Results returned when no limit is given
+const DEFAULT_LIMIT: usize = 50;
+
+/// Longest command line kept; longer input is not a command worth recalling
+const MAX_COMMAND_CHARS: usize = 4_096;
+
+static DB: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+static WRITER: OnceLock<Sender<HistoryEntry>> = OnceLock::new();
+
+/// A command run in a terminal
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HistoryEntry {
+    pub command: String,
+    /// Working directory the command ran in
+    pub cwd: Option<String>,
+    pub exit_code: Option<i32>,
+    /// Unix time in milliseconds
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Matches OSC 133 and 633 shell-integration markers
+fn marker_regex() -> &'static Regex {
+    static MARKERS: OnceLock<Regex> = OnceLock::new();
+    MARKERS.get_or_init(|| {
+        Regex::new(r"\x1b\](?:133|633);([A-Z])(?:;([^\x07\x1b]*))?(?:\x07|\x1b\\)")
+            .expect("valid marker regex")
+    })
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Decode the escaping 633;E uses for its command line (`\\` and `\xAB`)
+fn unescape_command(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let raw = value.as_bytes();
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'\\' && i + 1 < raw.len() {
+            if raw[i + 1] == b'\\' {
+                bytes.push(b'\\');
+                i += 2;
+                continue;
+            }
+            if raw[i + 1] == b'x' && i + 3 < raw.len() {
+                // Parse on bytes: the two after `\x` needn't be a char boundary
+                let hex = std::str::from_utf8(&raw[i + 2..i + 4]).ok();
+                if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    bytes.push(byte);
+                    i += 4;
+                    continue;
+                }
+            }
+        }
+        bytes.push(raw[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Follows shell-integration markers in one terminal's output
+#[derive(Default)]
+pub struct CommandTracker {
+    /// Echoed input since the B marker, while the user is typing
+    input: Option<String>,
+    /// Command line reported by 633;E
+    reported: Option<String>,
+    /// Command that is running, with its start time and directory
+    running: Option<(String, i64, Option<String>)>,
+}
+
+impl CommandTracker {
+    /// Process a chunk of output; `cwd` is the terminal's current directory
+    pub fn feed(&mut self, output: &[u8], cwd: Option<&str>) {
+        let text = String::from_utf8_lossy(output);
+        let mut last = 0;
+        for captures in marker_regex().captures_iter(&text) {
+            let whole = captures.get(0).expect("match");
+            if let Some(input) = self.input.as_mut() {
+                input.push_str(&text[last..whole.start()]);
+            }
+            last = whole.end();
+
+            let value = captures.get(2).map(|m| m.as_str());
+            match &captures[1] {
+                "A" => {
+                    self.input = None;
+                    self.reported = None;
+                }
+                "B" => self.input = Some(String::new()),
+                "E" => self.reported = value.map(unescape_command),
+                "C" => self.started(cwd),
+                "D" => self.finished(value.and_then(|code| code.trim().parse().ok())),
+                _ => {}
+            }
+        }
+        if let Some(input) = self.input.as_mut() {
+            input.push_str(&text[last..]);
+            if input.len() > MAX_COMMAND_CHARS {
+                self.input = None;
+            }
+        }
+    }
+
+    fn started(&mut self, cwd: Option<&str>) {
+        let echoed = self.input.take().map(|input| {
+            let visible = escape_regex().replace_all(&input, "");
+            // Carriage returns redraw the line; only the last segment is shown
+            visible.rsplit('\r').next().unwrap_or("").trim().to_string()
+        });
+        let command = self.reported.take().or(echoed).unwrap_or_default();
+        let command = command.trim();
+        if command.is_empty() || command.len() > MAX_COMMAND_CHARS {
+            self.running = None;
+            return;
+        }
+        self.running = Some((command.to_string(), now_ms(), cwd.map(str::to_string)));
+    }
+
+    fn finished(&mut self, exit_code: Option<i32>) {
+        let Some((command, started_at, cwd)) = self.running.take() else {
+            return;
+        };
+        record(HistoryEntry {
+            command,
+            cwd,
+            exit_code,
+            started_at,
+            finished_at: Some(now_ms()),
+        });
+    }
+}
+
+fn open(app: &AppHandle) -> Result<Connection, String> {
+    let dir = profiles::data_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    let connection = Connection::open(dir.join(DB_FILE))
+        .map_err(|e| format!("Failed to open command history: {}", e))?;
+    connection
+        .execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS commands (
+                 id INTEGER PRIMARY KEY,
+                 command TEXT NOT NULL,
+                 cwd TEXT,
+                 exit_code INTEGER,
+                 started_at INTEGER NOT NULL,
+                 finished_at INTEGER
+             );
+             CREATE INDEX IF NOT EXISTS commands_cwd ON commands (cwd, started_at);",
+        )
+        .map_err(|e| format!("Failed to prepare command history: {}", e))?;
+    Ok(connection)
+}
+
+fn insert(connection: &Connection, entry: &HistoryEntry) -> rusqlite::Result<()> {
+    connection.execute(
+        "INSERT INTO commands (command, cwd, exit_code, started_at, finished_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![entry.command, entry.cwd, entry.exit_code, entry.started_at, entry.finished_at],
+    )?;
+    Ok(())
+}
+
+fn prune(connection: &Connection) -> rusqlite::Result<()> {
+    connection.execute(
+        "DELETE FROM commands WHERE id <= (SELECT MAX(id) FROM commands) - ?1",
+        params![MAX_ENTRIES],
+    )?;
+    Ok(())
+}
+
+/// Open the history database and start the writer
+pub fn init(app: &AppHandle) {
+    let connection = match open(app) {
+        Ok(connection) => connection,
+        Err(e) => {
+            log::warn!("Command history disabled: {}", e);
+            return;
+        }
+    };
+    if DB.set(Mutex::new(connection)).is_err() {
+        return;
+    }
+
+    // Inserts happen off the PTY read path
+    let (sender, receiver) = mpsc::channel::<HistoryEntry>();
+    let _ = WRITER.set(sender);
+    std::thread::spawn(move || {
+        let mut inserted: u32 = 0;
+        for entry in receiver {
+            let Some(Ok(connection)) = DB.get().map(|db| db.lock()) else {
+                continue;
+            };
+            if let Err(e) = insert(&connection, &entry) {
+                log::warn!("Failed to record command: {}", e);
+                continue;
+            }
+            inserted = inserted.wrapping_add(1);
+            if inserted % PRUNE_EVERY == 0 {
+                if let Err(e) = prune(&connection) {
+                    log::warn!("Failed to prune command history: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Release memory SQLite holds for caching
+pub fn shrink_memory() {
+    let Some(Ok(connection)) = DB.get().map(|db| db.lock()) else {
+        return;
+    };
+    if let Err(e) = connection.execute_batch("PRAGMA shrink_memory;") {
+        log::warn!("Failed to shrink command history memory: {}", e);
+    }
+}
+
+/// Queue a finished command for storage
+fn record(entry: HistoryEntry) {
+    if let Some(writer) = WRITER.get() {
+        let _ = writer.send(entry);
+    }
+}
+
+/// Escape LIKE wildcards so user input matches literally
+fn like_literal(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn search(project: &str, query: &str, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    let db = DB.get().ok_or("Command history is not available")?;
+    let connection = db.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let project = project.trim_end_matches(['/', '\\']);
+    let mut statement = connection
+        .prepare_cached(
+            "SELECT command, cwd, exit_code, started_at, finished_at FROM commands
+             WHERE (?1 = '' OR cwd = ?1 OR cwd LIKE ?2 ESCAPE '\\' OR cwd LIKE ?3 ESCAPE '\\')
+               AND command LIKE ?4 ESCAPE '\\'
+             ORDER BY started_at DESC
+             LIMIT ?5",
+        )
+        .map_err(|e| format!("Failed to search command history: {}", e))?;
+    let rows = statement
+        .query_map(
+            params![
+                project,
+                format!("{}/%", like_literal(project)),
+                format!("{}\\\\%", like_literal(project)),
+                format!("%{}%", like_literal(query)),
+                limit as i64,
+            ],
+            |row| {
+                Ok(HistoryEntry {
+                    command: row.get(0)?,
+                    cwd: row.get(1)?,
+                    exit_code: row.get(2)?,
+                    started_at: row.get(3)?,
+                    finished_at: row.get(4)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Failed to search command history: {}", e))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read command history: {}", e))
+}
+
+/// Search commands run in a project directory (or any of its
+/// subdirectories), newest first. An empty project searches everywhere.
+#[tauri::command]
+pub async fn search_command_history(
+    project: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    tauri::async_runtime::spawn_blocking(move || search(&project, &query, limit))
+        .await
+        .map_err(|e| format!("Command history search failed: {}", e))?
+}
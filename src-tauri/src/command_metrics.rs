@@ -0,0 +1,169 @@
+// Tauri command metrics
+//
+// Every IPC invocation passes through `with_metrics`, which counts calls and keeps
+// a window of recent handler times per command, logging calls slower than
+// a frame. The handler time is how long the invoke handler held the thread:
+// the whole call for synchronous commands, and argument parsing plus
+// scheduling for async ones, whose bodies run on the async runtime. That is
+// the part of an IPC call that can stall the UI.
+//
+// Tauri gives the handler no way to see when an async body's future
+// completes, so the full call time (until the frontend has its answer) is
+// measured where it ends: the frontend times every invoke and reports them
+// in batches through `record_command_timings`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::orpc_metrics::percentile;
+
+/// Handler time samples kept per command
+const MAX_SAMPLES: usize = 1000;
+
+/// Calls slower than one 60Hz frame are logged
+const SLOW_THRESHOLD: Duration = Duration::from_millis(16);
+
+#[derive(Default)]
+struct CommandStats {
+    calls: u64,
+    slow_calls: u64,
+    durations_ms: VecDeque<f64>,
+    /// Full call times reported by the frontend
+    call_durations_ms: VecDeque<f64>,
+}
+
+static STATS: Mutex<Option<HashMap<String, CommandStats>>> = Mutex::new(None);
+
+/// Metrics for one command
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CommandMetrics {
+    pub command: String,
+    pub calls: u64,
+    /// Calls over the slow-call threshold
+    pub slow_calls: u64,
+    /// Handler time percentiles over the most recent calls
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    /// Full call time percentiles, including async bodies, over the most
+    /// recent calls the frontend reported
+    pub call_p50_ms: f64,
+    pub call_p95_ms: f64,
+    pub call_p99_ms: f64,
+    pub call_max_ms: f64,
+}
+
+/// A call time measured by the frontend
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CommandTiming {
+    pub command: String,
+    pub ms: f64,
+}
+
+fn push_sample(samples: &mut VecDeque<f64>, ms: f64) {
+    if samples.len() == MAX_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(ms);
+}
+
+fn sorted(samples: &VecDeque<f64>) -> Vec<f64> {
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted
+}
+
+fn record(command: &str, elapsed: Duration) {
+    let slow = elapsed >= SLOW_THRESHOLD;
+    if slow {
+        log::warn!(
+            "Slow command {} took {:.1}ms",
+            command,
+            elapsed.as_secs_f64() * 1000.0
+        );
+    }
+
+    let Ok(mut guard) = STATS.lock() else {
+        return;
+    };
+    let stats = guard
+        .get_or_insert_with(HashMap::new)
+        .entry(command.to_string())
+        .or_default();
+
+    stats.calls += 1;
+    if slow {
+        stats.slow_calls += 1;
+    }
+    push_sample(&mut stats.durations_ms, elapsed.as_secs_f64() * 1000.0);
+}
+
+/// Wrap an invoke handler so every call is recorded
+pub fn with_metrics<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let started = Instant::now();
+        let handled = handler(invoke);
+        record(&command, started.elapsed());
+        handled
+    }
+}
+
+/// Get call counts and handler time percentiles per command
+///
+/// Commands are ordered by p95, slowest first.
+#[tauri::command]
+pub async fn get_command_metrics() -> Result<Vec<CommandMetrics>, String> {
+    let guard = STATS.lock().map_err(|e| e.to_string())?;
+    let mut metrics: Vec<CommandMetrics> = guard
+        .iter()
+        .flatten()
+        .map(|(command, stats)| {
+            let handler = sorted(&stats.durations_ms);
+            let call = sorted(&stats.call_durations_ms);
+            CommandMetrics {
+                command: command.clone(),
+                calls: stats.calls,
+                slow_calls: stats.slow_calls,
+                p50_ms: percentile(&handler, 50.0),
+                p95_ms: percentile(&handler, 95.0),
+                p99_ms: percentile(&handler, 99.0),
+                max_ms: handler.last().copied().unwrap_or(0.0),
+                call_p50_ms: percentile(&call, 50.0),
+                call_p95_ms: percentile(&call, 95.0),
+                call_p99_ms: percentile(&call, 99.0),
+                call_max_ms: call.last().copied().unwrap_or(0.0),
+            }
+        })
+        .collect();
+    metrics.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+
+    Ok(metrics)
+}
+
+/// Record full call times measured by the frontend
+///
+/// Only commands the invoke handler has seen are kept, so plugin commands
+/// and unknown names don't grow the table.
+#[tauri::command]
+pub async fn record_command_timings(timings: Vec<CommandTiming>) -> Result<(), String> {
+    let mut guard = STATS.lock().map_err(|e| e.to_string())?;
+    let Some(stats) = guard.as_mut() else {
+        return Ok(());
+    };
+    for timing in timings {
+        if !timing.ms.is_finite() || timing.ms < 0.0 {
+            continue;
+        }
+        if let Some(stats) = stats.get_mut(&timing.command) {
+            push_sample(&mut stats.call_durations_ms, timing.ms);
+        }
+    }
+    Ok(())
+}
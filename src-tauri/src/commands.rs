@@ -1,7 +1,12 @@
 // Tauri command handlers for IPC communication
 
+use std::path::PathBuf;
 use std::process::Command;
-use tauri::{Emitter, Window};
+use std::sync::OnceLock;
+use sysinfo::System;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use crate::profiles;
 
 // System info structure
 #[derive(serde::Serialize)]
@@ -10,11 +15,97 @@ pub struct SystemInfo {
     arch: String,
     is_rosetta: bool,
     is_windows_wsl_shell: bool,
+    os_name: Option<String>,
+    /// e.g. "14.5" or "10.0.22631"
+    os_version: Option<String>,
+    kernel_version: Option<String>,
+    cpu_brand: Option<String>,
+    logical_cores: usize,
+    physical_cores: Option<usize>,
+    total_memory_bytes: u64,
+    available_memory_bytes: u64,
+    paths: AppPaths,
+}
+
+/// Where the app keeps its files
+#[derive(serde::Serialize)]
+pub struct AppPaths {
+    app_data: Option<PathBuf>,
+    /// Data directory of the active profile
+    profile_data: Option<PathBuf>,
+    config: Option<PathBuf>,
+    cache: Option<PathBuf>,
+    logs: Option<PathBuf>,
+}
+
+/// GPU names, probed once on first request
+static GPU_NAMES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// A command for a helper tool that shows no console window on Windows
+fn helper_command(program: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// List GPU names using the platform's own tools
+fn gpu_names() -> Vec<String> {
+    let (program, args, prefix): (&str, &[&str], &str) = if cfg!(target_os = "macos") {
+        ("system_profiler", &["SPDisplaysDataType"], "Chipset Model:")
+    } else if cfg!(windows) {
+        (
+            "powershell",
+            &["-NoProfile", "-Command", "(Get-CimInstance Win32_VideoController).Name"],
+            "",
+        )
+    } else {
+        ("lspci", &[], "")
+    };
+    
+    let Ok(output) = helper_command(program).args(args).output() else {
+        return Vec::new();
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            if cfg!(target_os = "linux") {
+                // "00:02.0 VGA compatible controller: Intel ..."
+                let (_, rest) = line.split_once(' ')?;
+                let (class, name) = rest.split_once(": ")?;
+                let is_display = ["VGA", "3D controller", "Display controller"]
+                    .iter()
+                    .any(|kind| class.contains(kind));
+                is_display.then(|| name.to_string())
+            } else {
+                line.strip_prefix(prefix).map(|name| name.trim().to_string())
+            }
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
 }
 
-/// Get platform information
+fn app_paths(app: &AppHandle) -> AppPaths {
+    let path = app.path();
+    AppPaths {
+        app_data: path.app_data_dir().ok(),
+        profile_data: profiles::data_dir(app).ok(),
+        config: path.app_config_dir().ok(),
+        cache: path.app_cache_dir().ok(),
+        logs: path.app_log_dir().ok(),
+    }
+}
+
+/// Get platform, hardware and app path information
 #[tauri::command]
-pub async fn get_system_info() -> Result<SystemInfo, String> {
+pub async fn get_system_info(app: AppHandle) -> Result<SystemInfo, String> {
     let platform = std::env::consts::OS.to_string();
     let arch = std::env::consts::ARCH.to_string();
     
@@ -32,16 +123,57 @@ pub async fn get_system_info() -> Result<SystemInfo, String> {
         false
     };
     
+    // Hardware details can take a moment
+    let system = tauri::async_runtime::spawn_blocking(|| {
+        let mut system = System::new();
+        system.refresh_memory();
+        system.refresh_cpu();
+        system
+    })
+    .await
+    .map_err(|e| format!("Failed to read system information: {}", e))?;
+    
     Ok(SystemInfo {
         platform,
         arch,
         is_rosetta,
         is_windows_wsl_shell,
+        os_name: System::name(),
+        os_version: System::os_version(),
+        kernel_version: System::kernel_version(),
+        cpu_brand: system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .filter(|brand| !brand.is_empty()),
+        logical_cores: std::thread::available_parallelism()
+            .map(|cores| cores.get())
+            .unwrap_or(system.cpus().len()),
+        physical_cores: system.physical_core_count(),
+        total_memory_bytes: system.total_memory(),
+        available_memory_bytes: system.available_memory(),
+        paths: app_paths(&app),
     })
 }
 
+/// Get GPU names, where the OS reports them
+///
+/// Separate from `get_system_info` because probing runs a platform tool
+/// (system_profiler, PowerShell, lspci) that can take seconds; the result
+/// is cached for the session.
+#[tauri::command]
+pub async fn get_gpu_info() -> Result<Vec<String>, String> {
+    if let Some(names) = GPU_NAMES.get() {
+        return Ok(names.clone());
+    }
+    let names = tauri::async_runtime::spawn_blocking(gpu_names)
+        .await
+        .map_err(|e| format!("Failed to read GPU information: {}", e))?;
+    Ok(GPU_NAMES.get_or_init(|| names).clone())
+}
+
 /// Check if running under Rosetta on macOS
-fn check_is_rosetta() -> bool {
+pub(crate) fn check_is_rosetta() -> bool {
     match Command::new("sysctl")
         .args(["-n", "sysctl.proc_translated"])
         .output()
@@ -65,7 +197,7 @@ fn check_is_windows_wsl_shell() -> bool {
     }
     
     // Try using 'where bash' command
-    if let Ok(output) = Command::new("where")
+    if let Ok(output) = helper_command("where")
         .args(["bash"])
         .output()
     {
@@ -130,6 +262,21 @@ pub fn show_window(window: Window) -> Result<(), String> {
     window.show().map_err(|e| e.to_string())
 }
 
+/// Keep window above other windows (e.g. a pinned mini chat)
+#[tauri::command]
+pub fn set_always_on_top(window: Window, always_on_top: bool) -> Result<(), String> {
+    window.set_always_on_top(always_on_top).map_err(|e| e.to_string())
+}
+
+/// Enter or leave fullscreen (e.g. distraction-free terminal mode)
+#[tauri::command]
+pub fn toggle_fullscreen(window: Window) -> Result<bool, String> {
+    let is_fullscreen = window.is_fullscreen().map_err(|e| e.to_string())?;
+    
+    window.set_fullscreen(!is_fullscreen).map_err(|e| e.to_string())?;
+    Ok(!is_fullscreen)
+}
+
 /// Get window state
 #[derive(serde::Serialize)]
 pub struct WindowState {
@@ -137,6 +284,8 @@ pub struct WindowState {
     is_minimized: bool,
     is_visible: bool,
     is_focused: bool,
+    is_always_on_top: bool,
+    is_fullscreen: bool,
 }
 
 #[tauri::command]
@@ -146,6 +295,8 @@ pub fn get_window_state(window: Window) -> Result<WindowState, String> {
         is_minimized: window.is_minimized().map_err(|e| e.to_string())?,
         is_visible: window.is_visible().map_err(|e| e.to_string())?,
         is_focused: window.is_focused().map_err(|e| e.to_string())?,
+        is_always_on_top: window.is_always_on_top().map_err(|e| e.to_string())?,
+        is_fullscreen: window.is_fullscreen().map_err(|e| e.to_string())?,
     })
 }
 
@@ -0,0 +1,243 @@
+// Startup crash-loop detection
+//
+// A marker file records that a startup is in progress and how many startups
+// in a row have failed (a panic during setup, the backend dying before it
+// stayed up, or the app never getting that far). After `FAILURE_THRESHOLD`
+// failures the app starts in safe mode: saved sidecar options and optional
+// subsystems are skipped, and `recovery-mode` is emitted with the captured
+// errors so the UI can offer a way out instead of crashing again.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener};
+
+use crate::profiles;
+
+/// Marker file name in the profile data directory
+const MARKER_FILE: &str = "startup.json";
+
+/// Consecutive failed startups before safe mode
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Errors kept in the marker
+const MAX_ERRORS: usize = 10;
+
+/// How long the backend must stay up for a startup to count as successful
+const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct StartupMarker {
+    /// Set while starting; still set at the next launch if startup died
+    in_progress: bool,
+    consecutive_failures: u32,
+    errors: Vec<String>,
+}
+
+/// Payload of `recovery-mode` and result of `get_recovery_state`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecoveryState {
+    pub safe_mode: bool,
+    pub consecutive_failures: u32,
+    pub errors: Vec<String>,
+}
+
+static MARKER_PATH: OnceLock<PathBuf> = OnceLock::new();
+static MARKER: Mutex<Option<StartupMarker>> = Mutex::new(None);
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// A failure was recorded during this run
+static FAILED_THIS_RUN: AtomicBool = AtomicBool::new(false);
+
+/// The backend has stayed up long enough to call this startup successful
+static BACKEND_STABLE: AtomicBool = AtomicBool::new(false);
+
+fn load(path: &PathBuf) -> StartupMarker {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn store(marker: &StartupMarker) {
+    let Some(path) = MARKER_PATH.get() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string(marker) {
+        Ok(contents) => {
+            if let Err(e) = std::fs::write(path, contents) {
+                log::warn!("Failed to write startup marker: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize startup marker: {}", e),
+    }
+}
+
+fn update(f: impl FnOnce(&mut StartupMarker)) {
+    if let Ok(mut guard) = MARKER.lock() {
+        if let Some(marker) = guard.as_mut() {
+            f(marker);
+            store(marker);
+        }
+    }
+}
+
+fn push_error(marker: &mut StartupMarker, error: String) {
+    if marker.errors.len() == MAX_ERRORS {
+        marker.errors.remove(0);
+    }
+    marker.errors.push(error);
+}
+
+/// Record a failed startup
+pub fn record_failure(error: impl Into<String>) {
+    let error = error.into();
+    log::error!("Startup failure: {}", error);
+    let first = !FAILED_THIS_RUN.swap(true, Ordering::SeqCst);
+    update(|marker| {
+        if first {
+            marker.consecutive_failures += 1;
+        }
+        push_error(marker, error);
+    });
+}
+
+/// The app started (or exited) cleanly; reset the failure count
+fn mark_success() {
+    if FAILED_THIS_RUN.load(Ordering::SeqCst) {
+        return;
+    }
+    update(|marker| *marker = StartupMarker::default());
+}
+
+/// Clean exit: an unfinished startup that wasn't a failure doesn't count,
+/// and a recorded failure isn't counted twice
+pub fn mark_clean_exit() {
+    if FAILED_THIS_RUN.load(Ordering::SeqCst) {
+        update(|marker| marker.in_progress = false);
+    } else {
+        mark_success();
+    }
+}
+
+/// Whether this run is in safe mode
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+}
+
+fn current_state() -> RecoveryState {
+    let marker = MARKER
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_default();
+    RecoveryState {
+        safe_mode: is_safe_mode(),
+        consecutive_failures: marker.consecutive_failures,
+        errors: marker.errors,
+    }
+}
+
+/// Start tracking this startup; returns true if the app should start in
+/// safe mode
+///
+/// Must run before the sidecar is spawned or adopted so its events are seen.
+pub fn begin(app: &AppHandle) -> bool {
+    let path = match profiles::data_dir(app) {
+        Ok(dir) => dir.join(MARKER_FILE),
+        Err(e) => {
+            log::warn!("Crash-loop detection disabled: {}", e);
+            return false;
+        }
+    };
+    let _ = MARKER_PATH.set(path.clone());
+
+    let mut marker = load(&path);
+    if marker.in_progress {
+        marker.consecutive_failures += 1;
+        push_error(&mut marker, "Previous startup did not complete".to_string());
+    }
+    marker.in_progress = true;
+    store(&marker);
+
+    let safe_mode = marker.consecutive_failures >= FAILURE_THRESHOLD;
+    SAFE_MODE.store(safe_mode, Ordering::SeqCst);
+    if let Ok(mut guard) = MARKER.lock() {
+        *guard = Some(marker);
+    }
+
+    install_panic_hook();
+    watch_backend(app);
+
+    if safe_mode {
+        let state = current_state();
+        log::warn!(
+            "Starting in safe mode after {} failed startups",
+            state.consecutive_failures
+        );
+        if let Err(e) = app.emit("recovery-mode", state) {
+            log::error!("Failed to emit recovery-mode event: {}", e);
+        }
+    }
+
+    safe_mode
+}
+
+/// Record panics during startup, which abort the app before anything
+/// else can
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !BACKEND_STABLE.load(Ordering::SeqCst) {
+            record_failure(format!("Panic: {}", info));
+        }
+        previous(info);
+    }));
+}
+
+/// Count the startup as successful once the backend has stayed up for a
+/// while
+fn watch_backend(app: &AppHandle) {
+    app.listen_any("backend-ready", |_| {
+        tauri::async_runtime::spawn(async {
+            tokio::time::sleep(STABLE_AFTER).await;
+            if crate::sidecar::is_backend_ready() && !BACKEND_STABLE.swap(true, Ordering::SeqCst) {
+                mark_success();
+            }
+        });
+    });
+
+    app.listen_any("backend-startup-timeout", |_| {
+        record_failure("Backend did not announce its port in time");
+    });
+}
+
+/// The sidecar exited without being asked to; a failure if it happens
+/// before the startup counted as successful
+pub fn backend_died(code: Option<i32>) {
+    if !BACKEND_STABLE.load(Ordering::SeqCst) {
+        record_failure(format!("Backend exited during startup (code {:?})", code));
+    }
+}
+
+/// Get the crash-loop state, e.g. to show a recovery prompt
+#[tauri::command]
+pub async fn get_recovery_state() -> Result<RecoveryState, String> {
+    Ok(current_state())
+}
+
+/// Forget recorded failures so the next launch starts normally
+#[tauri::command]
+pub async fn reset_recovery_state() -> Result<(), String> {
+    FAILED_THIS_RUN.store(false, Ordering::SeqCst);
+    update(|marker| {
+        marker.consecutive_failures = 0;
+        marker.errors.clear();
+    });
+    Ok(())
+}
@@ -1,56 +1,223 @@
 // Deep link handler for mux:// protocol
+//
+// Links come from anywhere, so prompts are stripped of control and
+// bidirectional formatting characters and capped in length. Links that
+// act on their own (sending a prompt, or opening a project outside the
+// trusted roots) are only followed after the user confirms in a native
+// dialog; the "always ask" setting extends that to every link.
+//
+// https://mup.app/open/<route> links are accepted as well and mapped onto
+// the mux:// route of the same name, so links in emails and issue trackers
+// don't trigger a custom-scheme warning in the browser. For the OS to
+// hand those links to the app, mup.app serves the files in `app-links/`
+// under /.well-known/ (with the team ID and package family name filled
+// in). Signed macOS release builds carry the associated-domains
+// entitlement (`tauri.applinks.conf.json`); on Windows the
+// `windows.appUriHandler` extension only applies to MSIX packages, so the
+// NSIS and MSI bundles leave https links to the browser.
 
-use std::path::Path;
-use tauri::{Emitter, Window};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Window};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
-/// Represents a parsed deep link payload
+use crate::deeplink_audit::{self, DeepLinkSource};
+use crate::{deeplink_callback, settings};
+
+/// How long `forward_and_wait` waits for the frontend to act on a link
+const LINK_WAIT: Duration = Duration::from_secs(60);
+
+/// Hosts of https links that map onto mux:// routes
+const UNIVERSAL_LINK_HOSTS: [&str; 2] = ["mup.app", "www.mup.app"];
+
+/// Path prefix of https links; the rest of the path is the route
+const UNIVERSAL_LINK_PREFIX: &str = "/open/";
+
+/// Longest prompt a link may carry, in characters
+const MAX_PROMPT_CHARS: usize = 10_000;
+
+/// Error for links the user declined to follow
+const DECLINED: &str = "Deep link was declined";
+
+/// Longest prompt excerpt shown when asking for confirmation
+const PROMPT_PREVIEW_CHARS: usize = 300;
+
+/// Deep link settings, persisted in app settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DeepLinkSettings {
+    /// Ask before following any link, not just ones that act on their own
+    pub always_ask: bool,
+    /// Directories whose projects links may open without asking
+    pub trusted_roots: Vec<String>,
+    /// Schemes besides https that x-callback-url callbacks may open (e.g.
+    /// "raycast"), as approved by the user
+    pub callback_schemes: Vec<String>,
+}
+
+/// Deep links received before the frontend could handle them (e.g. the
+/// URL a cold launch was started with), oldest first
+static PENDING: Mutex<Vec<DeepLinkPayload>> = Mutex::new(Vec::new());
+
+/// Set once the frontend has drained the queue, so its deep link listener
+/// is attached
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+
+/// A problem that stops a link from being followed
 #[derive(Debug, Clone, serde::Serialize)]
-pub struct DeepLinkPayload {
-    #[serde(rename = "type")]
-    pub payload_type: String,
-    pub project: Option<String>,
-    pub project_path: Option<String>,
-    pub project_id: Option<String>,
-    pub prompt: Option<String>,
-    pub section_id: Option<String>,
+pub struct DeepLinkIssue {
+    /// Query parameter at fault; `None` for problems with the link itself
+    pub param: Option<String>,
+    pub message: String,
+}
+
+/// What a link would do if followed
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeepLinkPreview {
+    /// Parsed link; `None` if it couldn't be parsed
+    pub payload: Option<DeepLinkPayload>,
+    /// Summary such as "start a chat in ~/code/app"
+    pub description: Option<String>,
+    /// Whether following it asks the user first
+    pub requires_confirmation: bool,
+    /// Whether it carries x-callback-url callbacks
+    pub has_callbacks: bool,
+    /// Empty if the link can be followed
+    pub errors: Vec<DeepLinkIssue>,
+}
+
+/// A parsed deep link, by route; also what the companion CLI sends
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeepLinkPayload {
+    /// mux://chat/new?project=...&prompt=...&send=1
+    NewChat {
+        project: Option<String>,
+        project_path: Option<String>,
+        project_id: Option<String>,
+        prompt: Option<String>,
+        section_id: Option<String>,
+        /// Send the prompt without waiting for the user
+        #[serde(default)]
+        auto_send: bool,
+        /// Pass to `complete_deep_link` with the created chat's ID
+        callback_id: Option<String>,
+    },
+    /// mux://chat/<id>
+    OpenChat { chat_id: String },
+    /// mux://project/open?path=...
+    OpenProject {
+        path: String,
+        /// Pass to `complete_deep_link` with the opened project's ID
+        callback_id: Option<String>,
+    },
+    /// mux://terminal/new?cwd=...
+    NewTerminal {
+        cwd: Option<String>,
+        /// Pass to `complete_deep_link` with the ID of the workspace the
+        /// terminal opened in
+        callback_id: Option<String>,
+    },
+    /// mux://settings/<section>
+    OpenSettings { section: Option<String> },
+}
+
+/// Whether a path segment is a plain identifier (chat IDs, settings sections)
+fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Map https://mup.app/open/<route>?... onto mux://<route>?...
+fn to_mux_url(url: url::Url) -> Result<url::Url, String> {
+    match url.scheme() {
+        "mux" => Ok(url),
+        "https" if url.host_str().is_some_and(|host| UNIVERSAL_LINK_HOSTS.contains(&host)) => {
+            let route = url
+                .path()
+                .strip_prefix(UNIVERSAL_LINK_PREFIX)
+                .filter(|route| !route.is_empty())
+                .ok_or_else(|| format!("Not an app link: {}", url.path()))?;
+            let mut mapped = url::Url::parse(&format!("mux://{}", route))
+                .map_err(|e| format!("Invalid URL: {}", e))?;
+            mapped.set_query(url.query());
+            Ok(mapped)
+        }
+        _ => Err("Protocol must be 'mux'".to_string()),
+    }
+}
+
+/// Whether a launch argument or opened URL is a deep link
+pub fn is_deep_link(value: &str) -> bool {
+    value.starts_with("mux://")
+        || url::Url::parse(value).is_ok_and(|url| {
+            url.scheme() == "https"
+                && url.host_str().is_some_and(|host| UNIVERSAL_LINK_HOSTS.contains(&host))
+                && url.path().starts_with(UNIVERSAL_LINK_PREFIX)
+        })
 }
 
 /// Parse a mux:// deep link URL into a structured payload
 ///
-/// Currently supported routes:
+/// Supported routes:
 /// - mux://chat/new?project=...&prompt=...
+/// - mux://chat/<id>
+/// - mux://project/open?path=...
+/// - mux://terminal/new?cwd=...
+/// - mux://settings/<section>
+///
+/// Each is also accepted as https://mup.app/open/<route>.
 pub fn parse_deep_link(url_str: &str) -> Result<DeepLinkPayload, String> {
     let url = url::Url::parse(url_str)
         .map_err(|e| format!("Invalid URL: {}", e))?;
 
-    // Verify protocol
-    if url.scheme() != "mux" {
-        return Err("Protocol must be 'mux'".to_string());
-    }
+    // Verify protocol, mapping app links onto their mux:// route
+    let url = to_mux_url(url)?;
 
-    // Normalize pathname (remove trailing slashes)
-    let normalized_path = url.path().trim_end_matches('/');
+    // The first segment parses as the host in mux://chat/new, and as part
+    // of the path in mux:/chat/new; trailing slashes are ignored
+    let segments: Vec<&str> = url
+        .host_str()
+        .into_iter()
+        .chain(url.path().split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
 
-    // Parse route: mux://chat/new
-    if normalized_path != "/chat/new" {
-        return Err(format!("Unsupported path: {}", normalized_path));
+    match segments.as_slice() {
+        ["chat", "new"] => Ok(DeepLinkPayload::NewChat {
+            project: get_query_param(&url, "project"),
+            project_path: get_query_param(&url, "projectPath"),
+            project_id: get_query_param(&url, "projectId"),
+            prompt: get_query_param(&url, "prompt")
+                .map(|prompt| sanitize_prompt(&prompt))
+                .transpose()?
+                .filter(|prompt| !prompt.trim().is_empty()),
+            section_id: get_query_param(&url, "sectionId"),
+            auto_send: matches!(get_query_param(&url, "send").as_deref(), Some("1" | "true")),
+            callback_id: None,
+        }),
+        ["chat", id] if is_identifier(id) => Ok(DeepLinkPayload::OpenChat {
+            chat_id: id.to_string(),
+        }),
+        ["project", "open"] => {
+            let path = get_query_param(&url, "path")
+                .ok_or_else(|| "mux://project/open needs a path".to_string())?;
+            Ok(DeepLinkPayload::OpenProject { path, callback_id: None })
+        }
+        ["terminal", "new"] => Ok(DeepLinkPayload::NewTerminal {
+            cwd: get_query_param(&url, "cwd"),
+            callback_id: None,
+        }),
+        ["settings"] => Ok(DeepLinkPayload::OpenSettings { section: None }),
+        ["settings", section] if is_identifier(section) => Ok(DeepLinkPayload::OpenSettings {
+            section: Some(section.to_string()),
+        }),
+        _ => Err(format!("Unsupported deep link: /{}", segments.join("/"))),
     }
-
-    // Extract query parameters
-    let project = get_query_param(&url, "project");
-    let project_path = get_query_param(&url, "projectPath");
-    let project_id = get_query_param(&url, "projectId");
-    let prompt = get_query_param(&url, "prompt");
-    let section_id = get_query_param(&url, "sectionId");
-
-    Ok(DeepLinkPayload {
-        payload_type: "new_chat".to_string(),
-        project,
-        project_path,
-        project_id,
-        prompt,
-        section_id,
-    })
 }
 
 /// Get a non-empty query parameter from URL
@@ -61,6 +228,20 @@ fn get_query_param(url: &url::Url, key: &str) -> Option<String> {
         .filter(|v| !v.is_empty())
 }
 
+/// Remove control characters (other than newlines and tabs) and
+/// bidirectional overrides, which can disguise what a prompt says
+fn sanitize_prompt(prompt: &str) -> Result<String, String> {
+    let cleaned: String = prompt
+        .chars()
+        .filter(|c| *c == '\n' || *c == '\t' || !c.is_control())
+        .filter(|c| !matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'))
+        .collect();
+    if cleaned.chars().count() > MAX_PROMPT_CHARS {
+        return Err(format!("Prompt is longer than {} characters", MAX_PROMPT_CHARS));
+    }
+    Ok(cleaned)
+}
+
 /// Validate that a project path exists on the filesystem
 pub fn validate_project_path(path: &str) -> Result<(), String> {
     let path_obj = Path::new(path);
@@ -76,21 +257,209 @@ pub fn validate_project_path(path: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Check the directories a payload refers to exist
+pub fn validate_payload(payload: &DeepLinkPayload) -> Result<(), String> {
+    match directory_param(payload) {
+        Some((_, path)) => validate_project_path(path),
+        None => Ok(()),
+    }
+}
+
+/// The directory a payload refers to, with the query parameter it came from
+fn directory_param(payload: &DeepLinkPayload) -> Option<(&'static str, &str)> {
+    match payload {
+        DeepLinkPayload::NewChat {
+            project_path: Some(path),
+            ..
+        } => Some(("projectPath", path)),
+        DeepLinkPayload::OpenProject { path, .. } => Some(("path", path)),
+        DeepLinkPayload::NewTerminal { cwd: Some(path), .. } => Some(("cwd", path)),
+        _ => None,
+    }
+}
+
+/// Whether `path` is inside one of the trusted roots
+fn is_trusted(path: &str, roots: &[String]) -> bool {
+    let Ok(path) = Path::new(path).canonicalize() else {
+        return false;
+    };
+    roots
+        .iter()
+        .filter_map(|root| PathBuf::from(root).canonicalize().ok())
+        .any(|root| path.starts_with(root))
+}
+
+/// What a link will do without further input, if anything
+fn unattended_action(payload: &DeepLinkPayload, settings: &DeepLinkSettings) -> Option<String> {
+    match payload {
+        DeepLinkPayload::NewChat {
+            prompt: Some(_),
+            auto_send: true,
+            ..
+        } => Some("start a chat and send a prompt".to_string()),
+        DeepLinkPayload::NewChat {
+            project_path: Some(path),
+            ..
+        }
+        | DeepLinkPayload::OpenProject { path, .. }
+            if !is_trusted(path, &settings.trusted_roots) =>
+        {
+            Some(format!("open the project {}", path))
+        }
+        _ => None,
+    }
+}
+
+/// What a link does, for the confirmation dialog
+fn describe(payload: &DeepLinkPayload) -> String {
+    match payload {
+        DeepLinkPayload::NewChat { project_path, project, .. } => match project_path.as_ref().or(project.as_ref()) {
+            Some(project) => format!("start a chat in {}", project),
+            None => "start a chat".to_string(),
+        },
+        DeepLinkPayload::OpenChat { chat_id } => format!("open chat {}", chat_id),
+        DeepLinkPayload::OpenProject { path, .. } => format!("open the project {}", path),
+        DeepLinkPayload::NewTerminal { cwd: Some(cwd), .. } => format!("open a terminal in {}", cwd),
+        DeepLinkPayload::NewTerminal { cwd: None, .. } => "open a terminal".to_string(),
+        DeepLinkPayload::OpenSettings { .. } => "open settings".to_string(),
+    }
+}
+
+/// Ask the user before following a link that acts on its own (or any link
+/// with "always ask" on); errors if they decline
+async fn confirm(app: &AppHandle, payload: &DeepLinkPayload) -> Result<(), String> {
+    let settings = settings::current().deep_links;
+    let action = match unattended_action(payload, &settings) {
+        Some(action) => action,
+        None if settings.always_ask => describe(payload),
+        None => return Ok(()),
+    };
+
+    let mut message = format!("A link wants to {}.", action);
+    if let DeepLinkPayload::NewChat { prompt: Some(prompt), .. } = payload {
+        let mut preview: String = prompt.chars().take(PROMPT_PREVIEW_CHARS).collect();
+        if preview.len() < prompt.len() {
+            preview.push('…');
+        }
+        message.push_str(&format!("\n\nPrompt:\n{}", preview));
+    }
+    message.push_str("\n\nOnly continue if you trust where this link came from.");
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(message)
+        .title("Open link?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Continue".to_string(), "Cancel".to_string()))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    if !rx.await.unwrap_or(false) {
+        log::info!("Deep link to {} was declined", action);
+        return Err(DECLINED.to_string());
+    }
+    Ok(())
+}
+
+impl DeepLinkPayload {
+    /// Where links that create or open something carry their callback ID
+    fn callback_slot(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            DeepLinkPayload::NewChat { callback_id, .. }
+            | DeepLinkPayload::OpenProject { callback_id, .. }
+            | DeepLinkPayload::NewTerminal { callback_id, .. } => Some(callback_id),
+            _ => None,
+        }
+    }
+
+    /// The mux:// URL for this payload; callback IDs are not included
+    pub fn to_url(&self) -> String {
+        let route = match self {
+            DeepLinkPayload::NewChat { .. } => "chat/new".to_string(),
+            DeepLinkPayload::OpenChat { chat_id } => format!("chat/{}", chat_id),
+            DeepLinkPayload::OpenProject { .. } => "project/open".to_string(),
+            DeepLinkPayload::NewTerminal { .. } => "terminal/new".to_string(),
+            DeepLinkPayload::OpenSettings { section: Some(section) } => format!("settings/{}", section),
+            DeepLinkPayload::OpenSettings { section: None } => "settings".to_string(),
+        };
+        // Chat IDs and settings sections are plain identifiers
+        let mut url = url::Url::parse(&format!("mux://{}", route)).expect("valid deep link URL");
+
+        let params: Vec<(&str, &str)> = match self {
+            DeepLinkPayload::NewChat {
+                project,
+                project_path,
+                project_id,
+                prompt,
+                section_id,
+                auto_send,
+                ..
+            } => [
+                ("project", project.as_deref()),
+                ("projectPath", project_path.as_deref()),
+                ("projectId", project_id.as_deref()),
+                ("prompt", prompt.as_deref()),
+                ("sectionId", section_id.as_deref()),
+                ("send", auto_send.then_some("1")),
+            ]
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| (key, value)))
+            .collect(),
+            DeepLinkPayload::OpenProject { path, .. } => vec![("path", path.as_str())],
+            DeepLinkPayload::NewTerminal { cwd: Some(cwd), .. } => vec![("cwd", cwd.as_str())],
+            _ => Vec::new(),
+        };
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        url.to_string()
+    }
+}
+
+/// Parse, validate and confirm a link, recording it in the audit log and
+/// answering its x-callback-url callbacks where the outcome is known
+async fn accept(app: &AppHandle, url: &str, source: DeepLinkSource) -> Result<DeepLinkPayload, String> {
+    let callbacks = deeplink_callback::parse(url);
+    let result = async {
+        let payload = parse_deep_link(url)?;
+        validate_payload(&payload)?;
+        if !source.is_in_app() {
+            confirm(app, &payload).await?;
+        }
+        Ok::<_, String>(payload)
+    }
+    .await;
+    deeplink_audit::record(source, url, &result);
+
+    let Some(callbacks) = callbacks else {
+        return result;
+    };
+    match result {
+        Ok(mut payload) => {
+            match payload.callback_slot() {
+                Some(slot) => *slot = Some(deeplink_callback::register(callbacks)),
+                None => deeplink_callback::succeed(app, &callbacks, None),
+            }
+            Ok(payload)
+        }
+        Err(e) => {
+            deeplink_callback::fail(app, &callbacks, &e, e == DECLINED);
+            Err(e)
+        }
+    }
+}
+
 /// Handle a deep link URL from the frontend
 ///
 /// This command:
-/// 1. Parses the mux:// URL
-/// 2. Validates the project path (if provided)
-/// 3. Emits a deep-link event to the frontend
+/// 1. Parses the mux:// URL, sanitizing any prompt
+/// 2. Validates the directories it refers to (if any)
+/// 3. Asks the user to confirm links that act on their own
+/// 4. Emits a deep-link event to the frontend
 #[tauri::command]
 pub async fn handle_deep_link(window: Window, url: String) -> Result<(), String> {
-    // Parse the URL
-    let payload = parse_deep_link(&url)?;
-    
-    // Validate project path if provided
-    if let Some(ref project_path) = payload.project_path {
-        validate_project_path(project_path)?;
-    }
+    // Parse, validate and confirm the URL
+    let payload = accept(window.app_handle(), &url, DeepLinkSource::Frontend).await?;
     
     // Emit event to frontend
     window
@@ -99,3 +468,103 @@ pub async fn handle_deep_link(window: Window, url: String) -> Result<(), String>
     
     Ok(())
 }
+
+/// Route a deep link that arrived outside the webview (e.g. from a second
+/// launch) to the frontend, queueing it if the frontend isn't ready yet
+pub async fn forward(app: &AppHandle, url: &str, source: DeepLinkSource) -> Result<(), String> {
+    let payload = accept(app, url, source).await?;
+    deliver(app, payload)
+}
+
+/// Route a link like `forward`, then wait for the frontend to act on links
+/// that create or open something; returns the ID of what it opened
+pub async fn forward_and_wait(app: &AppHandle, url: &str, source: DeepLinkSource) -> Result<Option<String>, String> {
+    let mut payload = accept(app, url, source).await?;
+    let waiter = payload
+        .callback_slot()
+        .filter(|slot| slot.is_none())
+        .map(|slot| {
+            let (id, outcome) = deeplink_callback::register_waiter();
+            *slot = Some(id.clone());
+            (id, outcome)
+        });
+    deliver(app, payload)?;
+    
+    let Some((id, outcome)) = waiter else {
+        return Ok(None);
+    };
+    match tokio::time::timeout(LINK_WAIT, outcome).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(_)) => Err("The app stopped before handling the request".to_string()),
+        Err(_) => {
+            deeplink_callback::forget_waiter(&id);
+            Err("Timed out waiting for the app to handle the request".to_string())
+        }
+    }
+}
+
+/// Emit an accepted link, or queue it until the frontend is ready
+fn deliver(app: &AppHandle, payload: DeepLinkPayload) -> Result<(), String> {
+    // Checked under the queue lock so a concurrent drain can't miss it
+    if let Ok(mut pending) = PENDING.lock() {
+        if !FRONTEND_READY.load(Ordering::SeqCst) {
+            pending.push(payload);
+            return Ok(());
+        }
+    }
+    app.emit("mux:deep-link", payload)
+        .map_err(|e| format!("Failed to emit deep-link event: {}", e))
+}
+
+/// Describe what a link would do, without following it: its payload, or
+/// what makes it invalid
+#[tauri::command]
+pub async fn parse_deep_link_preview(url: String) -> Result<DeepLinkPreview, String> {
+    let has_callbacks = deeplink_callback::parse(&url).is_some();
+    let payload = match parse_deep_link(&url) {
+        Ok(payload) => payload,
+        Err(message) => {
+            return Ok(DeepLinkPreview {
+                payload: None,
+                description: None,
+                requires_confirmation: false,
+                has_callbacks,
+                errors: vec![DeepLinkIssue { param: None, message }],
+            });
+        }
+    };
+
+    let errors = directory_param(&payload)
+        .and_then(|(param, path)| {
+            validate_project_path(path).err().map(|message| DeepLinkIssue {
+                param: Some(param.to_string()),
+                message,
+            })
+        })
+        .into_iter()
+        .collect();
+    let settings = settings::current().deep_links;
+    Ok(DeepLinkPreview {
+        description: Some(describe(&payload)),
+        requires_confirmation: settings.always_ask || unattended_action(&payload, &settings).is_some(),
+        has_callbacks,
+        errors,
+        payload: Some(payload),
+    })
+}
+
+/// Deep links still waiting for the frontend, without taking them
+pub fn pending_deep_links() -> Vec<DeepLinkPayload> {
+    PENDING.lock().map(|pending| pending.clone()).unwrap_or_default()
+}
+
+/// Take deep links that arrived before the frontend was ready
+///
+/// The frontend calls this once its `mux:deep-link` listener is attached;
+/// later links are emitted directly.
+#[tauri::command]
+pub async fn drain_pending_deep_links() -> Result<Vec<DeepLinkPayload>, String> {
+    let mut pending = PENDING.lock().map_err(|e| e.to_string())?;
+    FRONTEND_READY.store(true, Ordering::SeqCst);
+    Ok(std::mem::take(&mut *pending))
+}
@@ -0,0 +1,281 @@
+// Deep link audit log
+//
+// Every mux:// link the app receives is recorded with when it arrived,
+// where it came from, what it parsed to and whether it was followed, so
+// users can review what other applications asked the app to do. Entries
+// are kept in a SQLite database in the profile's audit directory, can be
+// filtered by source and outcome, and are deleted by the audit entry
+// retention setting and purges like the rest of the audit data.
+
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::deeplink::DeepLinkPayload;
+use crate::profiles;
+use crate::retention::{self, RetentionCategory};
+
+/// Database file in the profile's audit directory
+const DB_FILE: &str = "deep-links.db";
+
+/// Oldest entries are pruned beyond this many rows
+const MAX_ENTRIES: i64 = 10_000;
+
+/// Results returned when no limit is given
+const DEFAULT_LIMIT: usize = 100;
+
+/// Longest URL stored
+const MAX_URL_CHARS: usize = 16_384;
+
+static DB: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+/// Where a link came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepLinkSource {
+    /// Passed in by the frontend
+    Frontend,
+    /// The URL the app was launched with
+    LaunchArgument,
+    /// Forwarded from a second launch
+    SecondInstance,
+    /// Delivered by macOS to the running app
+    OsEvent,
+    /// Sent by the companion CLI
+    Cli,
+    /// Selected text sent through the macOS Services menu
+    Service,
+    /// Picked from the app's own tray menu
+    Tray,
+}
+
+impl DeepLinkSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            DeepLinkSource::Frontend => "frontend",
+            DeepLinkSource::LaunchArgument => "launch_argument",
+            DeepLinkSource::SecondInstance => "second_instance",
+            DeepLinkSource::OsEvent => "os_event",
+            DeepLinkSource::Cli => "cli",
+            DeepLinkSource::Service => "service",
+            DeepLinkSource::Tray => "tray",
+        }
+    }
+
+    /// Whether the link was built by the app's own UI rather than passed in
+    /// from outside, so it needs no confirmation
+    pub fn is_in_app(self) -> bool {
+        matches!(self, DeepLinkSource::Tray)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "frontend" => Some(DeepLinkSource::Frontend),
+            "launch_argument" => Some(DeepLinkSource::LaunchArgument),
+            "second_instance" => Some(DeepLinkSource::SecondInstance),
+            "os_event" => Some(DeepLinkSource::OsEvent),
+            "cli" => Some(DeepLinkSource::Cli),
+            "service" => Some(DeepLinkSource::Service),
+            "tray" => Some(DeepLinkSource::Tray),
+            _ => None,
+        }
+    }
+}
+
+/// A received deep link
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeepLinkRecord {
+    /// Unix time in milliseconds
+    pub received_at: i64,
+    /// `None` for entries from an unknown source
+    pub source: Option<DeepLinkSource>,
+    pub url: String,
+    /// Parsed payload, when the link could be parsed
+    pub payload: Option<serde_json::Value>,
+    pub accepted: bool,
+    /// Why the link was rejected
+    pub reason: Option<String>,
+}
+
+/// Filters for `get_deep_link_history`
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct DeepLinkHistoryQuery {
+    pub source: Option<DeepLinkSource>,
+    pub accepted: Option<bool>,
+    /// Only links received at or after this Unix time in milliseconds
+    pub since: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Move a log kept in the data directory by earlier versions into `dir`
+fn migrate(app: &AppHandle, dir: &Path) {
+    let Ok(old_dir) = profiles::data_dir(app) else {
+        return;
+    };
+    if !old_dir.join(DB_FILE).exists() || dir.join(DB_FILE).exists() {
+        return;
+    }
+    for suffix in ["", "-wal", "-shm"] {
+        let name = format!("{}{}", DB_FILE, suffix);
+        let old = old_dir.join(&name);
+        if old.exists() {
+            if let Err(e) = std::fs::rename(&old, dir.join(&name)) {
+                log::warn!("Failed to move {}: {}", old.display(), e);
+            }
+        }
+    }
+}
+
+fn open(app: &AppHandle) -> Result<Connection, String> {
+    let dir = retention::category_dir(app, RetentionCategory::AuditEntries)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create audit directory: {}", e))?;
+    migrate(app, &dir);
+    let connection = Connection::open(dir.join(DB_FILE))
+        .map_err(|e| format!("Failed to open deep link log: {}", e))?;
+    connection
+        .execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS deep_links (
+                 id INTEGER PRIMARY KEY,
+                 received_at INTEGER NOT NULL,
+                 source TEXT NOT NULL,
+                 url TEXT NOT NULL,
+                 payload TEXT,
+                 accepted INTEGER NOT NULL,
+                 reason TEXT
+             );
+             CREATE INDEX IF NOT EXISTS deep_links_received ON deep_links (received_at);",
+        )
+        .map_err(|e| format!("Failed to prepare deep link log: {}", e))?;
+    Ok(connection)
+}
+
+/// Open the audit database
+pub fn init(app: &AppHandle) {
+    match open(app) {
+        Ok(connection) => {
+            let _ = DB.set(Mutex::new(connection));
+        }
+        Err(e) => log::warn!("Deep link audit log disabled: {}", e),
+    }
+}
+
+/// Record a received link and whether it was followed
+pub fn record(source: DeepLinkSource, url: &str, result: &Result<DeepLinkPayload, String>) {
+    let Some(Ok(connection)) = DB.get().map(|db| db.lock()) else {
+        return;
+    };
+    let url: String = url.chars().take(MAX_URL_CHARS).collect();
+    let (payload, reason) = match result {
+        Ok(payload) => (serde_json::to_string(payload).ok(), None),
+        Err(e) => (None, Some(e.as_str())),
+    };
+
+    let inserted = connection.execute(
+        "INSERT INTO deep_links (received_at, source, url, payload, accepted, reason)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![now_ms(), source.as_str(), url, payload, result.is_ok(), reason],
+    );
+    let pruned = inserted.and_then(|_| {
+        connection.execute(
+            "DELETE FROM deep_links WHERE id <= (SELECT MAX(id) FROM deep_links) - ?1",
+            params![MAX_ENTRIES],
+        )
+    });
+    if let Err(e) = pruned {
+        log::warn!("Failed to record deep link: {}", e);
+    }
+}
+
+/// Whether a file belongs to the audit database, which retention prunes
+/// by row instead of deleting from under the open connection
+pub fn is_database_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with(DB_FILE))
+}
+
+/// Delete entries received before `cutoff`, or all of them; returns how
+/// many were deleted
+pub fn prune(cutoff: Option<SystemTime>) -> Result<u64, String> {
+    let Some(db) = DB.get() else {
+        return Ok(0);
+    };
+    let connection = db.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let cutoff_ms = cutoff.map_or(i64::MAX, |cutoff| {
+        cutoff
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or(0)
+    });
+    let deleted = connection
+        .execute("DELETE FROM deep_links WHERE received_at < ?1", params![cutoff_ms])
+        .map_err(|e| format!("Failed to prune deep link log: {}", e))?;
+
+    // Purged URLs and prompts shouldn't linger in free pages or the WAL
+    if cutoff.is_none() {
+        connection
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM;")
+            .map_err(|e| format!("Failed to compact deep link log: {}", e))?;
+    }
+    Ok(deleted as u64)
+}
+
+fn run_query(filter: &DeepLinkHistoryQuery) -> Result<Vec<DeepLinkRecord>, String> {
+    let db = DB.get().ok_or("Deep link audit log is not available")?;
+    let connection = db.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut statement = connection
+        .prepare_cached(
+            "SELECT received_at, source, url, payload, accepted, reason FROM deep_links
+             WHERE (?1 IS NULL OR source = ?1)
+               AND (?2 IS NULL OR accepted = ?2)
+               AND (?3 IS NULL OR received_at >= ?3)
+             ORDER BY received_at DESC, id DESC
+             LIMIT ?4",
+        )
+        .map_err(|e| format!("Failed to query deep link log: {}", e))?;
+    let rows = statement
+        .query_map(
+            params![
+                filter.source.map(DeepLinkSource::as_str),
+                filter.accepted,
+                filter.since,
+                filter.limit.unwrap_or(DEFAULT_LIMIT) as i64,
+            ],
+            |row| {
+                let source: String = row.get(1)?;
+                let payload: Option<String> = row.get(3)?;
+                Ok(DeepLinkRecord {
+                    received_at: row.get(0)?,
+                    source: DeepLinkSource::parse(&source),
+                    url: row.get(2)?,
+                    payload: payload.and_then(|payload| serde_json::from_str(&payload).ok()),
+                    accepted: row.get(4)?,
+                    reason: row.get(5)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Failed to query deep link log: {}", e))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read deep link log: {}", e))
+}
+
+/// Get received deep links, newest first
+#[tauri::command]
+pub async fn get_deep_link_history(query: Option<DeepLinkHistoryQuery>) -> Result<Vec<DeepLinkRecord>, String> {
+    let filter = query.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || run_query(&filter))
+        .await
+        .map_err(|e| format!("Deep link history query failed: {}", e))?
+}
@@ -0,0 +1,194 @@
+// x-callback-url responses for deep links
+//
+// Launchers like Raycast and Alfred pass `x-success`, `x-error` and
+// `x-cancel` URLs with a link and expect one of them to be opened once the
+// app has acted on it. Links that create something (a chat, a terminal)
+// get a callback ID in their payload; the frontend reports the created
+// resource with `complete_deep_link` and `x-success` is opened with its
+// `id` appended. Other links succeed as soon as they are accepted.
+// Failures open `x-error` with `errorCode` and `errorMessage`, and declined
+// links open `x-cancel`. Callbacks nobody completes expire. The companion
+// CLI waits on links the same way, so it only reports success once the
+// app has acted on them. Any app can
+// send a link, so callbacks may only be https URLs or use a scheme the
+// user has allowed in the deep link settings; anything else could start an
+// arbitrary protocol handler.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::settings;
+
+/// How long the frontend has to complete a link
+const CALLBACK_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Scheme callbacks may always use
+const ALLOWED_SCHEME: &str = "https";
+
+/// Callback URLs passed with a link
+#[derive(Debug, Clone)]
+pub struct Callbacks {
+    success: Option<url::Url>,
+    error: Option<url::Url>,
+    cancel: Option<url::Url>,
+}
+
+/// Callbacks waiting for the frontend, by callback ID
+static PENDING: Mutex<Option<HashMap<String, (Callbacks, Instant)>>> = Mutex::new(None);
+
+/// Outcome of a link: the ID of what it created, or an error
+pub type Outcome = Result<Option<String>, String>;
+
+/// Requests waiting for the frontend to complete their link, by callback ID
+static WAITERS: Mutex<Option<HashMap<String, oneshot::Sender<Outcome>>>> = Mutex::new(None);
+
+/// Whether a callback may be opened: https, or a scheme the user allowed
+/// (never mux itself)
+fn is_allowed(callback: &url::Url, allowed_schemes: &[String]) -> bool {
+    let scheme = callback.scheme();
+    scheme == ALLOWED_SCHEME
+        || (scheme != "mux" && allowed_schemes.iter().any(|allowed| allowed.eq_ignore_ascii_case(scheme)))
+}
+
+/// Parse a callback parameter, ignoring URLs with schemes not allowed
+fn callback_param(url: &url::Url, key: &str, allowed_schemes: &[String]) -> Option<url::Url> {
+    let value = url.query_pairs().find(|(k, _)| k == key)?.1;
+    match url::Url::parse(&value) {
+        Ok(callback) if is_allowed(&callback, allowed_schemes) => Some(callback),
+        _ => {
+            log::warn!("Ignoring {} callback {}", key, value);
+            None
+        }
+    }
+}
+
+/// Callbacks in a deep link's query, if it has any
+pub fn parse(url_str: &str) -> Option<Callbacks> {
+    let url = url::Url::parse(url_str).ok()?;
+    let allowed_schemes = settings::current().deep_links.callback_schemes;
+    let callbacks = Callbacks {
+        success: callback_param(&url, "x-success", &allowed_schemes),
+        error: callback_param(&url, "x-error", &allowed_schemes),
+        cancel: callback_param(&url, "x-cancel", &allowed_schemes),
+    };
+    let any = callbacks.success.is_some() || callbacks.error.is_some() || callbacks.cancel.is_some();
+    any.then_some(callbacks)
+}
+
+fn open(app: &AppHandle, url: &url::Url) {
+    if let Err(e) = app.opener().open_url(url.as_str(), None::<&str>) {
+        log::warn!("Failed to open deep link callback: {}", e);
+    }
+}
+
+/// Report success, with the ID of the created resource if any
+pub fn succeed(app: &AppHandle, callbacks: &Callbacks, resource_id: Option<&str>) {
+    let Some(mut url) = callbacks.success.clone() else {
+        return;
+    };
+    if let Some(id) = resource_id {
+        url.query_pairs_mut().append_pair("id", id);
+    }
+    open(app, &url);
+}
+
+/// Report failure, or cancellation if the user declined the link
+pub fn fail(app: &AppHandle, callbacks: &Callbacks, message: &str, declined: bool) {
+    if declined {
+        if let Some(url) = &callbacks.cancel {
+            open(app, url);
+            return;
+        }
+    }
+    let Some(mut url) = callbacks.error.clone() else {
+        return;
+    };
+    let code = if declined { "cancelled" } else { "invalid_link" };
+    url.query_pairs_mut()
+        .append_pair("errorCode", code)
+        .append_pair("errorMessage", message);
+    open(app, &url);
+}
+
+fn new_id() -> String {
+    format!("link-{:016x}", rand::random::<u64>())
+}
+
+/// Wait for the frontend to complete a link; returns the callback ID for
+/// the payload and where its outcome arrives
+pub fn register_waiter() -> (String, oneshot::Receiver<Outcome>) {
+    let id = new_id();
+    let (tx, rx) = oneshot::channel();
+    if let Ok(mut waiters) = WAITERS.lock() {
+        waiters.get_or_insert_with(HashMap::new).insert(id.clone(), tx);
+    }
+    (id, rx)
+}
+
+/// Stop waiting on a link, e.g. after timing out
+pub fn forget_waiter(callback_id: &str) {
+    if let Ok(mut waiters) = WAITERS.lock() {
+        if let Some(waiters) = waiters.as_mut() {
+            waiters.remove(callback_id);
+        }
+    }
+}
+
+/// Hold callbacks until the frontend completes the link; returns the
+/// callback ID for the payload
+pub fn register(callbacks: Callbacks) -> String {
+    let id = new_id();
+    if let Ok(mut pending) = PENDING.lock() {
+        let pending = pending.get_or_insert_with(HashMap::new);
+        pending.retain(|_, (_, created)| created.elapsed() < CALLBACK_TTL);
+        pending.insert(id.clone(), (callbacks, Instant::now()));
+    }
+    id
+}
+
+/// Report the outcome of a deep link that carried a callback ID: the ID of
+/// what it created, or an error
+#[tauri::command]
+pub async fn complete_deep_link(
+    app: AppHandle,
+    callback_id: String,
+    resource_id: Option<String>,
+    error: Option<String>,
+) -> Result<(), String> {
+    let waiter = WAITERS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_mut()
+        .and_then(|waiters| waiters.remove(&callback_id));
+    if let Some(waiter) = waiter {
+        let _ = waiter.send(error.map_or(Ok(resource_id), Err));
+        return Ok(());
+    }
+
+    let callbacks = PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_mut()
+        .and_then(|pending| pending.remove(&callback_id))
+        .filter(|(_, created)| created.elapsed() < CALLBACK_TTL)
+        .map(|(callbacks, _)| callbacks)
+        .ok_or_else(|| format!("Unknown or expired deep link callback: {}", callback_id))?;
+
+    match error {
+        Some(message) => {
+            let Some(mut url) = callbacks.error else {
+                return Ok(());
+            };
+            url.query_pairs_mut()
+                .append_pair("errorCode", "failed")
+                .append_pair("errorMessage", &message);
+            open(&app, &url);
+        }
+        None => succeed(&app, &callbacks, resource_id.as_deref()),
+    }
+    Ok(())
+}
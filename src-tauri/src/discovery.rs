@@ -0,0 +1,217 @@
+// Local network discovery
+//
+// Opt-in mDNS announcement and browsing so companion devices (a tablet
+// viewer, a second machine) can find this desktop app on the local
+// network. Only the service location and version are advertised; pairing
+// still goes through the authenticated bridge. The backend listens on
+// loopback unless `listen_on_lan` is set, so nothing is advertised until
+// it is.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use tauri::{AppHandle, Listener};
+
+use crate::{settings, sidecar};
+
+/// mDNS service type for mux desktop instances
+const SERVICE_TYPE: &str = "_mux._tcp.local.";
+
+/// Longest accepted browse duration
+const MAX_BROWSE_MS: u64 = 10_000;
+
+/// Discovery settings, persisted in app settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DiscoverySettings {
+    /// Announce this instance on the local network
+    pub enabled: bool,
+}
+
+/// A mux instance found on the local network
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiscoveredDevice {
+    pub name: String,
+    pub host: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    pub properties: HashMap<String, String>,
+}
+
+/// Shared mDNS daemon and the full name of our announced service
+struct Discovery {
+    daemon: ServiceDaemon,
+    announced: Option<String>,
+}
+
+static DISCOVERY: Mutex<Option<Discovery>> = Mutex::new(None);
+
+/// Run `f` with the mDNS daemon, starting it on first use
+fn with_daemon<T>(f: impl FnOnce(&mut Discovery) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = DISCOVERY.lock().map_err(|e| e.to_string())?;
+    if guard.is_none() {
+        let daemon = ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS: {}", e))?;
+        *guard = Some(Discovery {
+            daemon,
+            announced: None,
+        });
+    }
+    match guard.as_mut() {
+        Some(discovery) => f(discovery),
+        None => Err("mDNS daemon unavailable".to_string()),
+    }
+}
+
+fn host_name() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| "mux".to_string())
+}
+
+/// Stop announcing this instance
+fn withdraw() {
+    let _ = with_daemon(|discovery| {
+        if let Some(fullname) = discovery.announced.take() {
+            discovery
+                .daemon
+                .unregister(&fullname)
+                .map_err(|e| format!("Failed to withdraw mDNS service: {}", e))?;
+            log::info!("Stopped mDNS announcement");
+        }
+        Ok(())
+    });
+}
+
+/// Whether the running backend can be reached from the local network
+fn reachable_on_lan() -> bool {
+    settings::current().sidecar.listen_on_lan && sidecar::get_sidecar_socket().is_none()
+}
+
+/// Announce (or re-announce) this instance with the current backend port
+fn announce() -> Result<(), String> {
+    withdraw();
+
+    // Socket-only and loopback-only backends have nothing reachable to
+    // advertise
+    let port = sidecar::get_sidecar_port();
+    if port == 0 {
+        return Ok(());
+    }
+    if !reachable_on_lan() {
+        return Err("Not announcing on the local network: the backend only listens on this machine".to_string());
+    }
+
+    let instance = host_name();
+    let properties: HashMap<String, String> = HashMap::from([
+        ("version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        ("auth".to_string(), "token".to_string()),
+    ]);
+    let info = ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance,
+        &format!("{}.local.", instance),
+        "",
+        port,
+        Some(properties),
+    )
+    .map_err(|e| format!("Invalid mDNS service: {}", e))?
+    .enable_addr_auto();
+
+    with_daemon(|discovery| {
+        let fullname = info.get_fullname().to_string();
+        discovery
+            .daemon
+            .register(info)
+            .map_err(|e| format!("Failed to announce mDNS service: {}", e))?;
+        log::info!("Announcing {} on the local network", fullname);
+        discovery.announced = Some(fullname);
+        Ok(())
+    })
+}
+
+/// Announce when enabled, and keep the announcement in sync with the backend
+pub fn init(app: &AppHandle) {
+    app.listen_any("backend-ready", |_| {
+        if settings::current().discovery.enabled {
+            if let Err(e) = announce() {
+                log::warn!("{}", e);
+            }
+        }
+    });
+    app.listen_any("backend-terminated", |_| withdraw());
+}
+
+/// Enable or disable announcing this instance
+#[tauri::command]
+pub async fn set_discovery_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut current = settings::current();
+    current.discovery.enabled = enabled;
+    settings::save(&app, current)?;
+
+    if enabled {
+        announce()
+    } else {
+        withdraw();
+        Ok(())
+    }
+}
+
+/// Browse the local network for other mux instances
+#[tauri::command]
+pub async fn browse_devices(timeout_ms: Option<u64>) -> Result<Vec<DiscoveredDevice>, String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(3000).min(MAX_BROWSE_MS));
+    let own = with_daemon(|discovery| Ok(discovery.announced.clone()))?;
+    let receiver = with_daemon(|discovery| {
+        discovery
+            .daemon
+            .browse(SERVICE_TYPE)
+            .map_err(|e| format!("Failed to browse mDNS: {}", e))
+    })?;
+
+    let devices = tauri::async_runtime::spawn_blocking(move || {
+        let mut devices: HashMap<String, DiscoveredDevice> = HashMap::new();
+        let deadline = std::time::Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let event = match receiver.recv_timeout(remaining) {
+                Ok(event) => event,
+                Err(_) => break,
+            };
+
+            if let ServiceEvent::ServiceResolved(info) = event {
+                let name = info.get_fullname().to_string();
+                if own.as_deref() == Some(name.as_str()) {
+                    continue;
+                }
+
+                devices.insert(
+                    name.clone(),
+                    DiscoveredDevice {
+                        name,
+                        host: info.get_hostname().to_string(),
+                        addresses: info.get_addresses().iter().map(|a| a.to_string()).collect(),
+                        port: info.get_port(),
+                        properties: info
+                            .get_properties()
+                            .iter()
+                            .map(|p| (p.key().to_string(), p.val_str().to_string()))
+                            .collect(),
+                    },
+                );
+            }
+        }
+
+        devices
+    })
+    .await
+    .map_err(|e| format!("Browse task failed: {}", e))?;
+
+    let _ = with_daemon(|discovery| {
+        discovery
+            .daemon
+            .stop_browse(SERVICE_TYPE)
+            .map_err(|e| e.to_string())
+    });
+
+    Ok(devices.into_values().collect())
+}
@@ -0,0 +1,121 @@
+// Display enumeration
+//
+// Lists the connected displays with their geometry and scale so windows
+// can be placed on a chosen display, and lets saved window positions be
+// checked against the displays that are actually connected. Displays are
+// identified by their index in the system's monitor order.
+
+use tauri::{AppHandle, Monitor, PhysicalPosition, PhysicalSize, Window};
+
+/// A connected display, in physical pixels
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DisplayInfo {
+    pub index: usize,
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Area not covered by the taskbar, dock or menu bar
+    pub work_area: DisplayRect,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DisplayRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn same_monitor(a: &Monitor, b: &Monitor) -> bool {
+    a.name() == b.name() && a.position() == b.position()
+}
+
+fn monitors(app: &AppHandle) -> Result<Vec<Monitor>, String> {
+    app.available_monitors()
+        .map_err(|e| format!("Failed to list displays: {}", e))
+}
+
+/// Whether a physical point lies on a connected display
+pub(crate) fn is_on_screen(app: &AppHandle, x: i32, y: i32) -> bool {
+    monitors(app).unwrap_or_default().iter().any(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        x >= origin.x
+            && y >= origin.y
+            && x < origin.x + size.width as i32
+            && y < origin.y + size.height as i32
+    })
+}
+
+/// List connected displays
+#[tauri::command]
+pub async fn list_displays(app: AppHandle) -> Result<Vec<DisplayInfo>, String> {
+    let primary = app.primary_monitor().ok().flatten();
+    let displays = monitors(&app)?
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let work_area = monitor.work_area();
+            DisplayInfo {
+                index,
+                name: monitor.name().cloned(),
+                x: monitor.position().x,
+                y: monitor.position().y,
+                width: monitor.size().width,
+                height: monitor.size().height,
+                work_area: DisplayRect {
+                    x: work_area.position.x,
+                    y: work_area.position.y,
+                    width: work_area.size.width,
+                    height: work_area.size.height,
+                },
+                scale_factor: monitor.scale_factor(),
+                primary: primary.as_ref().is_some_and(|primary| same_monitor(primary, monitor)),
+            }
+        })
+        .collect();
+    Ok(displays)
+}
+
+/// Move the calling window to the center of a display's work area,
+/// shrinking it if it doesn't fit
+#[tauri::command]
+pub async fn move_window_to_display(app: AppHandle, window: Window, index: usize) -> Result<(), String> {
+    let monitors = monitors(&app)?;
+    let monitor = monitors
+        .get(index)
+        .ok_or_else(|| format!("No display {} ({} connected)", index, monitors.len()))?;
+    let area = monitor.work_area();
+
+    // Fullscreen and maximized windows can't be moved
+    if window.is_fullscreen().unwrap_or(false) {
+        window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        window.unmaximize().map_err(|e| e.to_string())?;
+    }
+
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let width = size.width.min(area.size.width);
+    let height = size.height.min(area.size.height);
+    if (width, height) != (size.width, size.height) {
+        window
+            .set_size(PhysicalSize::new(width, height))
+            .map_err(|e| e.to_string())?;
+    }
+    let x = area.position.x + (area.size.width - width) as i32 / 2;
+    let y = area.position.y + (area.size.height - height) as i32 / 2;
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| format!("Failed to move window: {}", e))?;
+
+    if maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,233 @@
+// Dock menu and badge count
+//
+// On macOS the dock icon gets a menu mirroring the tray's main actions,
+// and `set_badge_count` puts a count of unread agent results on the dock
+// tile. Windows has no badge API for desktop apps, so the same count is
+// drawn into a taskbar overlay icon; Linux launchers that support the
+// Unity launcher API show it natively. The count stays visible while the
+// window is minimized.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use tauri::{AppHandle, Manager};
+
+#[cfg(windows)]
+use tauri::image::Image;
+
+/// Overlay icon size; Windows scales it to the taskbar's overlay slot
+#[cfg(windows)]
+const OVERLAY_SIZE: i64 = 32;
+
+/// Badge colors (RGBA)
+#[cfg(windows)]
+const OVERLAY_BACKGROUND: [u8; 4] = [0xe5, 0x48, 0x4d, 0xff];
+#[cfg(windows)]
+const OVERLAY_TEXT: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+/// 3x5 bitmaps for the digits and '+', one row per byte (low 3 bits)
+#[cfg(windows)]
+const GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b010, 0b111, 0b010, 0b000],
+];
+
+/// Draw a count (9+ above nine) on a red disc
+#[cfg(windows)]
+fn overlay_icon(count: u32) -> Image<'static> {
+    let size = OVERLAY_SIZE;
+    let mut rgba = vec![0u8; (size * size * 4) as usize];
+    let mut paint = |x: i64, y: i64, color: [u8; 4]| {
+        if (0..size).contains(&x) && (0..size).contains(&y) {
+            let offset = ((y * size + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&color);
+        }
+    };
+
+    let radius = size / 2;
+    for y in 0..size {
+        for x in 0..size {
+            if (x - radius).pow(2) + (y - radius).pow(2) < radius.pow(2) {
+                paint(x, y, OVERLAY_BACKGROUND);
+            }
+        }
+    }
+
+    let glyphs: Vec<usize> = if count > 9 { vec![9, 10] } else { vec![count as usize] };
+    let scale = if glyphs.len() == 1 { 4 } else { 3 };
+    let width = glyphs.len() as i64 * 4 * scale - scale;
+    let (left, top) = ((size - width) / 2, (size - 5 * scale) / 2);
+    for (index, glyph) in glyphs.iter().enumerate() {
+        let origin = left + index as i64 * 4 * scale;
+        for (row, bits) in GLYPHS[*glyph].iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        paint(origin + column * scale + dx, top + row as i64 * scale + dy, OVERLAY_TEXT);
+                    }
+                }
+            }
+        }
+    }
+    Image::new_owned(rgba, size as u32, size as u32)
+}
+
+/// Count currently shown on the badge, 0 for none
+static BADGE_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Count currently shown on the badge
+pub fn badge_count() -> Option<u32> {
+    Some(BADGE_COUNT.load(Ordering::SeqCst)).filter(|count| *count > 0)
+}
+
+/// Show a count of unread agent results on the dock tile (macOS, Linux) or
+/// taskbar button (Windows); 0 or `None` clears it
+#[tauri::command]
+pub async fn set_badge_count(app: AppHandle, count: Option<u32>) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+    let count = count.filter(|count| *count > 0);
+
+    #[cfg(windows)]
+    let result = window
+        .set_overlay_icon(count.map(overlay_icon))
+        .map_err(|e| format!("Failed to set taskbar badge: {}", e));
+
+    #[cfg(not(windows))]
+    let result = window
+        .set_badge_count(count.map(i64::from))
+        .map_err(|e| format!("Failed to set badge count: {}", e));
+
+    if result.is_ok() {
+        BADGE_COUNT.store(count.unwrap_or(0), Ordering::SeqCst);
+    }
+    result
+}
+
+#[cfg(target_os = "macos")]
+mod menu {
+    use std::sync::OnceLock;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Imp, NSObject, Sel};
+    use objc2::{define_class, msg_send, sel, MainThreadMarker, MainThreadOnly};
+    use objc2_app_kit::{NSApplication, NSMenu, NSMenuItem};
+    use objc2_foundation::NSString;
+    use tauri::{AppHandle, Emitter, Manager};
+
+    /// App handle for routing dock menu choices
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    /// The dock menu, kept for the life of the app (AppKit objects aren't
+    /// `Send`, so it is stored as an address)
+    static MENU: OnceLock<usize> = OnceLock::new();
+
+    define_class!(
+        // Target of the dock menu items
+        #[unsafe(super(NSObject))]
+        #[thread_kind = MainThreadOnly]
+        #[name = "MupDockMenuTarget"]
+        struct DockMenuTarget;
+
+        impl DockMenuTarget {
+            #[unsafe(method(newChat:))]
+            fn new_chat(&self, _sender: Option<&AnyObject>) {
+                activate("tray-new-chat");
+            }
+
+            #[unsafe(method(openSettings:))]
+            fn open_settings(&self, _sender: Option<&AnyObject>) {
+                activate("tray-open-settings");
+            }
+        }
+    );
+
+    /// Bring the main window forward and tell the frontend what was chosen,
+    /// the same way the tray does
+    fn activate(event: &str) {
+        let Some(app) = APP.get() else {
+            return;
+        };
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        if let Err(e) = app.emit(event, ()) {
+            log::error!("Failed to emit {} event: {}", event, e);
+        }
+    }
+
+    /// `applicationDockMenu:`, added to the app delegate
+    unsafe extern "C-unwind" fn dock_menu(_this: &AnyObject, _cmd: Sel, _sender: &AnyObject) -> *mut NSMenu {
+        MENU.get().map(|menu| *menu as *mut NSMenu).unwrap_or(std::ptr::null_mut())
+    }
+
+    fn build(mtm: MainThreadMarker) -> Retained<NSMenu> {
+        let target: Retained<DockMenuTarget> = unsafe { msg_send![DockMenuTarget::alloc(mtm), init] };
+        let menu = NSMenu::new(mtm);
+        for (title, action) in [("New Chat", sel!(newChat:)), ("Settings", sel!(openSettings:))] {
+            let item = unsafe {
+                NSMenuItem::initWithTitle_action_keyEquivalent(
+                    NSMenuItem::alloc(mtm),
+                    &NSString::from_str(title),
+                    Some(action),
+                    &NSString::from_str(""),
+                )
+            };
+            let target: &AnyObject = &target;
+            unsafe { item.setTarget(Some(target)) };
+            menu.addItem(&item);
+        }
+        // Menu items hold their target weakly; it lives as long as the app
+        std::mem::forget(target);
+        menu
+    }
+
+    /// Install the dock menu; must run on the main thread
+    pub fn init(app: &AppHandle) {
+        let _ = APP.set(app.clone());
+
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::warn!("Dock menu not installed: not on the main thread");
+            return;
+        };
+        let application = NSApplication::sharedApplication(mtm);
+        let Some(delegate) = (unsafe { application.delegate() }) else {
+            log::warn!("Dock menu not installed: no app delegate");
+            return;
+        };
+
+        let menu = build(mtm);
+        let _ = MENU.set(Retained::into_raw(menu) as usize);
+
+        // Tauri owns the delegate class; add the dock menu method to it
+        let delegate: &AnyObject = (*delegate).as_ref();
+        let class: &AnyClass = delegate.class();
+        let imp: Imp = unsafe { std::mem::transmute(dock_menu as unsafe extern "C-unwind" fn(_, _, _) -> _) };
+        let added = unsafe {
+            objc2::ffi::class_addMethod(
+                class as *const AnyClass as *mut AnyClass,
+                sel!(applicationDockMenu:),
+                imp,
+                c"@@:@".as_ptr(),
+            )
+        };
+        if !added.as_bool() {
+            log::warn!("Dock menu not installed: the app delegate already provides one");
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub use menu::init;
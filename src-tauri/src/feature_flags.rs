@@ -0,0 +1,203 @@
+// Feature flags
+//
+// App-side flags let risky subsystems ship dark and be enabled gradually.
+// A flag's value comes from, in order of precedence:
+//   1. the environment: MUX_APP_FEATURES="flag_a,-flag_b" (a leading `-`
+//      disables a flag)
+//   2. overrides in settings
+//   3. an optional remote manifest (`{ "flags": { "<flag>": true } }`),
+//      fetched at startup and hourly; the last one fetched is kept on disk
+//      so flags checked during startup already reflect it
+//   4. built-in defaults; unknown flags are off
+// `feature-flags-changed` is emitted with all known flags whenever the
+// effective values change.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use crate::settings;
+
+/// Environment variable listing flag overrides
+const ENV_VAR: &str = "MUX_APP_FEATURES";
+
+/// Last fetched manifest, in the app's local data directory
+const CACHE_FILE: &str = "feature-flags.json";
+
+/// How often the remote manifest is refreshed
+const MANIFEST_REFRESH: Duration = Duration::from_secs(60 * 60);
+
+/// Built-in defaults for known flags
+const DEFAULTS: &[(&str, bool)] = &[
+    // Remote access tunnel (existing installs keep it available)
+    ("remote_access", true),
+];
+
+/// Feature flag settings, persisted in app settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FeatureFlagSettings {
+    /// Local overrides, taking precedence over the manifest and defaults
+    pub overrides: HashMap<String, bool>,
+    /// URL of a JSON flag manifest
+    pub manifest_url: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    flags: HashMap<String, bool>,
+}
+
+/// Flags from the remote manifest
+static REMOTE: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
+
+/// Effective flags as last emitted
+static LAST_EMITTED: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
+
+/// Flags set through the environment
+fn env_flags() -> HashMap<String, bool> {
+    std::env::var(ENV_VAR)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|flag| !flag.is_empty())
+        .map(|flag| match flag.strip_prefix('-') {
+            Some(flag) => (flag.to_string(), false),
+            None => (flag.to_string(), true),
+        })
+        .collect()
+}
+
+/// All known flags with their effective values
+fn effective_flags() -> HashMap<String, bool> {
+    let mut flags: HashMap<String, bool> = DEFAULTS
+        .iter()
+        .map(|(flag, enabled)| (flag.to_string(), *enabled))
+        .collect();
+    if let Ok(guard) = REMOTE.lock() {
+        flags.extend(guard.iter().flatten().map(|(flag, enabled)| (flag.clone(), *enabled)));
+    }
+    flags.extend(settings::current().feature_flags.overrides);
+    flags.extend(env_flags());
+    flags
+}
+
+/// Whether a flag is enabled
+pub fn is_on(flag: &str) -> bool {
+    effective_flags().get(flag).copied().unwrap_or(false)
+}
+
+/// Emit `feature-flags-changed` if the effective flags changed
+fn emit_if_changed(app: &AppHandle) {
+    let flags = effective_flags();
+    let Ok(mut last) = LAST_EMITTED.lock() else {
+        return;
+    };
+    if last.as_ref() == Some(&flags) {
+        return;
+    }
+    *last = Some(flags.clone());
+    if let Err(e) = app.emit("feature-flags-changed", flags) {
+        log::error!("Failed to emit feature-flags-changed event: {}", e);
+    }
+}
+
+fn cache_path(app: &AppHandle) -> Option<std::path::PathBuf> {
+    app.path().app_local_data_dir().ok().map(|dir| dir.join(CACHE_FILE))
+}
+
+/// Load the manifest fetched on a previous run
+fn load_cached(app: &AppHandle) -> Option<Manifest> {
+    let contents = std::fs::read_to_string(cache_path(app)?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_cached(app: &AppHandle, manifest: &Manifest) {
+    let Some(path) = cache_path(app) else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|()| serde_json::to_string(manifest).map_err(|e| e.to_string()))
+        .and_then(|contents| std::fs::write(&path, contents).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to cache flag manifest: {}", e);
+    }
+}
+
+/// Fetch the remote manifest, if one is configured
+async fn refresh_manifest(app: &AppHandle) {
+    let Some(url) = settings::current().feature_flags.manifest_url else {
+        return;
+    };
+
+    let result = async {
+        reqwest::Client::new()
+            .get(&url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to fetch flag manifest: {}", e))?
+            .json::<Manifest>()
+            .await
+            .map_err(|e| format!("Invalid flag manifest: {}", e))
+    }
+    .await;
+
+    match result {
+        Ok(manifest) => {
+            save_cached(app, &manifest);
+            if let Ok(mut guard) = REMOTE.lock() {
+                *guard = Some(manifest.flags);
+            }
+            emit_if_changed(app);
+        }
+        // Keep the last known manifest; flags fall back to defaults otherwise
+        Err(e) => log::warn!("{}", e),
+    }
+}
+
+/// Load the remote manifest and report flag changes
+///
+/// The cached manifest is applied before this returns, so run it before
+/// anything that checks flags at startup.
+pub fn init(app: &AppHandle) {
+    if settings::current().feature_flags.manifest_url.is_some() {
+        if let Some(manifest) = load_cached(app) {
+            if let Ok(mut guard) = REMOTE.lock() {
+                *guard = Some(manifest.flags);
+            }
+        }
+    }
+    if let Ok(mut last) = LAST_EMITTED.lock() {
+        *last = Some(effective_flags());
+    }
+
+    let app_handle = app.clone();
+    app.listen_any("settings-changed", move |_| emit_if_changed(&app_handle));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh_manifest(&app).await;
+            tokio::time::sleep(MANIFEST_REFRESH).await;
+        }
+    });
+}
+
+/// Check whether a feature flag is enabled
+#[tauri::command]
+pub async fn is_enabled(flag: String) -> Result<bool, String> {
+    Ok(is_on(&flag))
+}
+
+/// Get all known feature flags with their effective values
+#[tauri::command]
+pub async fn get_feature_flags() -> Result<HashMap<String, bool>, String> {
+    Ok(effective_flags())
+}
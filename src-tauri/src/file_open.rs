@@ -0,0 +1,99 @@
+// .mux file handling
+//
+// Exported sessions are saved as .mux files, and the bundle registers the
+// app to open them. A file can arrive as a launch argument (cold start on
+// Windows and Linux), from a second launch forwarded by the running
+// instance, or through an open-documents event on macOS. Its manifest is
+// read and checked without unpacking anything, and the result is emitted
+// as `mux:open-file`; the frontend imports the bundle and reveals where it
+// was unpacked. Files opened before the frontend is listening are queued
+// until it drains them.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::session_bundle::{self, BundleManifest};
+
+/// Extension of session files
+const FILE_EXTENSION: &str = "mux";
+
+/// Files opened before the frontend could handle them, oldest first
+static PENDING: Mutex<Vec<OpenFilePayload>> = Mutex::new(Vec::new());
+
+/// Set once the frontend has drained the queue
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+
+/// Payload of the `mux:open-file` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OpenFilePayload {
+    pub path: PathBuf,
+    /// Bundle contents, when the file is a valid session bundle
+    pub manifest: Option<BundleManifest>,
+    /// Why the file can't be imported
+    pub error: Option<String>,
+}
+
+/// Whether a launch argument names a session file
+pub fn is_session_file(arg: &str) -> bool {
+    !arg.contains("://")
+        && Path::new(arg)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(FILE_EXTENSION))
+}
+
+/// Read a session file and route it to the frontend, queueing it if the
+/// frontend isn't ready yet
+pub fn open(app: &AppHandle, path: PathBuf) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let source = path.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || session_bundle::read_manifest(&source))
+            .await
+            .unwrap_or_else(|e| Err(format!("Failed to read file: {}", e)));
+
+        let payload = match result {
+            Ok(manifest) => OpenFilePayload {
+                path,
+                manifest: Some(manifest),
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("Can't open {}: {}", path.display(), e);
+                OpenFilePayload {
+                    path,
+                    manifest: None,
+                    error: Some(e),
+                }
+            }
+        };
+
+        // Checked under the queue lock so a concurrent drain can't miss it
+        if let Ok(mut pending) = PENDING.lock() {
+            if !FRONTEND_READY.load(Ordering::SeqCst) {
+                pending.push(payload);
+                return;
+            }
+        }
+        if let Err(e) = app.emit("mux:open-file", payload) {
+            log::error!("Failed to emit open-file event: {}", e);
+        }
+    });
+}
+
+/// Open session files the app was launched with
+pub fn init(app: &AppHandle) {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    for arg in std::env::args().skip(1).filter(|arg| is_session_file(arg)) {
+        open(app, cwd.join(arg));
+    }
+}
+
+/// Take session files opened before the frontend was ready
+#[tauri::command]
+pub async fn drain_pending_open_files() -> Result<Vec<OpenFilePayload>, String> {
+    let mut pending = PENDING.lock().map_err(|e| e.to_string())?;
+    FRONTEND_READY.store(true, Ordering::SeqCst);
+    Ok(std::mem::take(&mut *pending))
+}
@@ -0,0 +1,208 @@
+// Aggregate application health
+//
+// Combines backend health, updater state, and disk space into a single
+// structure for the UI status bar, and emits `app-health-changed` whenever
+// a component's level changes so the frontend doesn't need to follow each
+// subsystem's own events. Details such as free disk space change on every
+// poll and don't trigger the event on their own.
+
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::storage::{self, DiskSpace};
+use crate::updater::{self, UpdateStatus};
+use crate::{backend_version, power, profiles, sidecar};
+
+/// How often health is re-evaluated
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Attempts, and the delay between them, when re-checking the backend
+/// after the system wakes
+const REVERIFY_ATTEMPTS: u32 = 5;
+const REVERIFY_DELAY: Duration = Duration::from_secs(2);
+
+/// Free space below which disk health is degraded
+const LOW_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Free space below which disk health is down
+const CRITICAL_DISK_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Health of a single component, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthLevel {
+    Ok,
+    Unknown,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BackendHealth {
+    pub level: HealthLevel,
+    pub port: Option<u16>,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct UpdaterHealth {
+    pub level: HealthLevel,
+    pub status: Option<UpdateStatus>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DiskHealth {
+    pub level: HealthLevel,
+    pub space: Option<DiskSpace>,
+}
+
+/// Aggregated health reported to the frontend
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct AppHealth {
+    pub overall: HealthLevel,
+    pub backend: BackendHealth,
+    pub updater: UpdaterHealth,
+    pub disk: DiskHealth,
+}
+
+impl AppHealth {
+    /// Levels the change event is based on
+    fn levels(&self) -> [HealthLevel; 4] {
+        [self.overall, self.backend.level, self.updater.level, self.disk.level]
+    }
+}
+
+static LAST_HEALTH: OnceLock<RwLock<Option<AppHealth>>> = OnceLock::new();
+
+fn health_cell() -> &'static RwLock<Option<AppHealth>> {
+    LAST_HEALTH.get_or_init(|| RwLock::new(None))
+}
+
+pub(crate) async fn backend_health() -> BackendHealth {
+    if !sidecar::is_backend_ready() {
+        return BackendHealth {
+            level: HealthLevel::Down,
+            port: None,
+            version: None,
+        };
+    }
+
+    let healthy = sidecar::check_backend_health().await.unwrap_or(false);
+    let port = Some(sidecar::get_sidecar_port()).filter(|p| *p != 0);
+    let version = backend_version::get_backend_version().await.ok();
+
+    let level = match (&version, healthy) {
+        (_, false) => HealthLevel::Down,
+        (Some(v), true) if !v.compatible => HealthLevel::Degraded,
+        (_, true) => HealthLevel::Ok,
+    };
+
+    BackendHealth {
+        level,
+        port,
+        version: version.and_then(|v| v.version),
+    }
+}
+
+fn updater_health() -> UpdaterHealth {
+    let status = updater::last_status();
+    // No status until the first check, which is fine
+    let level = match status {
+        Some(UpdateStatus::Error { .. }) => HealthLevel::Degraded,
+        _ => HealthLevel::Ok,
+    };
+
+    UpdaterHealth { level, status }
+}
+
+fn disk_health(app: &AppHandle) -> DiskHealth {
+    let space = profiles::data_dir(app)
+        .and_then(|dir| storage::disk_space(&dir))
+        .ok();
+
+    let level = match space {
+        Some(space) if space.available_bytes < CRITICAL_DISK_BYTES => HealthLevel::Down,
+        Some(space) if space.available_bytes < LOW_DISK_BYTES => HealthLevel::Degraded,
+        Some(_) => HealthLevel::Ok,
+        None => HealthLevel::Unknown,
+    };
+
+    DiskHealth { level, space }
+}
+
+/// Evaluate every component
+async fn evaluate(app: &AppHandle) -> AppHealth {
+    let backend = backend_health().await;
+    let updater = updater_health();
+    let disk = disk_health(app);
+
+    let overall = [backend.level, updater.level, disk.level]
+        .into_iter()
+        .max()
+        .unwrap_or(HealthLevel::Unknown);
+
+    AppHealth {
+        overall,
+        backend,
+        updater,
+        disk,
+    }
+}
+
+/// Store the new health and emit a change event if a level differs
+fn publish(app: &AppHandle, health: &AppHealth) {
+    let changed = match health_cell().write() {
+        Ok(mut guard) => {
+            let changed = guard.as_ref().map(AppHealth::levels) != Some(health.levels());
+            *guard = Some(health.clone());
+            changed
+        }
+        Err(_) => false,
+    };
+
+    if changed {
+        if let Err(e) = app.emit("app-health-changed", health) {
+            log::error!("Failed to emit app-health-changed event: {}", e);
+        }
+    }
+}
+
+/// Start the background health monitor
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Checks made while the system sleeps or has just woken fail
+            // spuriously; `power` re-verifies once it has settled
+            if !power::checks_paused() {
+                let health = evaluate(&app).await;
+                publish(&app, &health);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Re-check health after the system wakes, giving the backend a few
+/// chances to answer before reporting it down
+pub async fn reverify(app: &AppHandle) {
+    let mut health = evaluate(app).await;
+    for _ in 1..REVERIFY_ATTEMPTS {
+        if health.backend.level != HealthLevel::Down || !sidecar::is_backend_ready() {
+            break;
+        }
+        tokio::time::sleep(REVERIFY_DELAY).await;
+        health = evaluate(app).await;
+    }
+    publish(app, &health);
+}
+
+/// Get the current aggregated health
+#[tauri::command]
+pub async fn get_app_health(app: AppHandle) -> Result<AppHealth, String> {
+    let health = evaluate(&app).await;
+    publish(&app, &health);
+    Ok(health)
+}
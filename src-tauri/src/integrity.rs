@@ -0,0 +1,102 @@
+// Sidecar binary integrity
+//
+// Checksums of the bundled backend binaries are embedded at build time (see
+// build.rs). Before spawning, the resolved binary is hashed and compared so
+// a tampered binary, or one truncated by a failed update, is never run.
+
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+include!(concat!(env!("OUT_DIR"), "/sidecar_checksums.rs"));
+
+/// Target triple this app was built for
+const BUILD_TARGET: &str = env!("MUP_BUILD_TARGET");
+
+/// Payload of the `backend-integrity-failed` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IntegrityFailedPayload {
+    pub path: PathBuf,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Expected checksum for a binary, if one was embedded
+///
+/// Tauri bundles the build target's binary without its triple suffix.
+fn expected_checksum(path: &Path, base_name: &str) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    let plain = format!("{}{}", base_name, std::env::consts::EXE_SUFFIX);
+    let key = if name == plain {
+        format!("{}-{}{}", base_name, BUILD_TARGET, std::env::consts::EXE_SUFFIX)
+    } else {
+        name.to_string()
+    };
+
+    SIDECAR_CHECKSUMS
+        .iter()
+        .find(|(file, _)| *file == key)
+        .map(|(_, checksum)| *checksum)
+}
+
+/// SHA-256 of a file as lowercase hex
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+
+    loop {
+        let read = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Verify a bundled binary against its embedded checksum
+///
+/// Binaries without an embedded checksum are only allowed in debug builds,
+/// where the backend is often rebuilt after the app.
+pub fn verify_binary(app: &AppHandle, path: &Path, base_name: &str) -> Result<(), String> {
+    let expected = match expected_checksum(path, base_name) {
+        Some(expected) => expected,
+        None if cfg!(debug_assertions) => {
+            log::warn!("No embedded checksum for {}; skipping verification", path.display());
+            return Ok(());
+        }
+        None => {
+            log::error!("No embedded checksum for backend binary {}", path.display());
+            return Err("The backend binary is not one this app was built with. Reinstall the app.".to_string());
+        }
+    };
+
+    let actual = sha256_file(path)?;
+    if actual == expected {
+        return Ok(());
+    }
+
+    log::error!(
+        "Backend binary {} failed integrity check (expected {}, got {})",
+        path.display(),
+        expected,
+        actual
+    );
+
+    let payload = IntegrityFailedPayload {
+        path: path.to_path_buf(),
+        expected: expected.to_string(),
+        actual,
+    };
+    if let Err(e) = app.emit("backend-integrity-failed", payload) {
+        log::error!("Failed to emit backend-integrity-failed event: {}", e);
+    }
+
+    Err("The backend binary is damaged or has been modified. Reinstall the app.".to_string())
+}
@@ -1,48 +1,279 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod app_windows;
+mod asset_protocol;
+mod backend_data;
+mod backend_events;
+mod backend_version;
+pub mod cli_ipc;
+mod clock;
+mod command_history;
+mod command_metrics;
 mod commands;
+mod crash_loop;
 mod deeplink;
+mod deeplink_audit;
+mod deeplink_callback;
+mod discovery;
+mod displays;
+mod dock;
+mod feature_flags;
+mod file_open;
+mod health;
+mod integrity;
+mod logging;
+mod memory_pressure;
+#[cfg(target_os = "macos")]
+mod macos_services;
+mod native_control;
+mod notifications;
+mod offline_update;
+mod orphan;
+mod overlay;
 mod orpc_bridge;
+mod orpc_metrics;
+mod orpc_queue;
+mod power;
+mod process_env;
+mod process_tree;
+mod profiles;
+mod prompt;
+mod protocol_handler;
+mod qr;
+mod resources;
+mod release_notes;
+mod remote_access;
+mod retention;
+mod sanitize;
+mod session_bundle;
+mod session_journal;
+mod settings;
+mod shutdown;
+mod snapshot;
 mod sidecar;
+mod sidecar_registry;
+mod sidecar_update;
+mod single_instance;
+mod storage;
 mod terminal;
+mod toast_activation;
 mod tray;
+mod tray_status;
+mod update_hooks;
+mod update_policy;
+mod update_proxy;
+mod update_rollback;
+mod update_telemetry;
 mod updater;
+mod version;
+#[cfg(windows)]
+mod win_registry;
+mod window_effects;
+mod window_state;
 
 use tauri::{Emitter, Manager};
 
+/// Payload of the `scale-factor-changed` event (size in physical pixels)
+#[derive(Clone, serde::Serialize)]
+struct ScaleFactorPayload {
+    label: String,
+    scale_factor: f64,
+    width: u32,
+    height: u32,
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize logger
-    env_logger::init();
+    logging::init();
+    
+    // After a profile switch, let the previous instance release the
+    // single-instance lock first
+    single_instance::wait_for_previous_instance();
+    
+    let mut context = tauri::generate_context!();
+    update_policy::apply_install_mode(context.config_mut());
     
     tauri::Builder::default()
+        // Must come first so a second launch exits before anything else starts
+        .plugin(single_instance::plugin())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .register_asynchronous_uri_scheme_protocol(asset_protocol::SCHEME, asset_protocol::handle)
         .setup(|app| {
-            // Initialize the system tray (non-blocking - don't fail if tray fails)
-            if let Err(e) = tray::create_tray(app.handle()) {
-                eprintln!("Warning: Failed to create system tray: {}", e);
+            // Select the profile before resolving any data paths
+            if let Err(e) = profiles::init(app.handle()) {
+                eprintln!("Warning: Failed to initialize profiles: {}", e);
+            }
+            
+            // The main window isn't created from the config, so its
+            // webview storage can follow the profile
+            if let Some(config) = app.config().app.windows.iter().find(|w| w.label == "main").cloned() {
+                let builder = tauri::WebviewWindowBuilder::from_config(app.handle(), &config)?;
+                profiles::with_webview_storage(app.handle(), builder).build()?;
             }
             
-            // Spawn the backend sidecar process
-            if let Err(e) = sidecar::spawn_sidecar(app.handle()) {
-                eprintln!("Failed to spawn backend sidecar: {}", e);
-                // Don't fail startup - frontend can handle missing backend gracefully
+            // Load persisted settings before anything reads them
+            if let Err(e) = settings::init(app.handle()) {
+                eprintln!("Warning: Failed to load settings: {}", e);
+            }
+            logging::apply_saved();
+            
+            // After repeated failed startups, skip saved sidecar options
+            // and optional subsystems
+            let safe_mode = crash_loop::begin(app.handle());
+            
+            // Resolve feature flags before gated subsystems start
+            feature_flags::init(app.handle());
+            
+            // Record commands run in terminals for history search
+            command_history::init(app.handle());
+            
+            // Pick up an update downloaded in an earlier session, then
+            // check for new ones in the background
+            updater::init(app.handle());
+            updater::schedule_checks(app.handle());
+            
+            // Recover session state, replaying the journal after a crash
+            session_journal::init(app.handle());
+            
+            // Put the main window back where it was before an update restart
+            window_state::restore(app.handle());
+            
+            if !safe_mode {
+                // Announce on the local network when enabled (before the
+                // backend can report ready)
+                discovery::init(app.handle());
+                
+                // Keep a remote access tunnel pointed at the current backend
+                // port, while the remote_access flag allows it
+                remote_access::init(app.handle());
             }
             
+            // Relay backend push events to the frontend
+            backend_events::init(app.handle());
+            
+            // Show backend progress toasts in the activity overlay
+            overlay::init(app.handle());
+            
+            // Replay calls queued while the backend was down
+            orpc_queue::init(app.handle());
+            
+            // Record received deep links for review
+            deeplink_audit::init(app.handle());
+            
+            // Accept requests from the companion CLI
+            cli_ipc::init(app.handle());
+            
+            // Queue deep links from notification activation (cold launch)
+            toast_activation::init(app.handle());
+            
+            // Queue .mux session files the app was launched to open
+            file_open::init(app.handle());
+            
+            // Route "Send selection to MUP" from the Services menu into
+            // new chats
+            #[cfg(target_os = "macos")]
+            macos_services::init(app.handle());
+            
+            // Mirror the tray's main actions in the dock menu
+            #[cfg(target_os = "macos")]
+            dock::init(app.handle());
+            
+            // Initialize the system tray (non-blocking - reports
+            // `tray-unavailable` and retries on Linux if it can't be shown)
+            tray::init(app.handle());
+            
+            // Badge the tray icon when the backend stops or an update is ready
+            tray_status::init(app.handle());
+            
+            // Let the backend call back into native actions (before the
+            // sidecar is spawned so it receives the endpoint)
+            if let Err(e) = native_control::init(app.handle()) {
+                eprintln!("Warning: Failed to start native control endpoint: {}", e);
+            }
+            
+            // Adopt or clean up a sidecar that survived a previous crash
+            if let Err(e) = orphan::init(app.handle()) {
+                eprintln!("Warning: Failed to resolve sidecar PID file: {}", e);
+            }
+            // Recovery checks the old backend over HTTP, so it runs on the
+            // async runtime instead of holding up setup
+            let recover_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if orphan::recover(&recover_handle).await {
+                    return;
+                }
+                
+                // Spawn the backend sidecar process
+                let config = if safe_mode {
+                    sidecar::SidecarConfig::default()
+                } else {
+                    settings::current().sidecar
+                };
+                // Spawning blocks on the process handle lock, so it can't
+                // run on a runtime worker
+                let spawned = tauri::async_runtime::spawn_blocking(move || {
+                    sidecar::spawn_sidecar(&recover_handle, &config)
+                })
+                .await
+                .map_err(|e| format!("Spawn task failed: {}", e))
+                .and_then(|result| result);
+                if let Err(e) = spawned {
+                    eprintln!("Failed to spawn backend sidecar: {}", e);
+                    crash_loop::record_failure(format!("Failed to spawn backend: {}", e));
+                    // Don't fail startup - frontend can handle missing backend gracefully
+                }
+            });
+            
+            // Start auxiliary sidecars (indexers, local model runners, ...)
+            if !safe_mode {
+                sidecar_registry::start_all(app.handle());
+                
+                // Pre-spawn shells so the first terminal opens instantly
+                terminal::refill_pool();
+            }
+            
+            // Enforce data retention periodically
+            retention::start_cleanup_job(app.handle());
+            
+            // Track aggregate health for the status bar
+            health::start_monitor(app.handle());
+            
+            // Pause health checks across sleep and re-verify on wake
+            // (registers for OS notifications, so runs on the main thread)
+            power::init(app.handle());
+            
+            // Watch the sidecar for runaway CPU and memory usage
+            resources::start_monitor(app.handle());
+            
+            // Release idle shells and caches when system memory runs low
+            memory_pressure::init(app.handle());
+            
+            // Warn early about clock skew that would break TLS and signatures
+            clock::start_check(app.handle());
+            
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(command_metrics::with_metrics(tauri::generate_handler![
             // System info commands
             commands::get_system_info,
+            commands::get_gpu_info,
+            process_env::get_process_env,
             // Window management commands
             commands::minimize_window,
             commands::toggle_maximize_window,
+            commands::set_always_on_top,
+            commands::toggle_fullscreen,
             commands::close_window,
             commands::focus_window,
             commands::hide_window,
             commands::show_window,
             commands::get_window_state,
+            window_effects::set_window_effect,
+            window_effects::set_window_opacity,
             // IPC bridge commands
             commands::start_orpc_server,
             // Terminal commands
@@ -50,28 +281,204 @@ pub fn run() {
             terminal::terminal_write,
             terminal::terminal_read,
             terminal::terminal_resize,
+            terminal::terminal_is_idle,
+            terminal::terminal_bulk,
             terminal::terminal_close,
+            command_history::search_command_history,
             // oRPC bridge commands
             orpc_bridge::forward_orpc_call,
+            orpc_bridge::forward_orpc_stream,
+            orpc_bridge::forward_orpc_batch,
+            orpc_bridge::forward_http_request,
+            orpc_bridge::cancel_orpc_call,
+            orpc_bridge::orpc_upload,
+            orpc_bridge::orpc_download,
+            orpc_bridge::orpc_multipart,
             orpc_bridge::check_orpc_server,
+            orpc_metrics::get_orpc_metrics,
             // Sidecar commands
             sidecar::get_backend_port,
             sidecar::check_backend_health,
+            sidecar::restart_backend,
+            backend_version::get_backend_version,
+            backend_data::migrate_backend_data,
+            resources::get_backend_resource_usage,
+            sidecar_registry::list_sidecars,
+            sidecar_registry::start_named_sidecar,
+            sidecar_registry::stop_named_sidecar,
+            sidecar_registry::check_sidecar_health,
             // Updater commands
             updater::check_for_updates,
-            updater::install_update,
+            updater::download_update,
+            updater::pause_update_download,
+            updater::resume_update_download,
+            updater::install_downloaded_update,
+            updater::restart_app,
+            offline_update::install_update_from_file,
+            updater::skip_update_version,
+            updater::snooze_update,
+            update_rollback::get_rollback_version,
+            update_rollback::rollback_update,
+            sidecar_update::update_sidecar,
             updater::get_app_version,
+            release_notes::get_release_notes,
+            update_telemetry::get_update_telemetry,
+            update_policy::get_update_policy,
             // Deep link commands
             deeplink::handle_deep_link,
-        ])
-        .on_window_event(|window, event| {
-            // Handle window close - terminate sidecar
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                // Signal sidecar termination (async, non-blocking)
-                let _ = window.app_handle().emit("app-closing", ());
+            deeplink::drain_pending_deep_links,
+            deeplink::parse_deep_link_preview,
+            deeplink_callback::complete_deep_link,
+            deeplink_audit::get_deep_link_history,
+            file_open::drain_pending_open_files,
+            protocol_handler::register_protocol_handler,
+            protocol_handler::is_protocol_registered,
+            // Settings commands
+            settings::get_settings,
+            settings::update_settings,
+            // Profile commands
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
+            // Health commands
+            health::get_app_health,
+            // Snapshot commands
+            snapshot::get_full_state_snapshot,
+            // Session journal commands
+            session_journal::get_recovered_session,
+            session_journal::set_session_project,
+            // Storage commands
+            storage::check_disk_space,
+            // Retention commands
+            retention::purge_now,
+            // Session bundle commands
+            session_bundle::export_session_bundle,
+            session_bundle::import_session_bundle,
+            // Discovery commands
+            discovery::set_discovery_enabled,
+            discovery::browse_devices,
+            // Remote access commands
+            remote_access::start_remote_access,
+            remote_access::stop_remote_access,
+            remote_access::get_remote_access_status,
+            // QR code commands
+            qr::generate_qr,
+            // Sanitization commands
+            sanitize::sanitize_html,
+            // Logging commands
+            logging::set_log_level,
+            // Asset protocol commands
+            asset_protocol::allow_asset_root,
+            asset_protocol::revoke_asset_root,
+            // Tray commands
+            tray::is_tray_available,
+            tray::update_tray_menu,
+            tray::set_tray_quick_actions,
+            tray_status::set_tray_status,
+            tray_status::set_tray_progress,
+            dock::set_badge_count,
+            // Display commands
+            displays::list_displays,
+            displays::move_window_to_display,
+            // App window commands
+            app_windows::create_app_window,
+            app_windows::list_app_windows,
+            app_windows::close_app_window,
+            // Recovery commands
+            crash_loop::get_recovery_state,
+            crash_loop::reset_recovery_state,
+            // Feature flag commands
+            feature_flags::is_enabled,
+            feature_flags::get_feature_flags,
+            // Overlay commands
+            overlay::show_overlay_toast,
+            overlay::dismiss_overlay_toast,
+            overlay::get_overlay_toasts,
+            // Diagnostics commands
+            command_metrics::get_command_metrics,
+            command_metrics::record_command_timings,
+            memory_pressure::get_memory_pressure,
+        ]))
+        .on_window_event(|window, event| match event {
+            // Handle main window close - hide to the tray, or quit through
+            // the shutdown coordinator (detached windows handle their own
+            // close)
+            tauri::WindowEvent::CloseRequested { api, .. } if window.label() == "main" => {
+                api.prevent_close();
+                if !tray::hide_on_close(window) {
+                    shutdown::quit(window.app_handle());
+                }
+            }
+            // Redraw the tray icon for the new light/dark theme
+            tauri::WindowEvent::ThemeChanged(_) => tray_status::apply(window.app_handle()),
+            // Minimizing reports a resize; hide to the tray if enabled
+            tauri::WindowEvent::Resized(_) => tray::hide_on_minimize(window),
+            // Moving between monitors with different DPI
+            tauri::WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+                ..
+            } => {
+                terminal::rescale_window(window.label(), *scale_factor);
+                
+                let payload = ScaleFactorPayload {
+                    label: window.label().to_string(),
+                    scale_factor: *scale_factor,
+                    width: new_inner_size.width,
+                    height: new_inner_size.height,
+                };
+                let _ = window.app_handle().emit("scale-factor-changed", payload);
             }
+            _ => {}
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // macOS delivers opened files and mux:// links as events
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                for url in urls {
+                    if url.scheme() == "file" {
+                        if let Ok(path) = url.to_file_path() {
+                            file_open::open(app, path);
+                        }
+                    } else {
+                        let app = app.clone();
+                        let url = url.to_string();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = deeplink::forward(&app, &url, deeplink_audit::DeepLinkSource::OsEvent).await {
+                                log::warn!("Ignoring deep link {}: {}", url, e);
+                            }
+                        });
+                    }
+                }
+            }
+            
+            // Cmd+Q and closing the last window wind down like Quit; the
+            // coordinator's own exit carries a code and goes through
+            if let tauri::RunEvent::ExitRequested { code: None, api, .. } = &event {
+                api.prevent_exit();
+                shutdown::quit(app);
+            }
+            
+            if let tauri::RunEvent::Exit = event {
+                crash_loop::mark_clean_exit();
+                session_journal::shutdown();
+                cli_ipc::shutdown();
+                power::shutdown();
+                
+                // Apply an update the user chose to install on quit
+                updater::install_on_quit(app);
+                
+                // Guest sessions leave nothing behind
+                if profiles::is_guest() {
+                    if let Err(e) = tauri::async_runtime::block_on(sidecar::terminate_sidecar()) {
+                        eprintln!("Failed to stop sidecar: {}", e);
+                    }
+                    profiles::wipe_guest_data();
+                }
+            }
+        });
 }
 
@@ -0,0 +1,110 @@
+// Runtime log level control
+//
+// The Rust logger is initialized to accept every level and filtered through
+// `log::set_max_level`, so the level can be raised at runtime. The chosen
+// level is persisted and forwarded to the sidecar, letting support enable
+// debug logging without a restart. An explicit RUST_LOG always wins.
+
+use log::LevelFilter;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+use crate::{orpc_bridge, settings, sidecar};
+
+/// Level used when nothing is configured (env_logger's own default)
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Error;
+
+/// Whether RUST_LOG was set, in which case its filter takes precedence
+static ENV_FILTER: AtomicBool = AtomicBool::new(false);
+
+/// Initialize the global logger
+pub fn init() {
+    let env_filter = std::env::var_os("RUST_LOG").is_some();
+    ENV_FILTER.store(env_filter, Ordering::SeqCst);
+    
+    let mut builder = env_logger::Builder::from_default_env();
+    if !env_filter {
+        builder.filter_level(LevelFilter::Trace);
+    }
+    builder.init();
+    
+    if !env_filter {
+        log::set_max_level(DEFAULT_LEVEL);
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Invalid log level: {}", level))
+}
+
+/// Apply the persisted level, if any
+pub fn apply_saved() {
+    if ENV_FILTER.load(Ordering::SeqCst) {
+        return;
+    }
+    
+    if let Some(level) = settings::current().log_level {
+        match parse_level(&level) {
+            Ok(filter) => log::set_max_level(filter),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+}
+
+/// Send the level to the running sidecar's control endpoint
+async fn forward_to_sidecar(level: &str) -> Result<(), String> {
+    let base_url = orpc_bridge::get_backend_url()?;
+    let client = orpc_bridge::ensure_client()?;
+    
+    let mut request = client
+        .post(format!("{}/control/log-level", base_url))
+        .json(&serde_json::json!({ "level": level }))
+        .timeout(std::time::Duration::from_secs(5));
+    if let Some(token) = sidecar::get_auth_token() {
+        request = request.bearer_auth(token);
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach backend: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backend rejected log level: {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Set the log level of the app and the backend, and persist it
+#[tauri::command]
+pub async fn set_log_level(app: AppHandle, level: String) -> Result<(), String> {
+    let level = level.to_lowercase();
+    let filter = parse_level(&level)?;
+    
+    if ENV_FILTER.load(Ordering::SeqCst) {
+        log::warn!("RUST_LOG is set; it limits which messages are logged");
+    }
+    log::set_max_level(filter);
+    log::info!("Log level set to {}", level);
+    
+    // The backend has no trace level
+    let backend_level = if filter == LevelFilter::Trace {
+        "debug".to_string()
+    } else if filter == LevelFilter::Off {
+        "error".to_string()
+    } else {
+        level.clone()
+    };
+    
+    let mut current = settings::current();
+    current.log_level = Some(level);
+    current.sidecar.log_level = Some(backend_level.clone());
+    settings::save(&app, current)?;
+    
+    // A stopped backend picks the level up from settings on its next start
+    if sidecar::is_backend_ready() {
+        forward_to_sidecar(&backend_level).await?;
+    }
+    Ok(())
+}
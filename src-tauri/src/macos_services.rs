@@ -0,0 +1,87 @@
+// macOS Services integration
+//
+// Registers a "Send selection to MUP" entry in the system Services menu
+// (declared under NSServices in Info.plist). The selected text arrives on
+// the main thread through the app's services provider and is routed into
+// the deep link pipeline as a new chat prompt, then the main window is
+// brought forward. A share extension would need its own app extension
+// target, which the Tauri bundle doesn't build, so sharing goes through
+// the Services menu only.
+
+use std::sync::OnceLock;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{define_class, msg_send, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{NSApplication, NSPasteboard, NSPasteboardTypeString, NSUpdateDynamicServices};
+use objc2_foundation::NSString;
+use tauri::{AppHandle, Manager};
+
+use crate::deeplink;
+use crate::deeplink_audit::DeepLinkSource;
+
+/// App handle for routing service invocations
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+define_class!(
+    // Receives the NSMessage declared in Info.plist
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "MupServicesProvider"]
+    struct ServicesProvider;
+
+    impl ServicesProvider {
+        #[unsafe(method(sendSelectionToMup:userData:error:))]
+        fn send_selection(&self, pasteboard: &NSPasteboard, _user_data: Option<&NSString>, _error: *mut *mut NSString) {
+            let text = unsafe { pasteboard.stringForType(NSPasteboardTypeString) };
+            match text {
+                Some(text) => send_to_new_chat(&text.to_string()),
+                None => log::warn!("Service invoked without text on the pasteboard"),
+            }
+        }
+    }
+);
+
+/// Start a new chat with the selected text through the deep link pipeline
+fn send_to_new_chat(text: &str) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    let mut url = url::Url::parse("mux://chat/new").expect("valid deep link URL");
+    url.query_pairs_mut().append_pair("prompt", text);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = deeplink::forward(&app, url.as_str(), DeepLinkSource::Service).await {
+            log::warn!("Ignoring service invocation: {}", e);
+        }
+    });
+}
+
+/// Install the services provider; must run on the main thread
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("macOS Services not registered: not on the main thread");
+        return;
+    };
+
+    let provider: Retained<ServicesProvider> = unsafe { msg_send![ServicesProvider::alloc(mtm), init] };
+    let application = NSApplication::sharedApplication(mtm);
+    let object: &AnyObject = &provider;
+    unsafe {
+        application.setServicesProvider(Some(object));
+        NSUpdateDynamicServices();
+    }
+    // AppKit holds the provider weakly; it lives as long as the app
+    std::mem::forget(provider);
+}
@@ -0,0 +1,222 @@
+// Memory pressure handling
+//
+// Watches the system's memory pressure and, as it rises, gives memory back
+// before the OS starts killing processes: idle pre-spawned shells are
+// stopped (and not replaced until pressure eases) and SQLite caches are
+// released. A `memory-pressure` event is emitted on every level change so
+// the frontend can trim terminal scrollback and unload heavy views.
+//
+// The level comes from the platform's own signal where there is one: the
+// kernel's pressure level on macOS, PSI (/proc/pressure/memory) on Linux
+// and the low-memory resource notification on Windows. Otherwise it is
+// derived from the share of memory still available.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+use sysinfo::System;
+use tauri::{AppHandle, Emitter};
+
+use crate::{command_history, terminal};
+
+/// How often pressure is sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Available memory, as a share of total, below which pressure is assumed
+const WARNING_AVAILABLE_RATIO: f64 = 0.10;
+const CRITICAL_AVAILABLE_RATIO: f64 = 0.05;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Current level, as `PressureLevel as u8`
+static LEVEL: AtomicU8 = AtomicU8::new(PressureLevel::Normal as u8);
+
+/// System memory pressure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureLevel {
+    Normal = 0,
+    Warning = 1,
+    Critical = 2,
+}
+
+impl PressureLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            2 => PressureLevel::Critical,
+            1 => PressureLevel::Warning,
+            _ => PressureLevel::Normal,
+        }
+    }
+}
+
+/// Payload of the `memory-pressure` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MemoryPressureEvent {
+    pub level: PressureLevel,
+    pub available_mb: u64,
+    pub total_mb: u64,
+}
+
+/// Current memory pressure level
+pub fn level() -> PressureLevel {
+    PressureLevel::from_u8(LEVEL.load(Ordering::SeqCst))
+}
+
+/// Whether memory is short enough that optional allocations should wait
+pub fn is_under_pressure() -> bool {
+    level() > PressureLevel::Normal
+}
+
+/// The kernel's own pressure level (kern.memorystatus_vm_pressure_level)
+#[cfg(target_os = "macos")]
+fn platform_level() -> Option<PressureLevel> {
+    use std::ffi::{c_char, c_int, c_void};
+
+    extern "C" {
+        fn sysctlbyname(
+            name: *const c_char,
+            oldp: *mut c_void,
+            oldlenp: *mut usize,
+            newp: *mut c_void,
+            newlen: usize,
+        ) -> c_int;
+    }
+
+    let mut value: c_int = 0;
+    let mut size = std::mem::size_of::<c_int>();
+    let result = unsafe {
+        sysctlbyname(
+            c"kern.memorystatus_vm_pressure_level".as_ptr(),
+            &mut value as *mut c_int as *mut c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+    // DISPATCH_MEMORYPRESSURE_NORMAL / WARN / CRITICAL
+    match value {
+        4 => Some(PressureLevel::Critical),
+        2 => Some(PressureLevel::Warning),
+        _ => Some(PressureLevel::Normal),
+    }
+}
+
+/// Time stalled on memory over the last 10s, from PSI
+#[cfg(target_os = "linux")]
+fn platform_level() -> Option<PressureLevel> {
+    let contents = std::fs::read_to_string("/proc/pressure/memory").ok()?;
+    let avg10 = |kind: &str| -> Option<f64> {
+        contents
+            .lines()
+            .find(|line| line.starts_with(kind))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse()
+            .ok()
+    };
+
+    // "full" means every task was stalled; "some" means at least one was
+    if avg10("full")? >= 10.0 {
+        Some(PressureLevel::Critical)
+    } else if avg10("some")? >= 20.0 {
+        Some(PressureLevel::Warning)
+    } else {
+        Some(PressureLevel::Normal)
+    }
+}
+
+/// The low-memory resource notification; milder pressure falls back to
+/// available memory
+#[cfg(windows)]
+fn platform_level() -> Option<PressureLevel> {
+    use std::sync::OnceLock;
+    use windows_sys::Win32::System::Memory::{
+        CreateMemoryResourceNotification, LowMemoryResourceNotification, QueryMemoryResourceNotification,
+    };
+
+    // Handle kept for the life of the process
+    static LOW_MEMORY: OnceLock<usize> = OnceLock::new();
+    let handle = *LOW_MEMORY.get_or_init(|| unsafe {
+        CreateMemoryResourceNotification(LowMemoryResourceNotification) as usize
+    });
+    if handle == 0 {
+        return None;
+    }
+
+    let mut low = 0;
+    let ok = unsafe { QueryMemoryResourceNotification(handle as _, &mut low) };
+    (ok != 0 && low != 0).then_some(PressureLevel::Critical)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn platform_level() -> Option<PressureLevel> {
+    None
+}
+
+/// Pressure judged from the share of memory still available
+fn available_level(available: u64, total: u64) -> PressureLevel {
+    if total == 0 {
+        return PressureLevel::Normal;
+    }
+    let ratio = available as f64 / total as f64;
+    if ratio < CRITICAL_AVAILABLE_RATIO {
+        PressureLevel::Critical
+    } else if ratio < WARNING_AVAILABLE_RATIO {
+        PressureLevel::Warning
+    } else {
+        PressureLevel::Normal
+    }
+}
+
+/// Give memory back as pressure rises
+fn relieve(level: PressureLevel) {
+    terminal::drain_pool();
+    command_history::shrink_memory();
+    log::warn!("Memory pressure is {:?}; released idle shells and caches", level);
+}
+
+/// Sample memory pressure and react to changes
+pub fn init(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut system = System::new();
+        loop {
+            system.refresh_memory();
+            let available = system.available_memory();
+            let total = system.total_memory();
+            let level = platform_level()
+                .unwrap_or(PressureLevel::Normal)
+                .max(available_level(available, total));
+
+            let previous = PressureLevel::from_u8(LEVEL.swap(level as u8, Ordering::SeqCst));
+            if level != previous {
+                if level > previous {
+                    relieve(level);
+                } else if level == PressureLevel::Normal {
+                    // Idle shells come back once memory is available again
+                    terminal::refill_pool();
+                }
+
+                let event = MemoryPressureEvent {
+                    level,
+                    available_mb: available / BYTES_PER_MB,
+                    total_mb: total / BYTES_PER_MB,
+                };
+                if let Err(e) = app.emit("memory-pressure", event) {
+                    log::error!("Failed to emit memory-pressure event: {}", e);
+                }
+            }
+
+            std::thread::sleep(SAMPLE_INTERVAL);
+        }
+    });
+}
+
+/// Get the current memory pressure level
+#[tauri::command]
+pub async fn get_memory_pressure() -> Result<PressureLevel, String> {
+    Ok(level())
+}
@@ -0,0 +1,303 @@
+// Native control endpoint for the backend
+//
+// The backend otherwise has no way back into the desktop shell. This
+// module serves a tiny HTTP endpoint on loopback that the sidecar can call
+// to request native actions (show a notification, focus the window, reveal
+// a path). Its URL and a bearer token are passed to the sidecar through the
+// environment, and only actions in the settings allowlist are performed.
+//
+//   POST /actions/<action>   JSON body with the action's parameters
+
+use serde_json::Value as JsonValue;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::notifications::{self, NotificationRequest};
+use crate::settings;
+
+/// Largest request (head and body) accepted
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Route prefix of action requests
+const ACTIONS_PREFIX: &str = "/actions/";
+
+/// Native control settings, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NativeControlSettings {
+    /// Actions the backend may request
+    pub allowed_actions: Vec<String>,
+}
+
+impl Default for NativeControlSettings {
+    fn default() -> Self {
+        Self {
+            allowed_actions: vec![
+                "notify".to_string(),
+                "focus_window".to_string(),
+                "reveal_path".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RevealPathRequest {
+    path: PathBuf,
+}
+
+/// Where the endpoint listens and the token callers must present
+struct Endpoint {
+    url: String,
+    token: String,
+}
+
+static ENDPOINT: OnceLock<Endpoint> = OnceLock::new();
+
+/// Environment passing the endpoint to the sidecar
+pub fn sidecar_env() -> Vec<(&'static str, String)> {
+    match ENDPOINT.get() {
+        Some(endpoint) => vec![
+            ("MUX_NATIVE_CONTROL_URL", endpoint.url.clone()),
+            ("MUX_NATIVE_CONTROL_TOKEN", endpoint.token.clone()),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// Bind the endpoint and start serving
+///
+/// Must run before the sidecar is spawned so it receives the endpoint.
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to bind native control endpoint: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure native control endpoint: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read native control address: {}", e))?
+        .port();
+
+    let token = crate::sidecar::generate_auth_token();
+    let _ = ENDPOINT.set(Endpoint {
+        url: format!("http://127.0.0.1:{}", port),
+        token: token.clone(),
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Native control endpoint unavailable: {}", e);
+                return;
+            }
+        };
+        log::info!("Native control endpoint listening on port {}", port);
+
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::warn!("Native control accept failed: {}", e);
+                    continue;
+                }
+            };
+            let app = app.clone();
+            let token = token.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = serve(&app, &token, stream).await {
+                    log::debug!("Native control connection failed: {}", e);
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// A parsed request
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Read one HTTP/1.1 request
+async fn read_request(stream: &mut TcpStream) -> Result<Request, (u16, String)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Err((413, "Request too large".to_string()));
+        }
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (400, e.to_string()))?;
+        if n == 0 {
+            return Err((400, "Connection closed".to_string()));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| (400, "Invalid Content-Length".to_string()))?;
+            }
+            "authorization" => authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if head_end + 4 + content_length > MAX_REQUEST_BYTES {
+        return Err((413, "Request too large".to_string()));
+    }
+
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < content_length {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (400, e.to_string()))?;
+        if n == 0 {
+            return Err((400, "Connection closed".to_string()));
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+async fn serve(app: &AppHandle, token: &str, mut stream: TcpStream) -> std::io::Result<()> {
+    let (status, body) = match read_request(&mut stream).await {
+        Ok(request) => match handle(app, token, request) {
+            Ok(()) => (200, serde_json::json!({ "ok": true })),
+            Err((status, error)) => (status, serde_json::json!({ "error": error })),
+        },
+        Err((status, error)) => (status, serde_json::json!({ "error": error })),
+    };
+
+    let body = body.to_string();
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Whether an Authorization header carries the token, compared in constant
+/// time; hashing both sides first hides the token's length as well
+fn token_matches(authorization: Option<&str>, token: &str) -> bool {
+    use sha2::{Digest, Sha256};
+
+    let Some(presented) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let presented = Sha256::digest(presented.as_bytes());
+    let expected = Sha256::digest(token.as_bytes());
+    presented
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Check a request and perform its action
+fn handle(app: &AppHandle, token: &str, request: Request) -> Result<(), (u16, String)> {
+    if !token_matches(request.authorization.as_deref(), token) {
+        return Err((401, "Invalid token".to_string()));
+    }
+    let Some(action) = request.path.strip_prefix(ACTIONS_PREFIX) else {
+        return Err((404, "Not found".to_string()));
+    };
+    if request.method != "POST" {
+        return Err((405, "Use POST".to_string()));
+    }
+    if !settings::current()
+        .native_control
+        .allowed_actions
+        .iter()
+        .any(|allowed| allowed == action)
+    {
+        log::warn!("Backend requested disallowed native action {}", action);
+        return Err((403, format!("Action {} is not allowed", action)));
+    }
+
+    let params: JsonValue = if request.body.is_empty() {
+        JsonValue::Null
+    } else {
+        serde_json::from_slice(&request.body).map_err(|e| (400, format!("Invalid JSON: {}", e)))?
+    };
+    let invalid = |e: serde_json::Error| (400, format!("Invalid parameters: {}", e));
+
+    match action {
+        "notify" => {
+            let notification: NotificationRequest = serde_json::from_value(params).map_err(invalid)?;
+            notifications::show(app, &notification).map_err(|e| (500, e))
+        }
+        "focus_window" => {
+            let window = app
+                .get_webview_window("main")
+                .ok_or_else(|| (500, "Main window not found".to_string()))?;
+            let _ = window.unminimize();
+            let _ = window.show();
+            window.set_focus().map_err(|e| (500, e.to_string()))
+        }
+        "reveal_path" => {
+            let request: RevealPathRequest = serde_json::from_value(params).map_err(invalid)?;
+            if !request.path.is_absolute() || !request.path.exists() {
+                return Err((400, "Path must be absolute and exist".to_string()));
+            }
+            // Revealing rather than opening never launches executables
+            app.opener()
+                .reveal_item_in_dir(&request.path)
+                .map_err(|e| (500, e.to_string()))
+        }
+        _ => Err((404, format!("Unknown action {}", action))),
+    }
+}
@@ -0,0 +1,96 @@
+// Native notifications
+//
+// Shows OS notifications for the backend (agent finished, ...), the
+// updater and the tray. A notification may carry a `mux://` link that is
+// followed when it is clicked. On Windows the toast uses the link as its
+// protocol activation target, so clicking it after the app has exited
+// relaunches the app with the link (see `toast_activation`); elsewhere
+// notifications go through the notification plugin.
+
+use tauri::AppHandle;
+
+use crate::deeplink;
+
+/// A notification to show
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NotificationRequest {
+    pub title: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// `mux://` link to follow when the notification is clicked
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Show a notification
+pub fn show(app: &AppHandle, request: &NotificationRequest) -> Result<(), String> {
+    if let Some(url) = &request.url {
+        if !deeplink::is_deep_link(url) {
+            return Err(format!("Notification link must be a mux:// URL: {}", url));
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        show_toast(app, request)
+    }
+
+    #[cfg(not(windows))]
+    {
+        use tauri_plugin_notification::NotificationExt;
+
+        let mut builder = app.notification().builder().title(&request.title);
+        if let Some(body) = &request.body {
+            builder = builder.body(body);
+        }
+        builder
+            .show()
+            .map_err(|e| format!("Failed to show notification: {}", e))
+    }
+}
+
+#[cfg(windows)]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Show a toast attributed to the app's AppUserModelID
+#[cfg(windows)]
+fn show_toast(app: &AppHandle, request: &NotificationRequest) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+    let activation = request
+        .url
+        .as_deref()
+        .map(|url| format!(r#" activationType="protocol" launch="{}""#, xml_escape(url)))
+        .unwrap_or_default();
+    let body = request
+        .body
+        .as_deref()
+        .map(|body| format!("<text>{}</text>", xml_escape(body)))
+        .unwrap_or_default();
+    let xml = format!(
+        r#"<toast{}><visual><binding template="ToastGeneric"><text>{}</text>{}</binding></visual></toast>"#,
+        activation,
+        xml_escape(&request.title),
+        body
+    );
+
+    let show = || -> windows::core::Result<()> {
+        let document = XmlDocument::new()?;
+        document.LoadXml(&HSTRING::from(xml))?;
+        let toast = ToastNotification::CreateToastNotification(&document)?;
+        let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(
+            app.config().identifier.as_str(),
+        ))?;
+        notifier.Show(&toast)
+    };
+    show().map_err(|e| format!("Failed to show notification: {}", e))
+}
@@ -0,0 +1,316 @@
+// Offline update installation
+//
+// For air-gapped machines that can't reach the update endpoint, an update
+// bundle downloaded elsewhere can be installed from disk. It is the same
+// artifact the updater fetches (.app.tar.gz on macOS, .AppImage on Linux,
+// the .msi or NSIS setup .exe on Windows), with its minisign signature
+// alongside in `<bundle>.sig`. The signature is checked against the
+// updater's public key before anything is touched, and the bundle must be
+// newer than the running version. The version is read from something the
+// signature covers: the app's Info.plist on macOS, elsewhere the file name
+// recorded in the signature's trusted comment (never the name on disk,
+// which anyone can change). The updater plugin only installs releases it
+// fetched itself, so the bundle is put in place here.
+
+use base64::Engine;
+use minisign_verify::{PublicKey, Signature};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+use crate::storage::{self, UnpackLimits};
+use crate::update_telemetry::{PhaseSpan, UpdatePhase};
+use crate::updater::{self, UpdateErrorKind, UpdateStatus};
+use crate::version::is_newer;
+use crate::{update_hooks, update_policy, update_rollback};
+
+/// Extension of the detached signature next to a bundle
+const SIGNATURE_EXTENSION: &str = "sig";
+
+/// How much an app bundle may unpack to
+#[cfg(target_os = "macos")]
+const UNPACK_LIMITS: UnpackLimits = UnpackLimits {
+    max_entries: 50_000,
+    max_bytes: 4 * 1024 * 1024 * 1024,
+};
+
+/// A verified bundle, unpacked where needed and ready to swap in
+struct StagedUpdate {
+    version: String,
+    /// Unpacked app bundle, AppImage or installer
+    path: PathBuf,
+}
+
+fn decode_base64(value: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|e| format!("invalid base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("invalid UTF-8: {}", e))
+}
+
+/// The updater's public key from the app config
+fn public_key(app: &AppHandle) -> Result<PublicKey, String> {
+    let encoded = app
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|key| key.as_str())
+        .ok_or("No updater public key is configured")?;
+    let decoded = decode_base64(encoded).map_err(|e| format!("Invalid updater public key: {}", e))?;
+    PublicKey::decode(&decoded).map_err(|e| format!("Invalid updater public key: {}", e))
+}
+
+/// Check a bundle against its detached signature, made with the updater's
+/// signing key; returns the signature's trusted comment
+pub(crate) fn verify_signature(app: &AppHandle, bundle: &[u8], signature: &str) -> Result<String, String> {
+    let key = public_key(app)?;
+    let decoded = decode_base64(signature).map_err(|e| format!("Invalid signature file: {}", e))?;
+    let signature = Signature::decode(&decoded).map_err(|e| format!("Invalid signature file: {}", e))?;
+    // Checks the trusted comment too (the global signature covers it)
+    key.verify(bundle, &signature, true)
+        .map_err(|e| format!("Signature verification failed: {}", e))?;
+    Ok(signature.trusted_comment().to_string())
+}
+
+fn version_regex() -> &'static Regex {
+    static VERSION: OnceLock<Regex> = OnceLock::new();
+    VERSION.get_or_init(|| Regex::new(r"(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.]+))?").expect("valid version regex"))
+}
+
+/// Version in the file name a signature was made for, from its trusted
+/// comment (`timestamp:<secs>\tfile:<name>`), e.g. mup_0.18.0_amd64.AppImage
+#[cfg_attr(not(any(target_os = "linux", windows)), allow(dead_code))]
+fn signed_version(trusted_comment: &str) -> Option<String> {
+    let name = trusted_comment
+        .split('\t')
+        .find_map(|field| field.strip_prefix("file:"))?;
+    version_regex().find(name).map(|m| m.as_str().to_string())
+}
+
+/// Unpack the app bundle next to the installed one, so the swap is a
+/// rename on the same volume
+#[cfg(target_os = "macos")]
+fn stage(bundle: &Path, bytes: &[u8], _trusted_comment: &str, target: &Path) -> Result<StagedUpdate, String> {
+    if !bundle.to_string_lossy().ends_with(".app.tar.gz") {
+        return Err("Expected a .app.tar.gz update bundle".to_string());
+    }
+    let parent = target.parent().ok_or("Installation location is unknown")?;
+    let staging = parent.join(".mup-update");
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging).map_err(|e| format!("Failed to unpack update: {}", e))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+    if let Err(e) = storage::unpack_bounded(&mut archive, &staging, UNPACK_LIMITS, |_, _| true) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!("Failed to unpack update: {}", e));
+    }
+
+    let path = std::fs::read_dir(&staging)
+        .map_err(|e| format!("Failed to read unpacked update: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .ok_or("Update bundle contains no app")?;
+
+    // The signed archive's Info.plist is authoritative
+    let version = std::fs::read_to_string(path.join("Contents/Info.plist"))
+        .ok()
+        .and_then(|plist| {
+            Regex::new(r"<key>CFBundleShortVersionString</key>\s*<string>([^<]+)</string>")
+                .expect("valid plist regex")
+                .captures(&plist)
+                .map(|captures| captures[1].to_string())
+        })
+        .ok_or("Update bundle has no version")?;
+    Ok(StagedUpdate { version, path })
+}
+
+/// Write the new AppImage beside the running one
+#[cfg(target_os = "linux")]
+fn stage(bundle: &Path, bytes: &[u8], trusted_comment: &str, target: &Path) -> Result<StagedUpdate, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if !bundle.extension().is_some_and(|ext| ext == "AppImage") {
+        return Err("Expected an .AppImage update bundle".to_string());
+    }
+    let version = signed_version(trusted_comment).ok_or("Update signature names no version")?;
+    let mut path = target.as_os_str().to_owned();
+    path.push(".update");
+    let path = PathBuf::from(path);
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to stage update: {}", e))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to stage update: {}", e))?;
+    Ok(StagedUpdate { version, path })
+}
+
+/// Installers run from where they are
+#[cfg(windows)]
+fn stage(bundle: &Path, _bytes: &[u8], trusted_comment: &str, _target: &Path) -> Result<StagedUpdate, String> {
+    if !bundle.extension().is_some_and(|ext| ext == "msi" || ext == "exe") {
+        return Err("Expected an .msi or setup .exe update bundle".to_string());
+    }
+    let version = signed_version(trusted_comment).ok_or("Update signature names no version")?;
+    Ok(StagedUpdate {
+        version,
+        path: bundle.to_path_buf(),
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn stage(_bundle: &Path, _bytes: &[u8], _trusted_comment: &str, _target: &Path) -> Result<StagedUpdate, String> {
+    Err("Offline updates are not supported on this platform".to_string())
+}
+
+/// Replace the installed bundle with the staged one
+#[cfg(target_os = "macos")]
+fn swap(staged: &StagedUpdate, target: &Path) -> Result<(), String> {
+    let mut old = target.as_os_str().to_owned();
+    old.push(".old");
+    let old = PathBuf::from(old);
+    let _ = std::fs::remove_dir_all(&old);
+
+    std::fs::rename(target, &old).map_err(|e| format!("Failed to move the installed app: {}", e))?;
+    if let Err(e) = std::fs::rename(&staged.path, target) {
+        let _ = std::fs::rename(&old, target);
+        return Err(format!("Failed to install update: {}", e));
+    }
+    let _ = std::fs::remove_dir_all(&old);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn swap(staged: &StagedUpdate, target: &Path) -> Result<(), String> {
+    std::fs::rename(&staged.path, target).map_err(|e| format!("Failed to install update: {}", e))
+}
+
+/// Start the installer, which replaces the app's files once it has quit
+#[cfg(windows)]
+fn swap(staged: &StagedUpdate, _target: &Path) -> Result<(), String> {
+    let mut command = if staged.path.extension().is_some_and(|ext| ext == "msi") {
+        let mut command = std::process::Command::new("msiexec.exe");
+        command
+            .arg("/i")
+            .arg(&staged.path)
+            .args(["/passive", "/promptrestart", "AUTOLAUNCHAPP=True"]);
+        command
+    } else {
+        let mut command = std::process::Command::new(&staged.path);
+        command.args(["/P", "/R"]);
+        command
+    };
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start installer: {}", e))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn swap(_staged: &StagedUpdate, _target: &Path) -> Result<(), String> {
+    Err("Offline updates are not supported on this platform".to_string())
+}
+
+/// Remove what `stage` unpacked; installers belong to the user
+#[cfg_attr(windows, allow(unused_variables))]
+fn discard(staged: &StagedUpdate) {
+    #[cfg(target_os = "macos")]
+    if let Some(staging) = staged.path.parent() {
+        let _ = std::fs::remove_dir_all(staging);
+    }
+
+    #[cfg(target_os = "linux")]
+    let _ = std::fs::remove_file(&staged.path);
+}
+
+/// Verify and unpack a bundle
+fn prepare(app: &AppHandle, bundle: &Path, target: &Path) -> Result<StagedUpdate, String> {
+    let mut signature_path = bundle.as_os_str().to_owned();
+    signature_path.push(".");
+    signature_path.push(SIGNATURE_EXTENSION);
+    let signature = std::fs::read_to_string(PathBuf::from(signature_path))
+        .map_err(|e| format!("Failed to read the update's .sig file: {}", e))?;
+    let bytes = std::fs::read(bundle).map_err(|e| format!("Failed to read update bundle: {}", e))?;
+
+    let span = PhaseSpan::start(app, UpdatePhase::SignatureCheck, None);
+    let trusted_comment = match verify_signature(app, &bytes, &signature) {
+        Ok(trusted_comment) => trusted_comment,
+        Err(e) => {
+            span.fail(app, "signature_invalid", e.clone());
+            return Err(e);
+        }
+    };
+    span.succeed(app);
+
+    let span = PhaseSpan::start(app, UpdatePhase::Extraction, None);
+    let staged = match stage(bundle, &bytes, &trusted_comment, target) {
+        Ok(staged) => staged,
+        Err(e) => {
+            span.fail(app, "extraction_failed", e.clone());
+            return Err(e);
+        }
+    };
+    span.succeed(app);
+
+    let current = app.package_info().version.to_string();
+    if !is_newer(&staged.version, &current) {
+        discard(&staged);
+        return Err(format!(
+            "Update {} is not newer than the installed version {}",
+            staged.version, current
+        ));
+    }
+    Ok(staged)
+}
+
+async fn install(app: &AppHandle, bundle: &Path) -> Result<String, String> {
+    let target = update_rollback::install_target().ok_or("Installation location is unknown")?;
+    let staged = prepare(app, bundle, &target)?;
+
+    // Nothing may be working against the files being replaced
+    if let Err(e) = update_hooks::quiesce(app, &staged.version).await {
+        discard(&staged);
+        return Err(e);
+    }
+
+    // Keep the running version so a bad release can be rolled back
+    if let Err(e) = update_rollback::backup_current(app).await {
+        log::warn!("Installing without a rollback backup: {}", e);
+    }
+
+    let span = PhaseSpan::start(app, UpdatePhase::Swap, Some(staged.version.clone()));
+    let result = swap(&staged, &target);
+    match &result {
+        Ok(()) => {
+            span.succeed(app);
+            // A downloaded update must not be installed over this one at quit
+            updater::clear_pending(app);
+        }
+        Err(e) => span.fail(app, "swap_failed", e.clone()),
+    }
+    discard(&staged);
+
+    // The Windows installer can only replace the app's files once it quits
+    if cfg!(windows) && result.is_ok() {
+        app.exit(0);
+    } else {
+        update_hooks::resume(app).await;
+    }
+    result.map(|()| format!("Update {} installed. Restart to apply.", staged.version))
+}
+
+/// Install a signed update bundle from a local file, for machines that
+/// can't reach the update server. `<path>.sig` must hold its signature.
+#[tauri::command]
+pub async fn install_update_from_file(app: AppHandle, path: String) -> Result<String, String> {
+    update_policy::ensure_updates_allowed()?;
+
+    let result = install(&app, Path::new(&path)).await;
+    if let Err(e) = &result {
+        let status = UpdateStatus::Error {
+            message: e.clone(),
+            kind: UpdateErrorKind::Other,
+        };
+        updater::emit_status(&app, &status)?;
+    }
+    result
+}
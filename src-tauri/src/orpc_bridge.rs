@@ -3,42 +3,554 @@
 // This module provides a bridge between Tauri and the Node.js backend's oRPC server.
 // It forwards invoke calls from the frontend to the Node.js backend via HTTP.
 
+use base64::Engine;
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde_json::Value as JsonValue;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{oneshot, watch, Semaphore};
 
-use crate::sidecar;
+use crate::{orpc_metrics, orpc_queue, settings, sidecar};
 
-// HTTP client for communicating with oRPC server
-type HttpClient = Arc<Mutex<Option<Client>>>;
+/// Bridge behaviour, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BridgeSettings {
+    /// How long calls made before the backend is ready wait for it
+    pub ready_timeout_secs: u64,
+    /// Default per-attempt request timeout. Unset by default: agent turns,
+    /// builds and other long procedures legitimately run for many minutes,
+    /// so only calls that pass `timeout_ms` are cut short
+    pub request_timeout_secs: Option<u64>,
+    /// Default retries for idempotent calls
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on each further retry
+    pub retry_backoff_ms: u64,
+    /// Requests (calls, streams and transfers) sent to the backend at once;
+    /// applied at startup
+    pub max_concurrent_calls: usize,
+    /// Calls allowed to wait for a free slot before new ones are rejected
+    pub max_waiting_calls: usize,
+    /// Exchange MessagePack instead of JSON when the backend supports it
+    pub msgpack: bool,
+    /// Compress request and response bodies (gzip/brotli) on unary calls
+    pub compression: bool,
+    /// Bodies smaller than this are sent uncompressed
+    pub compression_threshold_bytes: usize,
+}
+
+impl Default for BridgeSettings {
+    fn default() -> Self {
+        Self {
+            ready_timeout_secs: 30,
+            request_timeout_secs: None,
+            max_retries: 2,
+            retry_backoff_ms: 500,
+            max_concurrent_calls: 16,
+            max_waiting_calls: 256,
+            msgpack: true,
+            compression: true,
+            compression_threshold_bytes: 32 * 1024,
+        }
+    }
+}
+
+/// Per-call overrides of the bridge defaults
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct CallOptions {
+    pub timeout_ms: Option<u64>,
+    pub retries: Option<u32>,
+    pub retry_backoff_ms: Option<u64>,
+    /// Only idempotent calls are retried, since a failed attempt may still
+    /// have been applied by the backend
+    pub idempotent: bool,
+    /// While the backend is unavailable, store the call and replay it after
+    /// `backend-ready` instead of failing
+    pub queueable: bool,
+}
+
+/// Tells the backend the smallest response body worth compressing
+const COMPRESSION_THRESHOLD_HEADER: &str = "X-Compression-Threshold";
+
+/// Content type of MessagePack bodies
+const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+// Whether the current backend answered in MessagePack; request bodies are
+// only encoded that way once it has
+static MSGPACK_SUPPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Header carrying the ID that ties backend logs to a bridge call
+const TRACE_HEADER: &str = "X-Trace-Id";
+
+/// Random ID sent with every forwarded request
+fn new_trace_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+/// Consecutive unreachable attempts that open the circuit
+const BREAKER_THRESHOLD: u32 = 3;
+
+/// How long an open circuit fails fast before letting a call through again
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Circuit breaker state
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+    /// The sidecar exited and no replacement has announced itself yet
+    backend_down: bool,
+}
+
+static BREAKER: std::sync::Mutex<Breaker> = std::sync::Mutex::new(Breaker {
+    failures: 0,
+    open_until: None,
+    backend_down: false,
+});
+
+/// Whether calls should fail fast because the backend is known to be down
+pub(crate) fn circuit_open() -> bool {
+    BREAKER
+        .lock()
+        .map(|breaker| {
+            breaker.backend_down
+                || breaker.open_until.map(|until| Instant::now() < until).unwrap_or(false)
+        })
+        .unwrap_or(false)
+}
+
+/// Record that the sidecar exited unexpectedly
+pub(crate) fn mark_backend_down() {
+    if let Ok(mut breaker) = BREAKER.lock() {
+        breaker.backend_down = true;
+    }
+}
+
+fn record_reachable() {
+    if let Ok(mut breaker) = BREAKER.lock() {
+        breaker.failures = 0;
+        breaker.open_until = None;
+    }
+}
+
+fn record_unreachable() {
+    if let Ok(mut breaker) = BREAKER.lock() {
+        breaker.failures += 1;
+        if breaker.failures >= BREAKER_THRESHOLD {
+            log::warn!(
+                "Backend unreachable after {} attempts; failing fast for {}s",
+                breaker.failures,
+                BREAKER_COOLDOWN.as_secs()
+            );
+            breaker.open_until = Some(Instant::now() + BREAKER_COOLDOWN);
+        }
+    }
+}
+
+/// Calls from one batch in flight at once
+const BATCH_CONCURRENCY: usize = 6;
+
+/// One call in a `forward_orpc_batch` request
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BatchCall {
+    pub method: String,
+    #[serde(default)]
+    pub params: Option<JsonValue>,
+    #[serde(default)]
+    pub options: Option<CallOptions>,
+}
+
+/// Outcome of one call in a batch; exactly one field is set
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BatchCallResult {
+    pub result: Option<JsonValue>,
+    pub error: Option<String>,
+}
+
+/// A failed attempt, and whether another attempt could succeed
+struct AttemptError {
+    message: String,
+    retryable: bool,
+    /// The backend could not be reached at all
+    unreachable: bool,
+}
+
+// Shared HTTP clients for the backend. One pooled loopback client serves
+// every TCP call (connections are pooled per port, so it survives backend
+// restarts); a socket-bound client is rebuilt when the socket changes
+struct BackendClients {
+    tcp: Client,
+    socket: Option<(PathBuf, Client)>,
+    // Endpoint of the last call, to notice when the backend changed
+    endpoint: (Option<PathBuf>, u16),
+}
 
-// Global HTTP client using OnceLock
 use std::sync::OnceLock;
 
-static HTTP_CLIENT: OnceLock<HttpClient> = OnceLock::new();
+static HTTP_CLIENTS: OnceLock<std::sync::Mutex<BackendClients>> = OnceLock::new();
+
+/// Idle keep-alive connections are dropped before Node's default 5s
+/// server-side keep-alive timeout closes them under us
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Idle connections kept per host
+const POOL_MAX_IDLE: usize = 16;
+
+// Backend readiness; calls made during startup wait on this
+static READY: OnceLock<watch::Sender<bool>> = OnceLock::new();
+
+fn ready_sender() -> &'static watch::Sender<bool> {
+    READY.get_or_init(|| watch::channel(false).0)
+}
+
+/// Record whether the backend is reachable, releasing waiting calls
+pub(crate) fn set_ready(ready: bool) {
+    if ready {
+        if let Ok(mut breaker) = BREAKER.lock() {
+            breaker.backend_down = false;
+            breaker.failures = 0;
+            breaker.open_until = None;
+        }
+    }
+    ready_sender().send_if_modified(|current| {
+        let changed = *current != ready;
+        *current = ready;
+        changed
+    });
+}
+
+/// Wait until the backend has announced where it is listening
+pub(crate) async fn wait_until_ready() -> Result<(), String> {
+    let timeout = Duration::from_secs(settings::current().bridge.ready_timeout_secs);
+    let mut ready = ready_sender().subscribe();
+    
+    match tokio::time::timeout(timeout, ready.wait_for(|ready| *ready)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(_)) => Err("Backend not started yet".to_string()),
+        Err(_) => Err(format!(
+            "Backend not ready after {}s",
+            timeout.as_secs()
+        )),
+    }
+}
+
+// Limits requests in flight to the backend (calls, streams, uploads,
+// downloads and multipart) so a runaway caller can't starve it
+static CALL_SLOTS: OnceLock<Semaphore> = OnceLock::new();
+
+// Calls currently waiting for a slot
+static WAITING_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Wait for a free call slot, failing fast when too many calls are waiting
+async fn acquire_call_slot() -> Result<tokio::sync::SemaphorePermit<'static>, String> {
+    let limits = settings::current().bridge;
+    let slots = CALL_SLOTS.get_or_init(|| Semaphore::new(limits.max_concurrent_calls.max(1)));
+    if let Ok(permit) = slots.try_acquire() {
+        return Ok(permit);
+    }
+    
+    // Counted until acquired or dropped (e.g. the call was cancelled)
+    struct Waiting;
+    impl Drop for Waiting {
+        fn drop(&mut self) {
+            WAITING_CALLS.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+    
+    let waiting = Waiting;
+    if WAITING_CALLS.fetch_add(1, Ordering::SeqCst) >= limits.max_waiting_calls {
+        return Err("Too many pending backend calls".to_string());
+    }
+    let permit = slots.acquire().await;
+    drop(waiting);
+    permit.map_err(|e| format!("Call limiter closed: {}", e))
+}
+
+// Calls in flight per backend endpoint, so a backend being replaced can be
+// drained before it is stopped
+static ENDPOINT_CALLS: std::sync::Mutex<Option<HashMap<String, usize>>> = std::sync::Mutex::new(None);
+
+/// Identifies the backend calls are currently sent to
+pub(crate) fn endpoint_key() -> String {
+    match sidecar::get_sidecar_socket() {
+        Some(socket) => socket.display().to_string(),
+        None => sidecar::get_sidecar_port().to_string(),
+    }
+}
+
+/// Number of calls in flight to an endpoint
+pub(crate) fn calls_in_flight(endpoint: &str) -> usize {
+    ENDPOINT_CALLS
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|calls| calls.get(endpoint).copied()))
+        .unwrap_or(0)
+}
+
+/// Wait until an endpoint's calls have finished or `timeout` passes;
+/// returns how many are still running
+pub(crate) async fn wait_for_calls(endpoint: &str, timeout: Duration) -> usize {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while calls_in_flight(endpoint) > 0 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    calls_in_flight(endpoint)
+}
+
+/// Hold new calls and stop the backend once the calls it is serving have
+/// finished. If some are still running after `timeout`, `force` stops it
+/// anyway; otherwise calls are let through again and an error returned.
+pub(crate) async fn stop_backend(timeout: Duration, force: bool) -> Result<(), String> {
+    set_ready(false);
+
+    let remaining = wait_for_calls(&endpoint_key(), timeout).await;
+    if remaining > 0 {
+        if !force {
+            set_ready(sidecar::is_backend_ready());
+            return Err(format!(
+                "{} backend call(s) still running after {}s",
+                remaining,
+                timeout.as_secs()
+            ));
+        }
+        log::warn!("Stopping the backend with {} call(s) still running", remaining);
+    }
+    sidecar::terminate_sidecar().await
+}
+
+/// Counts a call against its endpoint until dropped
+struct EndpointCall(String);
+
+impl EndpointCall {
+    fn start(endpoint: String) -> Self {
+        if let Ok(mut guard) = ENDPOINT_CALLS.lock() {
+            *guard.get_or_insert_with(HashMap::new).entry(endpoint.clone()).or_insert(0) += 1;
+        }
+        Self(endpoint)
+    }
+}
+
+impl Drop for EndpointCall {
+    fn drop(&mut self) {
+        if let Ok(mut guard) = ENDPOINT_CALLS.lock() {
+            if let Some(calls) = guard.as_mut() {
+                if let Some(count) = calls.get_mut(&self.0) {
+                    *count -= 1;
+                    if *count == 0 {
+                        calls.remove(&self.0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Stream call ID counter
+static NEXT_CALL_ID: AtomicU64 = AtomicU64::new(1);
 
-fn get_http_client() -> HttpClient {
-    HTTP_CLIENT.get_or_init(|| Arc::new(Mutex::new(None))).clone()
+// Registration counter, so a finished call can't unregister a later call
+// that reused its ID
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+// Cancellation senders for in-flight calls, by call ID
+static IN_FLIGHT: OnceLock<std::sync::Mutex<HashMap<String, (u64, oneshot::Sender<()>)>>> = OnceLock::new();
+
+fn in_flight() -> &'static std::sync::Mutex<HashMap<String, (u64, oneshot::Sender<()>)>> {
+    IN_FLIGHT.get_or_init(|| std::sync::Mutex::new(HashMap::new()))
+}
+
+/// A call ID registered for cancellation; unregistered when dropped
+struct CallRegistration {
+    call_id: String,
+    generation: u64,
+    cancel_rx: oneshot::Receiver<()>,
+}
+
+impl CallRegistration {
+    /// Register a call ID, failing if one with that ID is in flight
+    fn register(call_id: String) -> Result<Self, String> {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let generation = NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+        let mut calls = in_flight().lock().map_err(|e| e.to_string())?;
+        if calls.contains_key(&call_id) {
+            return Err(format!("Call {} is already in flight", call_id));
+        }
+        calls.insert(call_id.clone(), (generation, cancel_tx));
+        Ok(Self {
+            call_id,
+            generation,
+            cancel_rx,
+        })
+    }
+    
+    /// Run the call until it finishes or is cancelled
+    ///
+    /// Dropping the request future closes the connection, which aborts the
+    /// handler on the backend.
+    async fn run<T>(mut self, call: impl std::future::Future<Output = Result<T, String>>) -> Result<T, String> {
+        tokio::select! {
+            result = call => result,
+            _ = &mut self.cancel_rx => {
+                log::info!("oRPC call {} cancelled", self.call_id);
+                Err("Cancelled".to_string())
+            }
+        }
+    }
+}
+
+impl Drop for CallRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut calls) = in_flight().lock() {
+            if calls.get(&self.call_id).is_some_and(|(generation, _)| *generation == self.generation) {
+                calls.remove(&self.call_id);
+            }
+        }
+    }
+}
+
+/// Run a call that `cancel_orpc_call` can abort
+async fn cancellable<T>(
+    call_id: Option<String>,
+    call: impl std::future::Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    match call_id {
+        Some(call_id) => CallRegistration::register(call_id)?.run(call).await,
+        None => call.await,
+    }
+}
+
+/// Payload of the `orpc-stream-chunk` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StreamChunkPayload {
+    pub call_id: String,
+    /// One SSE `data` field (parsed as JSON when possible) or raw chunk text
+    pub data: JsonValue,
+}
+
+/// Payload of the `orpc-stream-end` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StreamEndPayload {
+    pub call_id: String,
+    pub error: Option<String>,
+}
+
+/// Builder with the pool tuning shared by all loopback clients
+fn loopback_builder() -> reqwest::ClientBuilder {
+    Client::builder()
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .pool_max_idle_per_host(POOL_MAX_IDLE)
+        .tcp_nodelay(true)
+        // System proxies must never see loopback traffic
+        .no_proxy()
+}
+
+/// Build the client for the sidecar's Unix socket
+#[cfg(unix)]
+fn build_socket_client(path: PathBuf) -> Result<Client, String> {
+    loopback_builder()
+        .unix_socket(path)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Build the client for the sidecar's named pipe
+#[cfg(windows)]
+fn build_socket_client(path: PathBuf) -> Result<Client, String> {
+    loopback_builder()
+        .windows_named_pipe(path)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn build_socket_client(_path: PathBuf) -> Result<Client, String> {
+    loopback_client()
+}
+
+/// Pooled client for loopback HTTP, shared by everything talking to local
+/// processes (the backend over TCP, auxiliary sidecar health checks)
+pub(crate) fn loopback_client() -> Result<Client, String> {
+    Ok(clients()?.lock().map_err(|e| e.to_string())?.tcp.clone())
+}
+
+fn clients() -> Result<&'static std::sync::Mutex<BackendClients>, String> {
+    if let Some(clients) = HTTP_CLIENTS.get() {
+        return Ok(clients);
+    }
+    let tcp = loopback_builder()
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    Ok(HTTP_CLIENTS.get_or_init(|| {
+        std::sync::Mutex::new(BackendClients {
+            tcp,
+            socket: None,
+            endpoint: (None, 0),
+        })
+    }))
 }
 
-/// Initialize the HTTP client
-fn ensure_client() -> Result<Client, String> {
-    let client_ref = get_http_client();
-    let mut client_guard = client_ref.try_lock()
+/// Get the client for the current backend endpoint
+pub(crate) fn ensure_client() -> Result<Client, String> {
+    let socket = sidecar::get_sidecar_socket();
+    let endpoint = (socket.clone(), sidecar::get_sidecar_port());
+    
+    let mut clients = clients()?
+        .lock()
         .map_err(|e| format!("Failed to acquire lock: {}", e))?;
     
-    if client_guard.is_none() {
-        *client_guard = Some(Client::new());
+    if clients.endpoint != endpoint {
+        clients.endpoint = endpoint;
+        // A different backend has to show MessagePack support again
+        MSGPACK_SUPPORTED.store(false, Ordering::SeqCst);
     }
     
-    client_guard.as_ref()
-        .cloned()
+    let Some(path) = socket else {
+        return Ok(clients.tcp.clone());
+    };
+    
+    // Rebuild when the socket changed (e.g. after a backend restart),
+    // dropping keep-alive connections to the old one
+    if clients.socket.as_ref().map(|(cached, _)| cached != &path).unwrap_or(true) {
+        let client = build_socket_client(path.clone())?;
+        clients.socket = Some((path, client));
+    }
+    clients
+        .socket
+        .as_ref()
+        .map(|(_, client)| client.clone())
         .ok_or_else(|| "Failed to create HTTP client".to_string())
 }
 
+/// Gzip a request body; fast compression keeps the cost below what the
+/// smaller copy saves
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+    
+    let mut encoder = flate2::write::GzEncoder::new(
+        Vec::with_capacity(bytes.len() / 4),
+        flate2::Compression::fast(),
+    );
+    encoder
+        .write_all(bytes)
+        .map_err(|e| format!("Failed to compress request: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress request: {}", e))
+}
+
 /// Get the backend base URL using the sidecar's dynamic port
-fn get_backend_url() -> Result<String, String> {
+///
+/// With the socket transport the host is ignored by the connector.
+pub(crate) fn get_backend_url() -> Result<String, String> {
+    if sidecar::get_sidecar_socket().is_some() {
+        return Ok("http://localhost".to_string());
+    }
+    
     let port = sidecar::get_sidecar_port();
     if port == 0 {
         return Err("Backend not started yet".to_string());
@@ -51,16 +563,105 @@ fn get_backend_url() -> Result<String, String> {
 /// # Arguments
 /// * method - The RPC method name (e.g., "getProjects", "createProject")
 /// * params - Optional JSON parameters for the RPC call
+/// * call_id - Optional caller-chosen ID for cancelling with `cancel_orpc_call`
+/// * options - Optional timeout and retry overrides
 /// 
 /// # Returns
 /// JSON result from the oRPC server
+///
+/// Queueable calls made while the backend is unavailable return
+/// `{ "queued": true, "queue_id": ... }` and are replayed later.
 #[tauri::command]
-pub async fn forward_orpc_call(method: String, params: Option<JsonValue>) -> Result<JsonValue, String> {
-    let client = ensure_client()?;
-    let base_url = get_backend_url()?;
+pub async fn forward_orpc_call(
+    app: AppHandle,
+    method: String,
+    params: Option<JsonValue>,
+    call_id: Option<String>,
+    options: Option<CallOptions>,
+) -> Result<JsonValue, String> {
+    let options = options.unwrap_or_default();
+    if options.queueable && circuit_open() {
+        let queue_id = orpc_queue::enqueue(&app, method, params)?;
+        return Ok(serde_json::json!({ "queued": true, "queue_id": queue_id }));
+    }
+    cancellable(call_id, send_call(method, params, options)).await
+}
+
+/// Forward several oRPC calls in one IPC round trip
+///
+/// Calls run concurrently (at most `BATCH_CONCURRENCY` at a time) and fail
+/// independently; results are returned in the order of `calls`.
+#[tauri::command]
+pub async fn forward_orpc_batch(calls: Vec<BatchCall>) -> Result<Vec<BatchCallResult>, String> {
+    let results = futures_util::stream::iter(calls)
+        .map(|call| send_call(call.method, call.params, call.options.unwrap_or_default()))
+        .buffered(BATCH_CONCURRENCY)
+        .map(|result| match result {
+            Ok(value) => BatchCallResult {
+                result: Some(value),
+                error: None,
+            },
+            Err(e) => BatchCallResult {
+                result: None,
+                error: Some(e),
+            },
+        })
+        .collect()
+        .await;
     
-    // Build URL: http://127.0.0.1:{port}/orpc/{method}
-    let url = format!("{}/orpc/{}", base_url, method);
+    Ok(results)
+}
+
+pub(crate) async fn send_call(
+    method: String,
+    params: Option<JsonValue>,
+    options: CallOptions,
+) -> Result<JsonValue, String> {
+    let trace_id = new_trace_id();
+    let started = Instant::now();
+    
+    let result = send_traced(&method, params, options, &trace_id).await;
+    
+    let elapsed = started.elapsed();
+    orpc_metrics::record(&method, elapsed, result.is_ok());
+    match &result {
+        Ok(_) => log::debug!("oRPC call {} [{}] took {}ms", method, trace_id, elapsed.as_millis()),
+        Err(e) => log::warn!(
+            "oRPC call {} [{}] failed after {}ms: {}",
+            method,
+            trace_id,
+            elapsed.as_millis(),
+            e
+        ),
+    }
+    result
+}
+
+async fn send_traced(
+    method: &str,
+    params: Option<JsonValue>,
+    options: CallOptions,
+    trace_id: &str,
+) -> Result<JsonValue, String> {
+    // Fail fast rather than waiting on a backend known to be down
+    if circuit_open() {
+        return Err("Backend unavailable".to_string());
+    }
+    
+    // Hold calls made during startup until the backend is ready
+    wait_until_ready().await?;
+    
+    let defaults = settings::current().bridge;
+    let timeout = options
+        .timeout_ms
+        .map(Duration::from_millis)
+        .or(defaults.request_timeout_secs.map(Duration::from_secs));
+    let retries = if options.idempotent {
+        options.retries.unwrap_or(defaults.max_retries)
+    } else {
+        0
+    };
+    let backoff = Duration::from_millis(options.retry_backoff_ms.unwrap_or(defaults.retry_backoff_ms));
     
     // Prepare request body
     let body = if let Some(params) = params {
@@ -69,15 +670,199 @@ pub async fn forward_orpc_call(method: String, params: Option<JsonValue>) -> Res
         serde_json::json!({})
     };
     
-    // Send POST request
-    let response = client
-        .post(&url)
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
+    let mut attempt = 0;
+    loop {
+        // Released before backing off so a waiting retry doesn't hold a slot
+        let slot = acquire_call_slot().await?;
+        let result = send_attempt(method, &body, timeout, trace_id).await;
+        drop(slot);
+        match &result {
+            Err(e) if e.unreachable => record_unreachable(),
+            _ => record_reachable(),
+        }
+        
+        match result {
+            Ok(response) => return Ok(response),
+            Err(e) if e.retryable && attempt < retries && !circuit_open() => {
+                let delay = backoff.saturating_mul(1u32 << attempt.min(16));
+                log::warn!(
+                    "oRPC call {} [{}] failed ({}); retrying in {}ms",
+                    method,
+                    trace_id,
+                    e.message,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e.message),
+        }
+    }
+}
+
+/// Make a single request to the backend
+async fn send_attempt(
+    method: &str,
+    body: &JsonValue,
+    timeout: Option<Duration>,
+    trace_id: &str,
+) -> Result<JsonValue, AttemptError> {
+    let fatal = |message: String| AttemptError {
+        message,
+        retryable: false,
+        unreachable: false,
+    };
+    
+    let client = ensure_client().map_err(fatal)?;
+    // The port changes if the backend restarted between attempts
+    let base_url = get_backend_url().map_err(|message| AttemptError {
+        message,
+        retryable: true,
+        unreachable: true,
+    })?;
+    
+    let _endpoint_call = EndpointCall::start(endpoint_key());
+    
+    // Build URL: http://127.0.0.1:{port}/orpc/{method}
+    let url = format!("{}/orpc/{}", base_url, method);
+    
+    // Send POST request, authenticated with the sidecar's token
+    let mut request = client.post(&url).header(TRACE_HEADER, trace_id);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let bridge = settings::current().bridge;
+    if bridge.msgpack {
+        request = request.header(
+            reqwest::header::ACCEPT,
+            format!("{}, application/json;q=0.9", MSGPACK_CONTENT_TYPE),
+        );
+    }
+    let (content_type, encoded) = if bridge.msgpack && MSGPACK_SUPPORTED.load(Ordering::SeqCst) {
+        let encoded = rmp_serde::to_vec_named(body)
+            .map_err(|e| fatal(format!("Failed to encode request: {}", e)))?;
+        (MSGPACK_CONTENT_TYPE, encoded)
+    } else {
+        let encoded = serde_json::to_vec(body)
+            .map_err(|e| fatal(format!("Failed to encode request: {}", e)))?;
+        ("application/json", encoded)
+    };
+    request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+    
+    // The client decompresses responses itself; without compression, ask
+    // the backend not to compress
+    if bridge.compression {
+        request = request.header(COMPRESSION_THRESHOLD_HEADER, bridge.compression_threshold_bytes);
+    } else {
+        request = request.header(reqwest::header::ACCEPT_ENCODING, "identity");
+    }
+    if bridge.compression && encoded.len() >= bridge.compression_threshold_bytes {
+        request = request
+            .header(reqwest::header::CONTENT_ENCODING, "gzip")
+            .body(gzip(&encoded).map_err(fatal)?);
+    } else {
+        request = request.body(encoded);
+    }
+    if let Some(token) = sidecar::get_auth_token() {
+        request = request.bearer_auth(token);
+    }
+    
+    let response = request.send().await.map_err(|e| AttemptError {
+        message: if let (true, Some(timeout)) = (e.is_timeout(), timeout) {
+            format!("Request timed out after {}ms", timeout.as_millis())
+        } else {
+            format!("Failed to send request: {}", e)
+        },
+        retryable: e.is_timeout() || e.is_connect(),
+        unreachable: e.is_connect(),
+    })?;
+    
+    let status = response.status();
+    let is_msgpack = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with(MSGPACK_CONTENT_TYPE))
+        .unwrap_or(false);
+    if is_msgpack {
+        MSGPACK_SUPPORTED.store(true, Ordering::SeqCst);
+    }
     
     // Check response status
+    if !status.is_success() {
+        let error_text = match response.bytes().await {
+            Ok(bytes) if is_msgpack => rmp_serde::from_slice::<JsonValue>(&bytes)
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| "Unable to decode error response".to_string()),
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(_) => "Unable to read error response".to_string(),
+        };
+        return Err(AttemptError {
+            message: format!("oRPC server returned error {}: {}", status, error_text),
+            retryable: matches!(status.as_u16(), 502..=504),
+            unreachable: false,
+        });
+    }
+    
+    // Parse and return response
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| fatal(format!("Failed to read response: {}", e)))?;
+    let response_json = if is_msgpack {
+        rmp_serde::from_slice::<JsonValue>(&bytes).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_slice::<JsonValue>(&bytes).map_err(|e| e.to_string())
+    }
+    .map_err(|e| fatal(format!("Failed to parse response: {}", e)))?;
+    
+    Ok(response_json)
+}
+
+/// Split complete SSE events off the front of `buffer`, returning their data
+fn drain_sse_events(buffer: &mut String) -> Vec<String> {
+    let mut events = Vec::new();
+    
+    while let Some(end) = buffer.find("\n\n") {
+        let block: String = buffer.drain(..end + 2).collect();
+        let data: Vec<&str> = block
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| data.strip_prefix(' ').unwrap_or(data))
+            .collect();
+        if !data.is_empty() {
+            events.push(data.join("\n"));
+        }
+    }
+    
+    events
+}
+
+/// Take the longest valid UTF-8 prefix, leaving a split character pending
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(text) => text.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        // Invalid bytes rather than a split character; don't stall on them
+        Err(_) => pending.len(),
+    };
+    let bytes: Vec<u8> = pending.drain(..valid).collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn emit_chunk(app: &AppHandle, call_id: &str, data: &str) {
+    let data = serde_json::from_str(data).unwrap_or_else(|_| JsonValue::String(data.to_string()));
+    let payload = StreamChunkPayload {
+        call_id: call_id.to_string(),
+        data,
+    };
+    if let Err(e) = app.emit("orpc-stream-chunk", payload) {
+        log::error!("Failed to emit orpc-stream-chunk event: {}", e);
+    }
+}
+
+/// Read a streamed response, emitting each SSE event or chunk
+async fn pump_stream(app: &AppHandle, call_id: &str, mut response: reqwest::Response) -> Result<(), String> {
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response
@@ -87,13 +872,614 @@ pub async fn forward_orpc_call(method: String, params: Option<JsonValue>) -> Res
         return Err(format!("oRPC server returned error {}: {}", status, error_text));
     }
     
-    // Parse and return response
-    let response_json = response
+    let is_sse = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("text/event-stream"))
+        .unwrap_or(false);
+    
+    let mut pending = Vec::new();
+    let mut buffer = String::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Stream interrupted: {}", e))?
+    {
+        pending.extend_from_slice(&chunk);
+        let text = take_utf8(&mut pending);
+        if text.is_empty() {
+            continue;
+        }
+        if !is_sse {
+            emit_chunk(app, call_id, &text);
+            continue;
+        }
+        
+        // SSE uses LF or CRLF line endings
+        buffer.push_str(&text.replace("\r\n", "\n"));
+        for data in drain_sse_events(&mut buffer) {
+            emit_chunk(app, call_id, &data);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Start a streaming oRPC call
+///
+/// Returns a call ID immediately; the response arrives as
+/// `orpc-stream-chunk` events followed by one `orpc-stream-end` event.
+#[tauri::command]
+pub async fn forward_orpc_stream(
+    app: AppHandle,
+    method: String,
+    params: Option<JsonValue>,
+) -> Result<String, String> {
+    wait_until_ready().await?;
+    
+    let client = ensure_client()?;
+    let url = format!("{}/orpc/{}", get_backend_url()?, method);
+    let body = params.unwrap_or_else(|| serde_json::json!({}));
+    
+    let trace_id = new_trace_id();
+    log::debug!("oRPC stream {} [{}]", method, trace_id);
+    let mut request = client
+        .post(&url)
+        .header(TRACE_HEADER, trace_id)
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .json(&body);
+    if let Some(token) = sidecar::get_auth_token() {
+        request = request.bearer_auth(token);
+    }
+    
+    // Prefixed so they can't collide with caller-chosen IDs
+    let call_id = format!("stream-{}", NEXT_CALL_ID.fetch_add(1, Ordering::SeqCst));
+    let stream_id = call_id.clone();
+    
+    // Registered before returning so the caller can cancel it right away
+    let registration = CallRegistration::register(call_id.clone())?;
+    
+    // Counted from now so a restart drains the stream, not just unary calls
+    let endpoint_call = EndpointCall::start(endpoint_key());
+    tauri::async_runtime::spawn(async move {
+        let _endpoint_call = endpoint_call;
+        let stream = async {
+            // Held until the stream ends
+            let _slot = acquire_call_slot().await?;
+            match request.send().await {
+                Ok(response) => pump_stream(&app, &stream_id, response).await,
+                Err(e) => Err(format!("Failed to send request: {}", e)),
+            }
+        };
+        let result = registration.run(stream).await;
+        
+        let payload = StreamEndPayload {
+            call_id: stream_id,
+            error: result.err(),
+        };
+        if let Err(e) = app.emit("orpc-stream-end", payload) {
+            log::error!("Failed to emit orpc-stream-end event: {}", e);
+        }
+    });
+    
+    Ok(call_id)
+}
+
+/// Size of file chunks sent to the backend
+const UPLOAD_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Payload of the `orpc-transfer-progress` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TransferProgressPayload {
+    pub transfer_id: String,
+    pub transferred: u64,
+    /// Total size, when known
+    pub total: Option<u64>,
+}
+
+fn emit_progress(app: &AppHandle, transfer_id: &str, transferred: u64, total: Option<u64>) {
+    let payload = TransferProgressPayload {
+        transfer_id: transfer_id.to_string(),
+        transferred,
+        total,
+    };
+    if let Err(e) = app.emit("orpc-transfer-progress", payload) {
+        log::error!("Failed to emit orpc-transfer-progress event: {}", e);
+    }
+}
+
+/// Fail with the backend's error text for unsuccessful responses
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    
+    let status = response.status();
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unable to read error response".to_string());
+    Err(format!("oRPC server returned error {}: {}", status, error_text))
+}
+
+/// Upload a file to an oRPC method as a raw request body
+///
+/// The file is streamed from disk with `orpc-transfer-progress` events
+/// and its name is sent in a `Content-Disposition` header.
+/// Can be cancelled with `cancel_orpc_call` using the transfer ID.
+#[tauri::command]
+pub async fn orpc_upload(
+    app: AppHandle,
+    method: String,
+    file_path: PathBuf,
+    transfer_id: Option<String>,
+) -> Result<JsonValue, String> {
+    let transfer_id =
+        transfer_id.unwrap_or_else(|| format!("upload-{}", NEXT_CALL_ID.fetch_add(1, Ordering::SeqCst)));
+    cancellable(Some(transfer_id.clone()), upload(app, method, file_path, transfer_id)).await
+}
+
+/// `Content-Disposition` naming an uploaded file (RFC 6266): an ASCII
+/// fallback plus the exact name, percent-encoded, in `filename*`
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| if c == ' ' || (c.is_ascii_graphic() && c != '"' && c != '\\') { c } else { '_' })
+        .collect();
+    let encoded = percent_encoding::utf8_percent_encode(file_name, percent_encoding::NON_ALPHANUMERIC);
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+}
+
+async fn upload(app: AppHandle, method: String, file_path: PathBuf, transfer_id: String) -> Result<JsonValue, String> {
+    wait_until_ready().await?;
+    let _slot = acquire_call_slot().await?;
+    let _endpoint_call = EndpointCall::start(endpoint_key());
+    
+    let file = tokio::fs::File::open(&file_path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", file_path.display(), e))?;
+    let total = file
+        .metadata()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", file_path.display(), e))?
+        .len();
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    
+    let progress_app = app.clone();
+    let progress_id = transfer_id.clone();
+    let chunks = futures_util::stream::unfold((file, 0u64), move |(mut file, sent)| {
+        let app = progress_app.clone();
+        let transfer_id = progress_id.clone();
+        async move {
+            let mut buf = vec![0u8; UPLOAD_CHUNK_BYTES];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(read) => {
+                    buf.truncate(read);
+                    let sent = sent + read as u64;
+                    emit_progress(&app, &transfer_id, sent, Some(total));
+                    Some((Ok::<_, std::io::Error>(buf), (file, sent)))
+                }
+                Err(e) => Some((Err(e), (file, sent))),
+            }
+        }
+    });
+    
+    let client = ensure_client()?;
+    let url = format!("{}/orpc/{}", get_backend_url()?, method);
+    
+    let trace_id = new_trace_id();
+    log::debug!("oRPC upload {} [{}]: {} bytes", method, trace_id, total);
+    let mut request = client
+        .post(&url)
+        .header(TRACE_HEADER, trace_id)
+        .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+        .header(reqwest::header::CONTENT_LENGTH, total)
+        .header(reqwest::header::CONTENT_DISPOSITION, content_disposition(&file_name))
+        .body(reqwest::Body::wrap_stream(chunks));
+    if let Some(token) = sidecar::get_auth_token() {
+        request = request.bearer_auth(token);
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Upload failed: {}", e))?;
+    check_status(response)
+        .await?
         .json::<JsonValue>()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// One part of a multipart/form-data request
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MultipartPart {
+    /// Plain form field
+    Text { name: String, value: String },
+    /// File streamed from disk
+    File {
+        name: String,
+        path: PathBuf,
+        file_name: Option<String>,
+        mime_type: Option<String>,
+    },
+    /// Bytes held by the frontend, e.g. an image pasted from the clipboard
+    Bytes {
+        name: String,
+        /// Base64-encoded, since IPC sends byte arrays as JSON number lists
+        data: String,
+        file_name: Option<String>,
+        mime_type: Option<String>,
+    },
+}
+
+/// Payload of the `orpc-multipart-progress` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MultipartProgressPayload {
+    pub transfer_id: String,
+    /// Index of the part in the request
+    pub part: usize,
+    pub name: String,
+    pub transferred: u64,
+    pub total: u64,
+}
+
+/// Stream a part's chunks, reporting progress for that part
+fn part_body<S>(app: AppHandle, transfer_id: String, part: usize, name: String, total: u64, chunks: S) -> reqwest::Body
+where
+    S: futures_util::Stream<Item = std::io::Result<Vec<u8>>> + Send + 'static,
+{
+    let mut sent = 0u64;
+    let chunks = chunks.map(move |chunk| {
+        if let Ok(ref bytes) = chunk {
+            sent += bytes.len() as u64;
+            let payload = MultipartProgressPayload {
+                transfer_id: transfer_id.clone(),
+                part,
+                name: name.clone(),
+                transferred: sent,
+                total,
+            };
+            if let Err(e) = app.emit("orpc-multipart-progress", payload) {
+                log::error!("Failed to emit orpc-multipart-progress event: {}", e);
+            }
+        }
+        chunk
+    });
+    reqwest::Body::wrap_stream(chunks)
+}
+
+/// Build a file-like part with an optional file name and MIME type
+fn file_part(
+    body: reqwest::Body,
+    length: u64,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+) -> Result<reqwest::multipart::Part, String> {
+    let mut part = reqwest::multipart::Part::stream_with_length(body, length);
+    if let Some(file_name) = file_name {
+        part = part.file_name(file_name);
+    }
+    if let Some(mime_type) = mime_type {
+        part = part
+            .mime_str(&mime_type)
+            .map_err(|e| format!("Invalid MIME type {}: {}", mime_type, e))?;
+    }
+    Ok(part)
+}
+
+/// Send multipart/form-data to a backend endpoint
+///
+/// File and byte parts are streamed with `orpc-multipart-progress` events
+/// per part. Can be cancelled with `cancel_orpc_call` using the transfer ID.
+#[tauri::command]
+pub async fn orpc_multipart(
+    app: AppHandle,
+    path: String,
+    parts: Vec<MultipartPart>,
+    transfer_id: Option<String>,
+) -> Result<JsonValue, String> {
+    let transfer_id =
+        transfer_id.unwrap_or_else(|| format!("multipart-{}", NEXT_CALL_ID.fetch_add(1, Ordering::SeqCst)));
+    cancellable(Some(transfer_id.clone()), multipart(app, path, parts, transfer_id)).await
+}
+
+async fn multipart(app: AppHandle, path: String, parts: Vec<MultipartPart>, transfer_id: String) -> Result<JsonValue, String> {
+    if !path.starts_with('/') || path.starts_with("//") {
+        return Err("Path must be absolute on the backend (e.g. /upload)".to_string());
+    }
+    wait_until_ready().await?;
+    let _slot = acquire_call_slot().await?;
+    let _endpoint_call = EndpointCall::start(endpoint_key());
     
-    Ok(response_json)
+    let mut form = reqwest::multipart::Form::new();
+    for (index, part) in parts.into_iter().enumerate() {
+        form = match part {
+            MultipartPart::Text { name, value } => form.text(name, value),
+            MultipartPart::File { name, path, file_name, mime_type } => {
+                let file = tokio::fs::File::open(&path)
+                    .await
+                    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+                let total = file
+                    .metadata()
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+                    .len();
+                let file_name = file_name.or_else(|| {
+                    path.file_name().map(|name| name.to_string_lossy().to_string())
+                });
+                let chunks = futures_util::stream::unfold(file, |mut file| async move {
+                    let mut buf = vec![0u8; UPLOAD_CHUNK_BYTES];
+                    match file.read(&mut buf).await {
+                        Ok(0) => None,
+                        Ok(read) => {
+                            buf.truncate(read);
+                            Some((Ok(buf), file))
+                        }
+                        Err(e) => Some((Err(e), file)),
+                    }
+                });
+                let body = part_body(app.clone(), transfer_id.clone(), index, name.clone(), total, chunks);
+                form.part(name, file_part(body, total, file_name, mime_type)?)
+            }
+            MultipartPart::Bytes { name, data, file_name, mime_type } => {
+                let data = base64::engine::general_purpose::STANDARD
+                    .decode(data)
+                    .map_err(|e| format!("Invalid base64 in part {}: {}", name, e))?;
+                let total = data.len() as u64;
+                let chunks = futures_util::stream::iter(
+                    data.chunks(UPLOAD_CHUNK_BYTES)
+                        .map(|chunk| Ok(chunk.to_vec()))
+                        .collect::<Vec<_>>(),
+                );
+                let body = part_body(app.clone(), transfer_id.clone(), index, name.clone(), total, chunks);
+                form.part(name, file_part(body, total, file_name, mime_type)?)
+            }
+        };
+    }
+    
+    let client = ensure_client()?;
+    let url = format!("{}{}", get_backend_url()?, path);
+    let trace_id = new_trace_id();
+    log::debug!("oRPC multipart {} [{}]", path, trace_id);
+    let mut request = client
+        .post(&url)
+        .header(TRACE_HEADER, trace_id)
+        .multipart(form);
+    if let Some(token) = sidecar::get_auth_token() {
+        request = request.bearer_auth(token);
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Upload failed: {}", e))?;
+    check_status(response)
+        .await?
+        .json::<JsonValue>()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Call an oRPC method and stream its response body to a file
+///
+/// Progress is reported with `orpc-transfer-progress` events. The file is
+/// written next to `dest_path` and only moved into place once complete.
+/// Returns the number of bytes written.
+#[tauri::command]
+pub async fn orpc_download(
+    app: AppHandle,
+    method: String,
+    params: Option<JsonValue>,
+    dest_path: PathBuf,
+    transfer_id: Option<String>,
+) -> Result<u64, String> {
+    let transfer_id =
+        transfer_id.unwrap_or_else(|| format!("download-{}", NEXT_CALL_ID.fetch_add(1, Ordering::SeqCst)));
+    
+    let mut partial = dest_path.clone().into_os_string();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    
+    let result = cancellable(
+        Some(transfer_id.clone()),
+        download(app, method, params, &partial, transfer_id),
+    )
+    .await;
+    
+    match result {
+        Ok(written) => {
+            tokio::fs::rename(&partial, &dest_path)
+                .await
+                .map_err(|e| format!("Failed to move download to {}: {}", dest_path.display(), e))?;
+            Ok(written)
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            Err(e)
+        }
+    }
+}
+
+async fn download(
+    app: AppHandle,
+    method: String,
+    params: Option<JsonValue>,
+    partial: &std::path::Path,
+    transfer_id: String,
+) -> Result<u64, String> {
+    wait_until_ready().await?;
+    let _slot = acquire_call_slot().await?;
+    let _endpoint_call = EndpointCall::start(endpoint_key());
+    
+    let client = ensure_client()?;
+    let url = format!("{}/orpc/{}", get_backend_url()?, method);
+    let body = params.unwrap_or_else(|| serde_json::json!({}));
+    
+    let trace_id = new_trace_id();
+    log::debug!("oRPC download {} [{}]", method, trace_id);
+    let mut request = client
+        .post(&url)
+        .header(TRACE_HEADER, trace_id)
+        .header(reqwest::header::ACCEPT, "application/octet-stream")
+        .json(&body);
+    if let Some(token) = sidecar::get_auth_token() {
+        request = request.bearer_auth(token);
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Download failed: {}", e))?;
+    let mut response = check_status(response).await?;
+    let total = response.content_length();
+    
+    let mut file = tokio::fs::File::create(partial)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+    let mut written = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download interrupted: {}", e))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+        written += chunk.len() as u64;
+        emit_progress(&app, &transfer_id, written, total);
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+    
+    Ok(written)
+}
+
+/// A request to an arbitrary backend endpoint
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct HttpRequest {
+    /// HTTP method, e.g. "GET" or "DELETE"
+    pub method: String,
+    /// Absolute path on the backend, e.g. "/health/details"
+    pub path: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub query: Vec<(String, String)>,
+    /// Sent as JSON when present
+    pub body: Option<JsonValue>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Response of `forward_http_request`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    /// Parsed JSON when possible, otherwise the body text
+    pub body: JsonValue,
+}
+
+/// Headers the bridge sets itself
+const RESERVED_HEADERS: [&str; 3] = ["authorization", "host", "x-trace-id"];
+
+/// Forward a request with any method, headers, and query to the backend
+///
+/// Unlike `forward_orpc_call`, non-2xx responses are returned rather than
+/// treated as errors, so callers can inspect the status.
+#[tauri::command]
+pub async fn forward_http_request(request: HttpRequest) -> Result<HttpResponse, String> {
+    if !request.path.starts_with('/') || request.path.starts_with("//") {
+        return Err("Path must be absolute on the backend (e.g. /health)".to_string());
+    }
+    let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", request.method))?;
+    
+    if circuit_open() {
+        return Err("Backend unavailable".to_string());
+    }
+    wait_until_ready().await?;
+    let _slot = acquire_call_slot().await?;
+    let _endpoint_call = EndpointCall::start(endpoint_key());
+    
+    let client = ensure_client()?;
+    let url = format!("{}{}", get_backend_url()?, request.path);
+    let timeout = request
+        .timeout_ms
+        .map(Duration::from_millis)
+        .or(settings::current().bridge.request_timeout_secs.map(Duration::from_secs));
+    let trace_id = new_trace_id();
+    
+    let mut builder = client
+        .request(method.clone(), &url)
+        .header(TRACE_HEADER, &trace_id)
+        .query(&request.query);
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    for (name, value) in &request.headers {
+        if RESERVED_HEADERS.contains(&name.to_lowercase().as_str()) {
+            return Err(format!("Header {} is set by the bridge", name));
+        }
+        builder = builder.header(name, value);
+    }
+    if let Some(ref body) = request.body {
+        builder = builder.json(body);
+    }
+    if let Some(token) = sidecar::get_auth_token() {
+        builder = builder.bearer_auth(token);
+    }
+    
+    let started = Instant::now();
+    let response = builder.send().await.map_err(|e| format!("Failed to send request: {}", e))?;
+    
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    let body = serde_json::from_str(&text).unwrap_or(JsonValue::String(text));
+    
+    let label = format!("{} {}", method, request.path);
+    orpc_metrics::record(&label, started.elapsed(), status < 500);
+    log::debug!(
+        "HTTP {} [{}] returned {} in {}ms",
+        label,
+        trace_id,
+        status,
+        started.elapsed().as_millis()
+    );
+    
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// Cancel an in-flight call or stream by its call ID
+///
+/// Returns false if the call already finished.
+#[tauri::command]
+pub async fn cancel_orpc_call(call_id: String) -> Result<bool, String> {
+    let sender = in_flight()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&call_id);
+    
+    Ok(sender.map(|(_, sender)| sender.send(()).is_ok()).unwrap_or(false))
 }
 
 /// Check if the oRPC server is available
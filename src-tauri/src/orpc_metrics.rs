@@ -0,0 +1,91 @@
+// oRPC bridge metrics
+//
+// Counts calls and errors per method and keeps a window of recent latencies
+// for percentiles, so "the app feels slow" reports can be narrowed down to
+// specific backend methods from inside the app.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Latency samples kept per method
+const MAX_SAMPLES: usize = 1000;
+
+#[derive(Default)]
+struct MethodStats {
+    calls: u64,
+    errors: u64,
+    latencies_ms: VecDeque<f64>,
+}
+
+static STATS: Mutex<Option<HashMap<String, MethodStats>>> = Mutex::new(None);
+
+/// Metrics for one method
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MethodMetrics {
+    pub method: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    /// Latency percentiles over the most recent calls
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Record a finished call
+pub fn record(method: &str, elapsed: Duration, ok: bool) {
+    let Ok(mut guard) = STATS.lock() else {
+        return;
+    };
+    let stats = guard
+        .get_or_insert_with(HashMap::new)
+        .entry(method.to_string())
+        .or_default();
+
+    stats.calls += 1;
+    if !ok {
+        stats.errors += 1;
+    }
+    if stats.latencies_ms.len() == MAX_SAMPLES {
+        stats.latencies_ms.pop_front();
+    }
+    stats.latencies_ms.push_back(elapsed.as_secs_f64() * 1000.0);
+}
+
+/// Nearest-rank percentile of sorted samples
+pub(crate) fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Get call counts, error rates, and latency percentiles per method
+///
+/// Methods are ordered by call count, busiest first.
+#[tauri::command]
+pub async fn get_orpc_metrics() -> Result<Vec<MethodMetrics>, String> {
+    let guard = STATS.lock().map_err(|e| e.to_string())?;
+    let mut metrics: Vec<MethodMetrics> = guard
+        .iter()
+        .flatten()
+        .map(|(method, stats)| {
+            let mut sorted: Vec<f64> = stats.latencies_ms.iter().copied().collect();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            MethodMetrics {
+                method: method.clone(),
+                calls: stats.calls,
+                errors: stats.errors,
+                error_rate: stats.errors as f64 / stats.calls.max(1) as f64,
+                p50_ms: percentile(&sorted, 50.0),
+                p95_ms: percentile(&sorted, 95.0),
+                p99_ms: percentile(&sorted, 99.0),
+            }
+        })
+        .collect();
+    metrics.sort_by(|a, b| b.calls.cmp(&a.calls));
+
+    Ok(metrics)
+}
@@ -0,0 +1,184 @@
+// Offline queue for bridge calls
+//
+// Calls flagged `queueable` that are made while the backend is down are
+// written to a queue file in the profile's data directory instead of
+// failing, so they survive an app restart. After `backend-ready` the queue
+// is replayed in order and each outcome is emitted as
+// `orpc-queued-call-completed`.
+
+use serde_json::Value as JsonValue;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Listener};
+
+use crate::orpc_bridge::{self, CallOptions};
+use crate::profiles;
+
+/// Queue file name in the data directory
+const QUEUE_FILE: &str = "bridge-queue.json";
+
+/// Calls kept at most; the queue is for short outages, not a data store
+const MAX_QUEUED: usize = 500;
+
+/// Serializes reads and writes of the queue file
+static QUEUE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Set while a replay is running
+static REPLAYING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct QueuedCall {
+    id: String,
+    method: String,
+    params: Option<JsonValue>,
+    queued_at_ms: u64,
+}
+
+/// Payload of the `orpc-queued-call-completed` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueuedCallCompletedPayload {
+    pub queue_id: String,
+    pub method: String,
+    pub result: Option<JsonValue>,
+    pub error: Option<String>,
+}
+
+fn queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(profiles::data_dir(app)?.join(QUEUE_FILE))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn load(path: &PathBuf) -> Vec<QueuedCall> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Discarding invalid bridge queue {}: {}", path.display(), e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Write the queue atomically, removing the file when empty
+fn store(path: &PathBuf, calls: &[QueuedCall]) -> Result<(), String> {
+    if calls.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to clear bridge queue: {}", e))
+            }
+            _ => Ok(()),
+        };
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let contents = serde_json::to_string(calls)
+        .map_err(|e| format!("Failed to serialize bridge queue: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents).map_err(|e| format!("Failed to write bridge queue: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write bridge queue: {}", e))
+}
+
+/// Store a call for replay, returning its queue ID
+pub fn enqueue(app: &AppHandle, method: String, params: Option<JsonValue>) -> Result<String, String> {
+    let path = queue_path(app)?;
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let mut calls = load(&path);
+    if calls.len() >= MAX_QUEUED {
+        return Err("Backend unavailable and the offline queue is full".to_string());
+    }
+
+    let id = format!("queued-{}-{}", now_ms(), rand::random::<u32>());
+    log::info!("Backend unavailable; queued {} as {}", method, id);
+    calls.push(QueuedCall {
+        id: id.clone(),
+        method,
+        params,
+        queued_at_ms: now_ms(),
+    });
+    store(&path, &calls)?;
+
+    Ok(id)
+}
+
+/// Remove and return the oldest queued call
+fn pop_front(path: &PathBuf) -> Result<Option<QueuedCall>, String> {
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut calls = load(path);
+    if calls.is_empty() {
+        return Ok(None);
+    }
+    let call = calls.remove(0);
+    store(path, &calls)?;
+    Ok(Some(call))
+}
+
+/// Put a call back at the front after the backend went away again
+fn push_front(path: &PathBuf, call: QueuedCall) -> Result<(), String> {
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut calls = load(path);
+    calls.insert(0, call);
+    store(path, &calls)
+}
+
+/// Replay queued calls in order until the queue is empty or the backend
+/// becomes unavailable again
+async fn replay(app: &AppHandle) -> Result<(), String> {
+    let path = queue_path(app)?;
+
+    while let Some(call) = pop_front(&path)? {
+        let result = orpc_bridge::send_call(
+            call.method.clone(),
+            call.params.clone(),
+            CallOptions::default(),
+        )
+        .await;
+
+        if result.is_err() && orpc_bridge::circuit_open() {
+            log::info!("Backend unavailable again; pausing queue replay");
+            return push_front(&path, call);
+        }
+
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e)),
+        };
+        let payload = QueuedCallCompletedPayload {
+            queue_id: call.id,
+            method: call.method,
+            result,
+            error,
+        };
+        if let Err(e) = app.emit("orpc-queued-call-completed", payload) {
+            log::error!("Failed to emit orpc-queued-call-completed event: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Replay the queue whenever the backend becomes ready
+pub fn init(app: &AppHandle) {
+    let app_handle = app.clone();
+    app.listen_any("backend-ready", move |_| {
+        if REPLAYING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = replay(&app).await {
+                log::error!("Failed to replay queued bridge calls: {}", e);
+            }
+            REPLAYING.store(false, Ordering::SeqCst);
+        });
+    });
+}
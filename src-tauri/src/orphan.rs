@@ -0,0 +1,145 @@
+// Orphaned sidecar detection
+//
+// The sidecar's pid, endpoint, and auth token are recorded in a PID file
+// while it runs. If the app crashes the sidecar can survive it; on the next
+// launch the record is used to find that backend and either adopt it (when
+// it is healthy and version-compatible) or kill it, so two backends never
+// fight over the same data files.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use sysinfo::{Pid, System};
+use tauri::AppHandle;
+
+use crate::process_tree::ProcessTree;
+use crate::{backend_version, profiles, sidecar};
+
+/// PID file name inside the profile data directory
+const PID_FILE: &str = "sidecar.pid.json";
+
+/// Substring of the sidecar's process name, guarding against pid reuse
+const SIDECAR_PROCESS_NAME: &str = "mup-server";
+
+/// Persisted record of a running sidecar
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PidRecord {
+    pub pid: u32,
+    pub port: u16,
+    pub socket: Option<PathBuf>,
+    pub auth_token: Option<String>,
+}
+
+static PID_FILE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Resolve the PID file location for the active profile
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let path = profiles::data_dir(app)?.join(PID_FILE);
+    let _ = PID_FILE_PATH.set(path);
+    Ok(())
+}
+
+/// Record the running sidecar
+pub fn write_record(record: &PidRecord) {
+    let path = match PID_FILE_PATH.get() {
+        Some(path) => path,
+        None => return,
+    };
+
+    let contents = match serde_json::to_string(record) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::warn!("Failed to serialize sidecar PID record: {}", e);
+            return;
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(path, contents) {
+        log::warn!("Failed to write sidecar PID file: {}", e);
+        return;
+    }
+
+    // The record holds the auth token
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+}
+
+/// Remove the record once the sidecar has stopped
+pub fn remove_record() {
+    if let Some(path) = PID_FILE_PATH.get() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn read_record() -> Option<PidRecord> {
+    let path = PID_FILE_PATH.get()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Whether `pid` is still a running sidecar
+pub(crate) fn is_sidecar_alive(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        return false;
+    }
+
+    system
+        .process(pid)
+        .map(|process| process.name().contains(SIDECAR_PROCESS_NAME))
+        .unwrap_or(false)
+}
+
+/// Adopt or kill a sidecar left over from a previous run
+///
+/// Returns true if a running backend was adopted and no new sidecar should
+/// be spawned.
+pub async fn recover(app: &AppHandle) -> bool {
+    let record = match read_record() {
+        Some(record) => record,
+        None => return false,
+    };
+
+    if record.pid == std::process::id() || !is_sidecar_alive(record.pid) {
+        remove_record();
+        return false;
+    }
+
+    log::warn!("Found sidecar from a previous run (pid {})", record.pid);
+
+    // Point the bridge at it and see whether it is usable
+    sidecar::adopt(&record);
+    let healthy = sidecar::check_backend_health().await.unwrap_or(false);
+    let compatible = match backend_version::query().await {
+        Ok(version) => version.compatible,
+        Err(_) => false,
+    };
+
+    if healthy && compatible {
+        log::info!("Adopted running sidecar (pid {})", record.pid);
+        sidecar::announce_adopted(app);
+        return true;
+    }
+
+    log::warn!(
+        "Killing unusable sidecar from a previous run (pid {}, healthy: {}, compatible: {})",
+        record.pid,
+        healthy,
+        compatible
+    );
+    if let Err(e) = sidecar::terminate_sidecar().await {
+        log::error!("{}", e);
+    }
+    // Fall back to killing by pid if the adopted state was already cleared
+    if is_sidecar_alive(record.pid) {
+        ProcessTree::attach(record.pid).kill_all();
+    }
+    remove_record();
+    false
+}
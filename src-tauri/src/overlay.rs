@@ -0,0 +1,316 @@
+// Activity overlay
+//
+// A small always-on-top, click-through window in a screen corner that shows
+// transient progress toasts for agent activity, so progress stays visible
+// while the main window is hidden to the tray. Toasts come from the backend
+// as `overlay-toast` / `overlay-toast-dismiss` push events (see
+// backend_events) or from the frontend through commands. A toast re-sent
+// with the same id updates it in place and restarts its timeout. The window
+// is created on first use, sized to its toasts and hidden when none remain.
+// It is destroyed when the main window closes so it can't keep the app
+// running on its own.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, Listener, LogicalSize, Manager, PhysicalPosition, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::{profiles, settings};
+
+/// Label of the overlay window
+const WINDOW_LABEL: &str = "overlay";
+
+/// Page rendering the toasts
+const WINDOW_URL: &str = "overlay.html";
+
+/// Logical width of the overlay, and the height given to each toast
+const TOAST_WIDTH: f64 = 360.0;
+const TOAST_HEIGHT: f64 = 76.0;
+
+/// Gap kept between the overlay and the screen edges, in logical pixels
+const EDGE_MARGIN: f64 = 16.0;
+
+/// Active toasts, oldest first
+static TOASTS: Mutex<Vec<ActiveToast>> = Mutex::new(Vec::new());
+
+/// Bumped on every show, so stale timeouts leave updated toasts alone
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Set once the overlay is closed for good
+static CLOSED: AtomicBool = AtomicBool::new(false);
+
+/// Screen corner the overlay sits in
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Overlay settings, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    pub enabled: bool,
+    pub corner: OverlayCorner,
+    /// How long a toast stays up when it doesn't set its own timeout
+    pub timeout_ms: u64,
+    /// Toasts shown at once; the oldest are dropped beyond this
+    pub max_toasts: usize,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            corner: OverlayCorner::default(),
+            timeout_ms: 5_000,
+            max_toasts: 4,
+        }
+    }
+}
+
+/// A progress toast
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct OverlayToast {
+    /// Toasts with the same id replace each other
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Completion between 0 and 1; absent for indeterminate work
+    #[serde(default)]
+    pub progress: Option<f64>,
+    /// Overrides the configured timeout for this toast
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+struct ActiveToast {
+    toast: OverlayToast,
+    generation: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct DismissRequest {
+    id: String,
+}
+
+fn toasts() -> Vec<OverlayToast> {
+    TOASTS
+        .lock()
+        .map(|guard| guard.iter().map(|active| active.toast.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Get the overlay window, creating it hidden on first use
+fn window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        return Ok(window);
+    }
+    if CLOSED.load(Ordering::SeqCst) {
+        return Err("Overlay is closed".to_string());
+    }
+
+    let builder = WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App(WINDOW_URL.into()))
+        .title("mux activity")
+        .inner_size(TOAST_WIDTH, TOAST_HEIGHT)
+        .decorations(false)
+        .always_on_top(true)
+        .visible_on_all_workspaces(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .shadow(false)
+        .visible(false);
+    // Transparent windows need the private API on macOS; the toast page
+    // fills the window there instead
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.transparent(true);
+
+    let window = profiles::with_webview_storage(app, builder)
+        .build()
+        .map_err(|e| format!("Failed to create overlay window: {}", e))?;
+    // Clicks pass through to whatever is underneath
+    window
+        .set_ignore_cursor_events(true)
+        .map_err(|e| format!("Failed to make overlay click-through: {}", e))?;
+    Ok(window)
+}
+
+/// Size the overlay to its toasts and move it into the configured corner
+/// of the monitor the main window is on
+fn place(app: &AppHandle, window: &WebviewWindow, count: usize) -> Result<(), String> {
+    let height = TOAST_HEIGHT * count as f64;
+    window
+        .set_size(LogicalSize::new(TOAST_WIDTH, height))
+        .map_err(|e| format!("Failed to size overlay: {}", e))?;
+
+    let monitor = app
+        .get_webview_window("main")
+        .and_then(|main| main.current_monitor().ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten());
+    let Some(monitor) = monitor else {
+        return Ok(());
+    };
+
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let width = (TOAST_WIDTH * scale).round() as i32;
+    let height = (height * scale).round() as i32;
+    let margin = (EDGE_MARGIN * scale).round() as i32;
+    let (left, top) = (area.position.x, area.position.y);
+    let (right, bottom) = (left + area.size.width as i32, top + area.size.height as i32);
+
+    let (x, y) = match settings::current().overlay.corner {
+        OverlayCorner::TopLeft => (left + margin, top + margin),
+        OverlayCorner::TopRight => (right - width - margin, top + margin),
+        OverlayCorner::BottomLeft => (left + margin, bottom - height - margin),
+        OverlayCorner::BottomRight => (right - width - margin, bottom - height - margin),
+    };
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| format!("Failed to position overlay: {}", e))
+}
+
+/// Push the active toasts to the overlay, showing or hiding it as needed
+fn render(app: &AppHandle) -> Result<(), String> {
+    let toasts = toasts();
+    if toasts.is_empty() {
+        if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+            window
+                .hide()
+                .map_err(|e| format!("Failed to hide overlay: {}", e))?;
+        }
+        return Ok(());
+    }
+
+    let window = window(app)?;
+    place(app, &window, toasts.len())?;
+    if let Err(e) = app.emit_to(WINDOW_LABEL, "overlay-toasts", &toasts) {
+        log::error!("Failed to emit overlay-toasts event: {}", e);
+    }
+    window
+        .show()
+        .map_err(|e| format!("Failed to show overlay: {}", e))
+}
+
+/// Destroy the overlay and stop showing toasts, so that closing the main
+/// window leaves no window holding the app open
+pub fn close(app: &AppHandle) {
+    CLOSED.store(true, Ordering::SeqCst);
+    if let Ok(mut guard) = TOASTS.lock() {
+        guard.clear();
+    }
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        if let Err(e) = window.destroy() {
+            log::warn!("Failed to close overlay: {}", e);
+        }
+    }
+}
+
+/// Show or update a toast and schedule its removal
+pub fn show(app: &AppHandle, toast: OverlayToast) -> Result<(), String> {
+    let config = settings::current().overlay;
+    if !config.enabled || CLOSED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let timeout = Duration::from_millis(toast.timeout_ms.unwrap_or(config.timeout_ms));
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let id = toast.id.clone();
+    {
+        let mut active = TOASTS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        active.retain(|existing| existing.toast.id != id);
+        active.push(ActiveToast { toast, generation });
+        let excess = active.len().saturating_sub(config.max_toasts.max(1));
+        active.drain(..excess);
+    }
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let expired = match TOASTS.lock() {
+            Ok(mut active) => {
+                let before = active.len();
+                active.retain(|existing| existing.toast.id != id || existing.generation != generation);
+                active.len() != before
+            }
+            Err(_) => false,
+        };
+        if expired {
+            if let Err(e) = render(&app_handle) {
+                log::warn!("{}", e);
+            }
+        }
+    });
+
+    render(app)
+}
+
+/// Remove a toast before its timeout
+pub fn dismiss(app: &AppHandle, id: &str) -> Result<(), String> {
+    TOASTS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .retain(|existing| existing.toast.id != id);
+    render(app)
+}
+
+/// Show toasts pushed by the backend
+pub fn init(app: &AppHandle) {
+    let app_handle = app.clone();
+    app.listen_any("backend-event:overlay-toast", move |event| {
+        let toast = match serde_json::from_str::<OverlayToast>(event.payload()) {
+            Ok(toast) => toast,
+            Err(e) => {
+                log::warn!("Ignoring malformed overlay toast: {}", e);
+                return;
+            }
+        };
+        // Windows are created off the event loop thread
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = show(&app, toast) {
+                log::warn!("{}", e);
+            }
+        });
+    });
+
+    let app_handle = app.clone();
+    app.listen_any("backend-event:overlay-toast-dismiss", move |event| {
+        let Ok(request) = serde_json::from_str::<DismissRequest>(event.payload()) else {
+            return;
+        };
+        let app = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = dismiss(&app, &request.id) {
+                log::warn!("{}", e);
+            }
+        });
+    });
+}
+
+/// Show or update a toast in the activity overlay
+#[tauri::command]
+pub async fn show_overlay_toast(app: AppHandle, toast: OverlayToast) -> Result<(), String> {
+    show(&app, toast)
+}
+
+/// Dismiss a toast in the activity overlay
+#[tauri::command]
+pub async fn dismiss_overlay_toast(app: AppHandle, id: String) -> Result<(), String> {
+    dismiss(&app, &id)
+}
+
+/// Get the active overlay toasts, for the overlay page to render on load
+#[tauri::command]
+pub async fn get_overlay_toasts() -> Result<Vec<OverlayToast>, String> {
+    Ok(toasts())
+}
@@ -0,0 +1,403 @@
+// Power and session events
+//
+// Follows system sleep/wake and session lock/unlock and emits
+// `power-suspend`, `power-resume` and `session-locked` for the frontend.
+// Notifications come from the platform: NSWorkspace and the screen lock
+// distributed notifications on macOS, WM_POWERBROADCAST and WTS session
+// changes on Windows, and logind signals (via `gdbus monitor`) on Linux.
+// A clock gap check catches wakes the platform didn't report. It uses a
+// monotonic clock that keeps counting during sleep (CLOCK_BOOTTIME on
+// Linux), so NTP syncs and manual clock changes don't look like sleeps.
+//
+// Backend health checks are paused while the system sleeps and for a
+// short grace period after it wakes, when sockets and the network are
+// still coming back, so a laptop waking up doesn't report a dead backend;
+// the backend is re-verified once the grace period is over.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::health;
+
+/// How often the clock is sampled for sleep gaps
+const TICK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A clock jump longer than this between ticks means the system slept
+const SLEEP_GAP: Duration = Duration::from_secs(20);
+
+/// How long health checks stay paused after waking
+const RESUME_GRACE: Duration = Duration::from_secs(10);
+
+/// App handle for routing platform notifications
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Set between suspend and resume
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+/// Monotonic times in milliseconds of the last suspend, resume and clock tick
+static SUSPENDED_AT: AtomicU64 = AtomicU64::new(0);
+static RESUMED_AT: AtomicU64 = AtomicU64::new(0);
+static LAST_TICK: AtomicU64 = AtomicU64::new(0);
+
+/// Payload of the `power-resume` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PowerResumePayload {
+    /// How long the system slept, when known
+    pub slept_secs: Option<u64>,
+}
+
+/// Payload of the `session-locked` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionLockPayload {
+    pub locked: bool,
+}
+
+/// A platform notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowerEvent {
+    Suspend,
+    Resume,
+    Lock,
+    Unlock,
+}
+
+/// Milliseconds since boot, including time spent asleep
+#[cfg(unix)]
+fn now_ms() -> u64 {
+    // CLOCK_MONOTONIC already counts sleep on macOS
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const CLOCK: libc::clockid_t = libc::CLOCK_BOOTTIME;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const CLOCK: libc::clockid_t = libc::CLOCK_MONOTONIC;
+
+    let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    if unsafe { libc::clock_gettime(CLOCK, &mut time) } != 0 {
+        return 0;
+    }
+    time.tv_sec as u64 * 1000 + time.tv_nsec as u64 / 1_000_000
+}
+
+/// Milliseconds since boot, including time spent asleep
+#[cfg(windows)]
+fn now_ms() -> u64 {
+    unsafe { windows_sys::Win32::System::SystemInformation::GetTickCount64() }
+}
+
+/// Whether backend health checks should wait: the system is asleep, has
+/// just woken, or has slept without the clock check noticing yet
+pub fn checks_paused() -> bool {
+    let now = now_ms();
+    let last_tick = LAST_TICK.load(Ordering::SeqCst);
+    SUSPENDED.load(Ordering::SeqCst)
+        || now.saturating_sub(RESUMED_AT.load(Ordering::SeqCst)) < RESUME_GRACE.as_millis() as u64
+        || (last_tick != 0 && now.saturating_sub(last_tick) > SLEEP_GAP.as_millis() as u64)
+}
+
+fn emit<S: serde::Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        log::error!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+/// Mark the system as woken and re-check the backend after the grace period
+fn resumed(app: &AppHandle, slept_secs: Option<u64>) {
+    RESUMED_AT.store(now_ms(), Ordering::SeqCst);
+    log::info!("System resumed (slept {:?}s)", slept_secs);
+    emit(app, "power-resume", PowerResumePayload { slept_secs });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RESUME_GRACE).await;
+        health::reverify(&app).await;
+    });
+}
+
+fn handle(event: PowerEvent) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    match event {
+        PowerEvent::Suspend => {
+            if SUSPENDED.swap(true, Ordering::SeqCst) {
+                return;
+            }
+            SUSPENDED_AT.store(now_ms(), Ordering::SeqCst);
+            log::info!("System suspending");
+            emit(app, "power-suspend", ());
+        }
+        PowerEvent::Resume => {
+            if !SUSPENDED.swap(false, Ordering::SeqCst) {
+                // Already handled by the clock check
+                return;
+            }
+            let slept = now_ms().saturating_sub(SUSPENDED_AT.load(Ordering::SeqCst)) / 1000;
+            resumed(app, Some(slept));
+        }
+        PowerEvent::Lock | PowerEvent::Unlock => {
+            let locked = event == PowerEvent::Lock;
+            emit(app, "session-locked", SessionLockPayload { locked });
+        }
+    }
+}
+
+/// Detect sleeps the platform didn't report from jumps in the clock
+fn spawn_clock_check(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let previous = LAST_TICK.swap(now_ms(), Ordering::SeqCst);
+        let gap = now_ms().saturating_sub(previous);
+        if previous != 0 && gap > SLEEP_GAP.as_millis() as u64 && !SUSPENDED.load(Ordering::SeqCst) {
+            // A platform resume within the gap already covered this sleep
+            let reported = RESUMED_AT.load(Ordering::SeqCst) > previous;
+            if !reported {
+                emit(&app, "power-suspend", ());
+                resumed(&app, Some(gap / 1000));
+            }
+        }
+        std::thread::sleep(TICK_INTERVAL);
+    });
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, NSObject, Sel};
+    use objc2::{define_class, msg_send, sel, MainThreadMarker, MainThreadOnly};
+    use objc2_foundation::NSString;
+
+    use super::{handle, PowerEvent};
+
+    define_class!(
+        // Receives workspace and screen lock notifications
+        #[unsafe(super(NSObject))]
+        #[thread_kind = MainThreadOnly]
+        #[name = "MupPowerObserver"]
+        struct PowerObserver;
+
+        impl PowerObserver {
+            #[unsafe(method(willSleep:))]
+            fn will_sleep(&self, _notification: &AnyObject) {
+                handle(PowerEvent::Suspend);
+            }
+
+            #[unsafe(method(didWake:))]
+            fn did_wake(&self, _notification: &AnyObject) {
+                handle(PowerEvent::Resume);
+            }
+
+            #[unsafe(method(screenLocked:))]
+            fn screen_locked(&self, _notification: &AnyObject) {
+                handle(PowerEvent::Lock);
+            }
+
+            #[unsafe(method(screenUnlocked:))]
+            fn screen_unlocked(&self, _notification: &AnyObject) {
+                handle(PowerEvent::Unlock);
+            }
+        }
+    );
+
+    fn observe(center: &AnyObject, observer: &AnyObject, selector: Sel, name: &str) {
+        let name = NSString::from_str(name);
+        unsafe {
+            let _: () = msg_send![
+                center,
+                addObserver: observer,
+                selector: selector,
+                name: &*name,
+                object: None::<&AnyObject>
+            ];
+        }
+    }
+
+    /// Register for notifications; must run on the main thread
+    pub fn init() {
+        let Some(mtm) = MainThreadMarker::new() else {
+            log::warn!("Power notifications unavailable: not on the main thread");
+            return;
+        };
+        let (Some(workspace_class), Some(distributed_class)) = (
+            AnyClass::get(c"NSWorkspace"),
+            AnyClass::get(c"NSDistributedNotificationCenter"),
+        ) else {
+            return;
+        };
+
+        let observer: Retained<PowerObserver> = unsafe { msg_send![PowerObserver::alloc(mtm), init] };
+        let observer_object: &AnyObject = &observer;
+        unsafe {
+            let workspace: Retained<AnyObject> = msg_send![workspace_class, sharedWorkspace];
+            let workspace_center: Retained<AnyObject> = msg_send![&*workspace, notificationCenter];
+            observe(&workspace_center, observer_object, sel!(willSleep:), "NSWorkspaceWillSleepNotification");
+            observe(&workspace_center, observer_object, sel!(didWake:), "NSWorkspaceDidWakeNotification");
+
+            let distributed_center: Retained<AnyObject> = msg_send![distributed_class, defaultCenter];
+            observe(&distributed_center, observer_object, sel!(screenLocked:), "com.apple.screenIsLocked");
+            observe(&distributed_center, observer_object, sel!(screenUnlocked:), "com.apple.screenIsUnlocked");
+        }
+        // Notification centers don't retain observers; it lives as long as the app
+        std::mem::forget(observer);
+    }
+
+    pub fn shutdown() {}
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::RemoteDesktop::{WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, TranslateMessage, MSG,
+        PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND, WM_POWERBROADCAST, WM_WTSSESSION_CHANGE, WNDCLASSW,
+        WTS_SESSION_LOCK, WTS_SESSION_UNLOCK,
+    };
+
+    use super::{handle, PowerEvent};
+
+    unsafe extern "system" fn window_proc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        match (message, wparam as u32) {
+            (WM_POWERBROADCAST, PBT_APMSUSPEND) => handle(PowerEvent::Suspend),
+            (WM_POWERBROADCAST, PBT_APMRESUMEAUTOMATIC) => handle(PowerEvent::Resume),
+            (WM_WTSSESSION_CHANGE, WTS_SESSION_LOCK) => handle(PowerEvent::Lock),
+            (WM_WTSSESSION_CHANGE, WTS_SESSION_UNLOCK) => handle(PowerEvent::Unlock),
+            _ => {}
+        }
+        DefWindowProcW(hwnd, message, wparam, lparam)
+    }
+
+    /// Listen on a hidden window of our own: power broadcasts only reach
+    /// top-level windows, and the app's windows belong to the webview
+    pub fn init() {
+        std::thread::spawn(|| unsafe {
+            let class_name: Vec<u16> = "MupPowerListener".encode_utf16().chain(std::iter::once(0)).collect();
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                lpszClassName: class_name.as_ptr(),
+                ..std::mem::zeroed()
+            };
+            if RegisterClassW(&class) == 0 {
+                log::warn!("Power notifications unavailable: failed to register window class");
+                return;
+            }
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                class_name.as_ptr(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                log::warn!("Power notifications unavailable: failed to create listener window");
+                return;
+            }
+            if WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) == 0 {
+                log::warn!("Session lock notifications unavailable");
+            }
+
+            let mut message: MSG = std::mem::zeroed();
+            while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+                TranslateMessage(&message);
+                DispatchMessageW(&message);
+            }
+        });
+    }
+
+    pub fn shutdown() {}
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::{handle, PowerEvent};
+
+    /// Process ID of the running `gdbus monitor`, or 0
+    static MONITOR_PID: AtomicU32 = AtomicU32::new(0);
+
+    /// Map a `gdbus monitor` line from logind to an event
+    fn parse(line: &str) -> Option<PowerEvent> {
+        if line.contains("PrepareForSleep (true") {
+            Some(PowerEvent::Suspend)
+        } else if line.contains("PrepareForSleep (false") {
+            Some(PowerEvent::Resume)
+        } else if line.contains("login1.Session.Lock ") {
+            Some(PowerEvent::Lock)
+        } else if line.contains("login1.Session.Unlock ") {
+            Some(PowerEvent::Unlock)
+        } else {
+            None
+        }
+    }
+
+    /// Follow logind's signals on the system bus
+    pub fn init() {
+        std::thread::spawn(|| {
+            let child = Command::new("gdbus")
+                .args(["monitor", "--system", "--dest", "org.freedesktop.login1"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    log::warn!("Power notifications unavailable: failed to run gdbus: {}", e);
+                    return;
+                }
+            };
+            MONITOR_PID.store(child.id(), Ordering::SeqCst);
+            let Some(stdout) = child.stdout.take() else {
+                return;
+            };
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(event) = parse(&line) {
+                    handle(event);
+                }
+            }
+
+            // Cleared before reaping so `shutdown` never signals a reused pid
+            let stopping = MONITOR_PID.swap(0, Ordering::SeqCst) == 0;
+            let _ = child.wait();
+            if !stopping {
+                log::warn!("Stopped receiving power notifications");
+            }
+        });
+    }
+
+    /// Stop `gdbus monitor`, which would otherwise outlive the app
+    pub fn shutdown() {
+        let pid = MONITOR_PID.swap(0, Ordering::SeqCst);
+        if pid != 0 {
+            unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows, target_os = "linux")))]
+mod platform {
+    pub fn init() {}
+
+    pub fn shutdown() {}
+}
+
+/// Start following power and session events; must run on the main thread
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    platform::init();
+    spawn_clock_check(app);
+}
+
+/// Stop following power events on exit
+pub fn shutdown() {
+    platform::shutdown();
+}
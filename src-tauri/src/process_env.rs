@@ -0,0 +1,99 @@
+// Process environment inspection
+//
+// GUI launches often get a different environment than a login shell (a
+// short PATH on macOS in particular), which shows up as tools that work in
+// the user's terminal but not in the app. This reports the app's effective
+// environment and what terminals and the sidecar are started with, with
+// secret-looking values redacted by default.
+
+use std::collections::BTreeMap;
+
+use crate::{settings, sidecar, terminal};
+
+/// Shown instead of redacted values
+const REDACTED: &str = "<redacted>";
+
+/// Name fragments that mark a variable as secret
+const SECRET_MARKERS: [&str; 9] = [
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+    "COOKIE",
+    "AUTH",
+];
+
+/// Environment report returned by `get_process_env`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProcessEnv {
+    /// The app's own environment; terminals inherit it unchanged
+    pub app: BTreeMap<String, String>,
+    /// Shell that new terminals run
+    pub terminal_shell: String,
+    /// Variables the sidecar gets on top of `app`
+    pub sidecar_overrides: BTreeMap<String, String>,
+    /// PATH entries in search order
+    pub path: Vec<String>,
+    /// Names whose values were redacted
+    pub redacted: Vec<String>,
+}
+
+fn is_secret(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// Redact secret-looking values in place, recording their names
+fn redact(env: &mut BTreeMap<String, String>, redacted: &mut Vec<String>) {
+    for (name, value) in env.iter_mut().filter(|(name, _)| is_secret(name)) {
+        *value = REDACTED.to_string();
+        redacted.push(name.clone());
+    }
+}
+
+/// Get the app's environment and what child processes are started with
+///
+/// `filtered` (default true) redacts values whose names look like secrets.
+#[tauri::command]
+pub async fn get_process_env(filtered: Option<bool>) -> Result<ProcessEnv, String> {
+    let mut app: BTreeMap<String, String> = std::env::vars_os()
+        .map(|(name, value)| {
+            (
+                name.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+
+    let mut sidecar_overrides: BTreeMap<String, String> = sidecar::config_env(&settings::current().sidecar)?
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    // Regenerated for every spawn and never exposed
+    sidecar_overrides.insert("MUX_SERVER_AUTH_TOKEN".to_string(), REDACTED.to_string());
+
+    let path = std::env::var_os("PATH")
+        .map(|path| {
+            std::env::split_paths(&path)
+                .map(|entry| entry.to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut redacted = Vec::new();
+    if filtered.unwrap_or(true) {
+        redact(&mut app, &mut redacted);
+        redact(&mut sidecar_overrides, &mut redacted);
+    }
+
+    Ok(ProcessEnv {
+        app,
+        terminal_shell: terminal::default_shell(),
+        sidecar_overrides,
+        path,
+        redacted,
+    })
+}
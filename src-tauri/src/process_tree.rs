@@ -0,0 +1,193 @@
+// Process tree termination
+//
+// Killing the sidecar's CommandChild only kills the Node process itself;
+// agent subprocesses it spawned survive. On Windows the sidecar is placed in
+// a job object that kills every member when terminated or closed. On Unix
+// the shell plugin gives no hook to put the child in its own process group
+// before exec (and setpgid fails once the child has exec'd), so descendants
+// are found by walking the process table and killed before the sidecar.
+
+use sysinfo::{Pid, System};
+#[cfg(unix)]
+use sysinfo::Signal;
+
+/// Handle for terminating a process and all of its descendants
+pub struct ProcessTree {
+    pid: u32,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+impl ProcessTree {
+    /// Start tracking the tree rooted at `pid`
+    ///
+    /// Call immediately after spawning so child processes created later are
+    /// captured by the Windows job object.
+    pub fn attach(pid: u32) -> Self {
+        #[cfg(windows)]
+        let job = match job::Job::for_process(pid) {
+            Ok(job) => Some(job),
+            Err(e) => {
+                log::warn!("Failed to create job object for pid {}: {}", pid, e);
+                None
+            }
+        };
+
+        Self {
+            pid,
+            #[cfg(windows)]
+            job,
+        }
+    }
+
+    /// Kill every descendant of the root process
+    ///
+    /// The root is normally left for the caller, which owns its handle.
+    /// Returns true if the root was terminated as well (Windows job objects
+    /// take down every member at once).
+    pub fn kill_descendants(&self) -> bool {
+        #[cfg(windows)]
+        if let Some(ref job) = self.job {
+            job.terminate();
+            return true;
+        }
+
+        let mut system = System::new();
+        system.refresh_processes();
+
+        let descendants = descendants_of(&system, self.pid);
+        if !descendants.is_empty() {
+            log::info!(
+                "Killing {} descendant processes of pid {}",
+                descendants.len(),
+                self.pid
+            );
+        }
+
+        for pid in descendants {
+            if let Some(process) = system.process(pid) {
+                #[cfg(unix)]
+                let killed = process.kill_with(Signal::Kill).unwrap_or(false);
+                #[cfg(not(unix))]
+                let killed = process.kill();
+
+                if !killed {
+                    log::warn!("Failed to kill descendant process {}", pid);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl ProcessTree {
+    /// Kill the root and all of its descendants, for processes without a
+    /// child handle (e.g. a sidecar left over from a crashed run)
+    pub fn kill_all(&self) {
+        if self.kill_descendants() {
+            return;
+        }
+
+        let mut system = System::new();
+        system.refresh_processes();
+        if let Some(process) = system.process(Pid::from_u32(self.pid)) {
+            if !process.kill() {
+                log::warn!("Failed to kill process {}", self.pid);
+            }
+        }
+    }
+}
+
+/// Collect all descendants of `root`, deepest first
+fn descendants_of(system: &System, root: u32) -> Vec<Pid> {
+    let root = Pid::from_u32(root);
+    let mut found = vec![root];
+    let mut index = 0;
+
+    while index < found.len() {
+        let parent = found[index];
+        found.extend(
+            system
+                .processes()
+                .iter()
+                .filter(|(_, process)| process.parent() == Some(parent))
+                .map(|(pid, _)| *pid),
+        );
+        index += 1;
+    }
+
+    // Drop the root and kill leaves before their parents can respawn them
+    found.remove(0);
+    found.reverse();
+    found
+}
+
+#[cfg(windows)]
+mod job {
+    use std::ffi::c_void;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+    /// Job object that kills its processes when terminated or closed
+    pub struct Job(HANDLE);
+
+    // The handle is only used through thread-safe Win32 calls
+    unsafe impl Send for Job {}
+    unsafe impl Sync for Job {}
+
+    impl Job {
+        pub fn for_process(pid: u32) -> Result<Self, String> {
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return Err(std::io::Error::last_os_error().to_string());
+                }
+                let job = Job(handle);
+
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                if SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ) == 0
+                {
+                    return Err(std::io::Error::last_os_error().to_string());
+                }
+
+                let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+                if process.is_null() {
+                    return Err(std::io::Error::last_os_error().to_string());
+                }
+                let assigned = AssignProcessToJobObject(job.0, process);
+                CloseHandle(process);
+                if assigned == 0 {
+                    return Err(std::io::Error::last_os_error().to_string());
+                }
+
+                Ok(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
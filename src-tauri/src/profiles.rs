@@ -0,0 +1,406 @@
+// Named profiles (e.g. work/personal)
+//
+// Each profile has its own data directory holding settings, recent projects,
+// and the backend's MUX_HOME (which includes provider credentials). The
+// active profile is chosen at launch from `--profile <name>`, the
+// MUX_PROFILE environment variable, or the last profile used. Switching
+// profiles relaunches the app so every subsystem picks up the new paths.
+//
+// Webviews get the profile's own storage too (localStorage, IndexedDB,
+// cookies), so web state doesn't carry over between profiles; the default
+// profile keeps the webview's default location.
+//
+// Guest mode (`--guest` or MUX_GUEST=1) replaces the profile with a
+// throwaway directory under the system temp dir, for demos on shared
+// machines and clean-slate bug reproduction. Each guest launch gets its own
+// unpredictable, owner-only directory that is deleted on exit; ones left by
+// guest sessions that crashed are removed on the next guest launch.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, WebviewWindowBuilder, Wry};
+
+use crate::single_instance;
+
+/// Profile that maps onto the pre-profile data locations
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profile name reported while in guest mode
+pub const GUEST_PROFILE: &str = "guest";
+
+/// Prefix of guest state directories inside the system temp dir, followed
+/// by the owning process ID and a random suffix
+const GUEST_DIR_PREFIX: &str = "mup-guest-";
+
+/// Profile index file name inside the app data directory
+const PROFILES_FILE: &str = "profiles.json";
+
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// Webview storage directory inside a profile's data directory
+const WEBVIEW_DIR: &str = "webview";
+
+/// Persisted list of profiles
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct ProfileIndex {
+    profiles: Vec<String>,
+    last_used: String,
+}
+
+impl Default for ProfileIndex {
+    fn default() -> Self {
+        Self {
+            profiles: vec![DEFAULT_PROFILE.to_string()],
+            last_used: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+/// Profiles as reported to the frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProfileList {
+    pub profiles: Vec<String>,
+    pub active: String,
+    pub guest: bool,
+}
+
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+static GUEST_MODE: OnceLock<bool> = OnceLock::new();
+
+/// This launch's guest state directory
+static GUEST_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+fn load_index(app: &AppHandle) -> ProfileIndex {
+    let path = match app_data_dir(app) {
+        Ok(dir) => dir.join(PROFILES_FILE),
+        Err(_) => return ProfileIndex::default(),
+    };
+
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(app: &AppHandle, index: &ProfileIndex) -> Result<(), String> {
+    let dir = app_data_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    let contents = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    std::fs::write(dir.join(PROFILES_FILE), contents)
+        .map_err(|e| format!("Failed to write profiles: {}", e))
+}
+
+/// Validate a profile name for use as a directory name
+fn validate_chars(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name must be 1-{} characters",
+            MAX_PROFILE_NAME_LEN
+        ));
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err("Profile name may only contain letters, digits, '-' and '_'".to_string());
+    }
+
+    Ok(())
+}
+
+/// Validate a profile name; "guest" is reserved so guest mode can't be
+/// confused with a persistent profile of that name
+fn validate_name(name: &str) -> Result<(), String> {
+    validate_chars(name)?;
+    if name.eq_ignore_ascii_case(GUEST_PROFILE) {
+        return Err(format!("\"{}\" is reserved for guest mode", GUEST_PROFILE));
+    }
+    Ok(())
+}
+
+/// Profile named in launch arguments (without the program name)
+pub fn profile_arg(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next().cloned();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// Whether launch arguments (without the program name) ask for guest mode
+pub fn guest_arg(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--guest")
+}
+
+fn launch_args() -> Vec<String> {
+    std::env::args().skip(1).collect()
+}
+
+/// Profile requested on the command line or via MUX_PROFILE
+fn requested_profile() -> Option<String> {
+    profile_arg(&launch_args()).or_else(|| std::env::var("MUX_PROFILE").ok().filter(|v| !v.is_empty()))
+}
+
+/// Guest mode requested on the command line or via MUX_GUEST
+fn guest_requested() -> bool {
+    guest_arg(&launch_args())
+        || std::env::var("MUX_GUEST")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+}
+
+/// Whether this launch keeps all state in a throwaway directory
+pub fn is_guest() -> bool {
+    GUEST_MODE.get().copied().unwrap_or(false)
+}
+
+/// Create this launch's guest directory (owner-only on Unix)
+fn create_guest_dir() -> Result<PathBuf, String> {
+    tempfile::Builder::new()
+        .prefix(&format!("{}{}-", GUEST_DIR_PREFIX, std::process::id()))
+        .tempdir()
+        .map(|dir| dir.keep())
+        .map_err(|e| format!("Failed to create guest directory: {}", e))
+}
+
+/// Remove guest directories whose session is no longer running
+fn remove_stale_guest_dirs() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    let mut system = sysinfo::System::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(pid) = name
+            .strip_prefix(GUEST_DIR_PREFIX)
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if system.refresh_process(sysinfo::Pid::from_u32(pid)) {
+            continue;
+        }
+        // Other users' directories can't be removed, which is fine
+        let _ = std::fs::remove_dir_all(entry.path());
+    }
+}
+
+/// Delete this launch's guest state
+pub fn wipe_guest_data() {
+    let Some(dir) = GUEST_DIR.get() else {
+        return;
+    };
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(dir) {
+            log::warn!("Failed to wipe guest data at {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Select the active profile for this launch
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    if guest_requested() {
+        remove_stale_guest_dirs();
+        let dir = create_guest_dir()?;
+        log::info!("Running in guest mode; state is kept in {}", dir.display());
+        let _ = GUEST_DIR.set(dir);
+        let _ = GUEST_MODE.set(true);
+        let _ = ACTIVE_PROFILE.set(GUEST_PROFILE.to_string());
+        return Ok(());
+    }
+    let _ = GUEST_MODE.set(false);
+
+    let mut index = load_index(app);
+
+    let name = match requested_profile() {
+        Some(name) => {
+            validate_name(&name)?;
+            name
+        }
+        None => index.last_used.clone(),
+    };
+
+    if !index.profiles.contains(&name) {
+        index.profiles.push(name.clone());
+    }
+    index.last_used = name.clone();
+    save_index(app, &index)?;
+
+    log::info!("Active profile: {}", name);
+    let _ = ACTIVE_PROFILE.set(name);
+    Ok(())
+}
+
+/// Name of the active profile
+pub fn active() -> &'static str {
+    ACTIVE_PROFILE
+        .get()
+        .map(|s| s.as_str())
+        .unwrap_or(DEFAULT_PROFILE)
+}
+
+/// Directory holding the active profile's app-side state
+///
+/// The default profile uses the app data directory itself so data from
+/// before profiles existed stays in place.
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    if is_guest() {
+        return GUEST_DIR.get().cloned().ok_or_else(|| "Guest directory is missing".to_string());
+    }
+
+    let base = app_data_dir(app)?;
+    match active() {
+        DEFAULT_PROFILE => Ok(base),
+        name => Ok(base.join("profiles").join(name)),
+    }
+}
+
+/// Give a window's webview the active profile's storage
+///
+/// WKWebView has no data directory; on macOS 14+ a data store identified
+/// by the profile's directory is used instead.
+pub fn with_webview_storage<'a, M: Manager<Wry>>(
+    app: &AppHandle,
+    builder: WebviewWindowBuilder<'a, Wry, M>,
+) -> WebviewWindowBuilder<'a, Wry, M> {
+    if !is_guest() && active() == DEFAULT_PROFILE {
+        return builder;
+    }
+    let dir = match data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Webview storage is shared with the default profile: {}", e);
+            return builder;
+        }
+    };
+
+    #[cfg(target_os = "macos")]
+    let builder = {
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(dir.to_string_lossy().as_bytes());
+        let mut identifier = [0u8; 16];
+        identifier.copy_from_slice(&digest[..16]);
+        builder.data_store_identifier(identifier)
+    };
+    builder.data_directory(dir.join(WEBVIEW_DIR))
+}
+
+/// MUX_HOME override for the backend (None keeps the backend's default)
+pub fn backend_home(app: &AppHandle) -> Result<Option<PathBuf>, String> {
+    match active() {
+        DEFAULT_PROFILE => Ok(None),
+        _ => Ok(Some(data_dir(app)?.join("mux"))),
+    }
+}
+
+/// Relaunch the app with the given profile
+pub fn relaunch_with_profile(app: &AppHandle, name: &str) -> Result<(), String> {
+    validate_name(name)?;
+
+    let mut index = load_index(app);
+    if !index.profiles.iter().any(|p| p == name) {
+        return Err(format!("Profile '{}' does not exist", name));
+    }
+    index.last_used = name.to_string();
+    save_index(app, &index)?;
+
+    relaunch(app, &["--profile".to_string(), name.to_string()])
+}
+
+/// Exit and start a new instance with `args`
+///
+/// The new instance waits for this one to exit before taking the
+/// single-instance lock, so it isn't forwarded back here.
+pub fn relaunch(app: &AppHandle, args: &[String]) -> Result<(), String> {
+    let binary = tauri::process::current_binary(&app.env())
+        .map_err(|e| format!("Failed to locate app binary: {}", e))?;
+    std::process::Command::new(binary)
+        .args(args)
+        .env(single_instance::RELAUNCH_ENV, std::process::id().to_string())
+        .spawn()
+        .map_err(|e| format!("Failed to relaunch app: {}", e))?;
+
+    app.exit(0);
+    Ok(())
+}
+
+/// List profiles and the active one
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> Result<ProfileList, String> {
+    Ok(ProfileList {
+        profiles: load_index(&app).profiles,
+        active: active().to_string(),
+        guest: is_guest(),
+    })
+}
+
+/// Create a new, empty profile
+#[tauri::command]
+pub async fn create_profile(app: AppHandle, name: String) -> Result<(), String> {
+    validate_name(&name)?;
+
+    let mut index = load_index(&app);
+    if index.profiles.contains(&name) {
+        return Err(format!("Profile '{}' already exists", name));
+    }
+    index.profiles.push(name);
+    save_index(&app, &index)
+}
+
+/// Delete a profile and all of its data
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    // Not `validate_name`, so a "guest" profile created before the name was
+    // reserved can still be removed
+    validate_chars(&name)?;
+
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    if name == active() {
+        return Err("The active profile cannot be deleted".to_string());
+    }
+
+    let mut index = load_index(&app);
+    index.profiles.retain(|p| p != &name);
+    if index.last_used == name {
+        index.last_used = DEFAULT_PROFILE.to_string();
+    }
+    save_index(&app, &index)?;
+
+    let dir = app_data_dir(&app)?.join("profiles").join(&name);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove profile data: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Switch to another profile (relaunches the app)
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    relaunch_with_profile(&app, &name)
+}
+
+/// Profile names for the tray menu
+pub fn profile_names(app: &AppHandle) -> Vec<String> {
+    load_index(app).profiles
+}
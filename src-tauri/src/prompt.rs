@@ -0,0 +1,147 @@
+// Shell prompt detection
+//
+// Agents need to know when a shell is idle at its prompt before sending the
+// next command. Terminal output is fed through a per-PTY detector that
+// tracks the current (last) line with escape sequences removed and matches
+// it against a regex set for the shell. Patterns can be overridden per
+// shell in settings; new terminals pick up changes.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::settings;
+
+/// Longest current line kept for matching
+const MAX_LINE_CHARS: usize = 1024;
+
+/// Shell name used when no patterns are known for a shell
+const FALLBACK_SHELL: &str = "default";
+
+/// Prompt detection settings, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PromptSettings {
+    /// Regexes per shell name (e.g. "bash", "zsh"); replace the built-in
+    /// patterns for that shell
+    pub patterns: HashMap<String, Vec<String>>,
+    /// Time without output after a prompt before the shell counts as idle
+    pub quiet_ms: u64,
+}
+
+impl Default for PromptSettings {
+    fn default() -> Self {
+        Self {
+            patterns: HashMap::new(),
+            quiet_ms: 150,
+        }
+    }
+}
+
+/// Built-in prompt patterns per shell
+fn default_patterns(shell: &str) -> &'static [&'static str] {
+    match shell {
+        "bash" | "sh" | "dash" | "ksh" => &[r"[\$#]\s?$"],
+        "zsh" => &[r"[%#\$>❯›]\s?$"],
+        "fish" | "nu" => &[r"[>❯#]\s?$"],
+        "pwsh" | "powershell" => &[r"^PS .*>\s?$"],
+        "cmd" => &[r"^[A-Za-z]:\\.*>$"],
+        _ => &[r"[\$#%>❯]\s?$"],
+    }
+}
+
+/// Matches terminal escape sequences (CSI, OSC, charset and keypad modes)
+pub(crate) fn escape_regex() -> &'static Regex {
+    static ESCAPES: OnceLock<Regex> = OnceLock::new();
+    ESCAPES.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[()][0-9A-Za-z]|\x1b[=>]")
+            .expect("valid escape regex")
+    })
+}
+
+/// Shell name from a shell path ("/bin/zsh" -> "zsh", "cmd.exe" -> "cmd")
+pub fn shell_name(shell: &str) -> String {
+    std::path::Path::new(shell)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| stem.to_lowercase())
+        .unwrap_or_else(|| FALLBACK_SHELL.to_string())
+}
+
+/// Compile the prompt patterns for a shell, preferring settings
+fn compile_patterns(shell: &str) -> Vec<Regex> {
+    let configured = settings::current().prompt.patterns.remove(shell);
+    let sources: Vec<String> = match configured {
+        Some(patterns) => patterns,
+        None => default_patterns(shell).iter().map(|p| p.to_string()).collect(),
+    };
+
+    sources
+        .iter()
+        .filter_map(|source| match Regex::new(source) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Ignoring invalid prompt pattern for {}: {}", shell, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Tracks whether a terminal is sitting at its prompt
+pub struct PromptDetector {
+    patterns: Vec<Regex>,
+    quiet: Duration,
+    line: String,
+    at_prompt: bool,
+    last_output: Instant,
+}
+
+impl PromptDetector {
+    pub fn new(shell: &str) -> Self {
+        Self {
+            patterns: compile_patterns(&shell_name(shell)),
+            quiet: Duration::from_millis(settings::current().prompt.quiet_ms),
+            line: String::new(),
+            at_prompt: false,
+            last_output: Instant::now(),
+        }
+    }
+
+    /// Process a chunk of terminal output
+    pub fn feed(&mut self, output: &[u8]) {
+        if output.is_empty() {
+            return;
+        }
+        self.last_output = Instant::now();
+
+        let text = String::from_utf8_lossy(output);
+        match text.rfind('\n') {
+            Some(index) => self.line = text[index + 1..].to_string(),
+            None => self.line.push_str(&text),
+        }
+        if self.line.len() > MAX_LINE_CHARS {
+            let cut = self.line.len() - MAX_LINE_CHARS;
+            let cut = (cut..self.line.len())
+                .find(|i| self.line.is_char_boundary(*i))
+                .unwrap_or(self.line.len());
+            self.line.drain(..cut);
+        }
+
+        let visible = escape_regex().replace_all(&self.line, "");
+        // Carriage returns redraw the line; only the last segment is shown
+        let visible = visible.rsplit('\r').next().unwrap_or("");
+        self.at_prompt = self.patterns.iter().any(|regex| regex.is_match(visible));
+    }
+
+    /// Input was sent; the shell is busy until it prints a prompt again
+    pub fn input_sent(&mut self) {
+        self.at_prompt = false;
+    }
+
+    /// Whether the shell shows a prompt and has been quiet since
+    pub fn is_idle(&self) -> bool {
+        self.at_prompt && self.last_output.elapsed() >= self.quiet
+    }
+}
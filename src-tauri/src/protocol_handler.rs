@@ -0,0 +1,205 @@
+// mux:// protocol registration
+//
+// Installers register the `mux://` scheme, but portable and unpackaged
+// copies (a zip on Windows, a bare AppImage on Linux) never run one, so
+// deep links don't reach them. These commands report whether the OS sends
+// the scheme to this copy of the app and register it at runtime:
+//   - Windows: HKCU\Software\Classes\mux pointing at this executable
+//   - Linux: a .desktop file in ~/.local/share/applications made the
+//     default x-scheme-handler/mux with xdg-mime
+//   - macOS: the scheme is declared in the bundle's Info.plist and can't be
+//     added at runtime; registering re-registers the bundle with Launch
+//     Services so it is picked up.
+
+use std::path::PathBuf;
+
+#[cfg(windows)]
+use crate::win_registry;
+
+/// URL scheme handled by the app
+const SCHEME: &str = "mux";
+
+/// Desktop entry written for the scheme on Linux
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "mup-url-handler.desktop";
+
+/// The executable the OS should launch with a URL
+#[cfg(any(windows, target_os = "linux"))]
+fn launch_target() -> Result<PathBuf, String> {
+    // An AppImage runs from a temporary mount; launch the image itself
+    #[cfg(target_os = "linux")]
+    if let Some(image) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(image));
+    }
+    std::env::current_exe().map_err(|e| format!("Failed to resolve executable: {}", e))
+}
+
+/// Open command for the scheme, from the merged HKCU/HKLM view
+#[cfg(windows)]
+fn registered_command() -> Option<String> {
+    use windows_sys::Win32::System::Registry::HKEY_CLASSES_ROOT;
+
+    win_registry::read_string(HKEY_CLASSES_ROOT, &format!("{}\\shell\\open\\command", SCHEME), None)
+}
+
+#[cfg(windows)]
+fn is_registered() -> Result<bool, String> {
+    let target = launch_target()?.to_string_lossy().to_lowercase();
+    Ok(registered_command().is_some_and(|command| command.to_lowercase().contains(&target)))
+}
+
+#[cfg(windows)]
+fn register() -> Result<(), String> {
+    use windows_sys::Win32::System::Registry::HKEY_CURRENT_USER;
+
+    let set = |subkey: &str, name: Option<&str>, value: &str| -> Result<(), String> {
+        win_registry::write_string(HKEY_CURRENT_USER, &format!("Software\\Classes\\{}", subkey), name, value)
+    };
+
+    let target = launch_target()?;
+    set(SCHEME, None, &format!("URL:{} Protocol", SCHEME))?;
+    set(SCHEME, Some("URL Protocol"), "")?;
+    set(
+        &format!("{}\\shell\\open\\command", SCHEME),
+        None,
+        &format!("\"{}\" \"%1\"", target.display()),
+    )
+}
+
+/// Directories holding desktop entries, user first
+#[cfg(target_os = "linux")]
+fn applications_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS").unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+    data_home
+        .into_iter()
+        .chain(data_dirs.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from))
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn xdg_mime(args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("xdg-mime")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run xdg-mime: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "xdg-mime failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn is_registered() -> Result<bool, String> {
+    let handler = xdg_mime(&["query", "default", &format!("x-scheme-handler/{}", SCHEME)])?;
+    if handler.is_empty() {
+        return Ok(false);
+    }
+    let Some(entry) = applications_dirs()
+        .into_iter()
+        .map(|dir| dir.join(&handler))
+        .find_map(|path| std::fs::read_to_string(path).ok())
+    else {
+        return Ok(false);
+    };
+
+    let target = launch_target()?.to_string_lossy().to_string();
+    Ok(entry
+        .lines()
+        .filter_map(|line| line.strip_prefix("Exec="))
+        .any(|exec| exec.contains(&target)))
+}
+
+#[cfg(target_os = "linux")]
+fn register() -> Result<(), String> {
+    let dir = applications_dirs()
+        .into_iter()
+        .next()
+        .ok_or("Failed to resolve the applications directory")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let target = launch_target()?;
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=mup\n\
+         Exec=\"{}\" %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        target.display(),
+        SCHEME
+    );
+    std::fs::write(dir.join(DESKTOP_FILE), entry).map_err(|e| format!("Failed to write desktop entry: {}", e))?;
+
+    // Refreshing the cache is best effort; xdg-mime sets the default
+    let _ = std::process::Command::new("update-desktop-database").arg(&dir).status();
+    xdg_mime(&["default", DESKTOP_FILE, &format!("x-scheme-handler/{}", SCHEME)]).map(|_| ())
+}
+
+/// The running app bundle
+#[cfg(target_os = "macos")]
+fn bundle() -> Result<PathBuf, String> {
+    crate::update_rollback::install_target().ok_or_else(|| "Not running from an app bundle".to_string())
+}
+
+/// Whether the bundle's Info.plist declares the scheme
+#[cfg(target_os = "macos")]
+fn is_registered() -> Result<bool, String> {
+    let plist = std::fs::read_to_string(bundle()?.join("Contents/Info.plist"))
+        .map_err(|e| format!("Failed to read Info.plist: {}", e))?;
+    let declared = plist
+        .split("<key>CFBundleURLSchemes</key>")
+        .skip(1)
+        .filter_map(|rest| rest.split("</array>").next())
+        .any(|schemes| schemes.contains(&format!("<string>{}</string>", SCHEME)));
+    Ok(declared)
+}
+
+#[cfg(target_os = "macos")]
+fn register() -> Result<(), String> {
+    const LSREGISTER: &str = "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister";
+
+    if !is_registered()? {
+        return Err(format!("This build doesn't declare the {}:// scheme in Info.plist", SCHEME));
+    }
+    let status = std::process::Command::new(LSREGISTER)
+        .arg("-f")
+        .arg(bundle()?)
+        .status()
+        .map_err(|e| format!("Failed to run lsregister: {}", e))?;
+    if !status.success() {
+        return Err(format!("lsregister failed: {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn is_registered() -> Result<bool, String> {
+    Ok(false)
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn register() -> Result<(), String> {
+    Err("Protocol registration is not supported on this platform".to_string())
+}
+
+/// Register this copy of the app as the mux:// handler
+#[tauri::command]
+pub async fn register_protocol_handler() -> Result<(), String> {
+    register()?;
+    log::info!("Registered as the {}:// handler", SCHEME);
+    Ok(())
+}
+
+/// Whether mux:// links open this copy of the app
+#[tauri::command]
+pub async fn is_protocol_registered() -> Result<bool, String> {
+    is_registered()
+}
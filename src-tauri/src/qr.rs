@@ -0,0 +1,63 @@
+// QR code generation
+//
+// Renders mux:// pairing and deep links as PNG QR codes so a mobile
+// companion can connect by scanning the desktop window.
+
+use qrcode::{Color, QrCode};
+
+/// Default size of one QR module in pixels
+const DEFAULT_MODULE_PX: u32 = 8;
+
+/// Largest accepted module size, keeping images reasonably small
+const MAX_MODULE_PX: u32 = 32;
+
+/// Blank modules around the code, as required by the QR spec
+const QUIET_ZONE: u32 = 4;
+
+/// Render `data` as a grayscale PNG
+pub fn render_png(data: &str, module_px: u32) -> Result<Vec<u8>, String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    
+    let modules = code.width() as u32;
+    let colors = code.to_colors();
+    let side = (modules + 2 * QUIET_ZONE) * module_px;
+    
+    let mut pixels = vec![0xFFu8; (side * side) as usize];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x0 = (index as u32 % modules + QUIET_ZONE) * module_px;
+        let y0 = (index as u32 / modules + QUIET_ZONE) * module_px;
+        for y in y0..y0 + module_px {
+            let row = (y * side) as usize;
+            pixels[row + x0 as usize..row + (x0 + module_px) as usize].fill(0);
+        }
+    }
+    
+    let mut png_bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_bytes, side, side);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Failed to write PNG header: {}", e))?;
+        writer
+            .write_image_data(&pixels)
+            .map_err(|e| format!("Failed to write PNG data: {}", e))?;
+    }
+    
+    Ok(png_bytes)
+}
+
+/// Generate a QR code PNG, returned as raw bytes
+#[tauri::command]
+pub async fn generate_qr(data: String, module_size: Option<u32>) -> Result<tauri::ipc::Response, String> {
+    if data.is_empty() {
+        return Err("Nothing to encode".to_string());
+    }
+    
+    let module_px = module_size.unwrap_or(DEFAULT_MODULE_PX).clamp(1, MAX_MODULE_PX);
+    render_png(&data, module_px).map(tauri::ipc::Response::new)
+}
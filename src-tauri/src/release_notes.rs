@@ -0,0 +1,147 @@
+// Release notes
+//
+// The "What's new" dialog shows every release between two versions rather
+// than just the latest, so users who skipped versions see the whole delta.
+// Notes come from the project's GitHub releases, paged until the older
+// version is passed. When an administrator has pinned the update server,
+// or GitHub can't be reached, the update manifest's notes for the latest
+// release are used instead. Pre-releases are only listed on the beta and
+// nightly channels.
+
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::updater::{self, UpdateChannel};
+use crate::version::Version;
+use crate::{settings, update_policy, update_proxy};
+
+/// GitHub releases of the app, newest first
+const CHANGELOG_URL: &str = "https://api.github.com/repos/Coder/mup/releases";
+
+/// Releases per page, and pages fetched at most
+const PAGE_SIZE: usize = 100;
+const MAX_PAGES: usize = 5;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Notes for one release
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ReleaseNote {
+    pub version: String,
+    pub name: Option<String>,
+    /// Markdown body
+    pub body: Option<String>,
+    pub date: Option<String>,
+    pub prerelease: bool,
+}
+
+/// A release as the GitHub API reports it
+#[derive(Debug, serde::Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    name: Option<String>,
+    body: Option<String>,
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// Fetch releases newest first, stopping once `oldest` has been passed
+async fn fetch_releases(oldest: &Version) -> Result<Vec<GithubRelease>, String> {
+    let client = update_proxy::client(CHANGELOG_URL, REQUEST_TIMEOUT)?;
+    let mut releases = Vec::new();
+
+    for page in 1..=MAX_PAGES {
+        let batch: Vec<GithubRelease> = client
+            .get(CHANGELOG_URL)
+            .query(&[("per_page", PAGE_SIZE), ("page", page)])
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to fetch release notes: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid release notes: {}", e))?;
+
+        let count = batch.len();
+        let passed = batch
+            .iter()
+            .filter_map(|release| Version::parse(&release.tag_name))
+            .any(|key| key <= *oldest);
+        releases.extend(batch);
+        if passed || count < PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(releases)
+}
+
+/// Notes for the latest release from the update manifest
+async fn manifest_notes(app: &AppHandle) -> Result<Vec<ReleaseNote>, String> {
+    let update = updater::latest_update(app).await?;
+    Ok(update
+        .into_iter()
+        .map(|update| ReleaseNote {
+            version: update.version,
+            name: None,
+            body: update.body,
+            date: update.date.map(|date| date.to_string()),
+            prerelease: false,
+        })
+        .collect())
+}
+
+/// Get the notes of every release after `from_version` (default: the
+/// installed version) up to and including `to_version` (default: the
+/// latest), newest first
+#[tauri::command]
+pub async fn get_release_notes(
+    app: AppHandle,
+    from_version: Option<String>,
+    to_version: Option<String>,
+) -> Result<Vec<ReleaseNote>, String> {
+    let from = from_version.unwrap_or_else(|| app.package_info().version.to_string());
+    let from_key = Version::parse(&from).ok_or_else(|| format!("Invalid version: {}", from))?;
+    let to_key = match &to_version {
+        Some(to) => Some(Version::parse(to).ok_or_else(|| format!("Invalid version: {}", to))?),
+        None => None,
+    };
+    let include_prereleases = settings::current().updates.channel != UpdateChannel::Stable;
+
+    // A pinned server may carry internal builds that never appear on GitHub
+    if update_policy::current().update_url.is_some() {
+        return manifest_notes(&app).await;
+    }
+
+    let releases = match fetch_releases(&from_key).await {
+        Ok(releases) => releases,
+        Err(e) => {
+            log::warn!("{}; falling back to the update manifest", e);
+            return manifest_notes(&app).await;
+        }
+    };
+
+    let mut notes: Vec<(Version, ReleaseNote)> = releases
+        .into_iter()
+        .filter(|release| !release.draft && (include_prereleases || !release.prerelease))
+        .filter_map(|release| {
+            let key = Version::parse(&release.tag_name)?;
+            let in_range = key > from_key && !matches!(&to_key, Some(to) if key > *to);
+            in_range.then(|| {
+                let note = ReleaseNote {
+                    version: release.tag_name.trim_start_matches('v').to_string(),
+                    name: release.name,
+                    body: release.body,
+                    date: release.published_at,
+                    prerelease: release.prerelease,
+                };
+                (key, note)
+            })
+        })
+        .collect();
+    notes.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(notes.into_iter().map(|(_, note)| note).collect())
+}
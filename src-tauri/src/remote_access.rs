@@ -0,0 +1,331 @@
+// Remote access tunnel
+//
+// Optionally exposes the local backend to another machine through an SSH
+// reverse tunnel or a user-provided relay command (e.g. a tunnelling
+// client). The backend still requires its bearer token, so the tunnel only
+// makes it reachable; it does not bypass authentication. Status changes are
+// emitted as `remote-access-status`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::{settings, sidecar};
+
+/// Time after spawning without an exit before the tunnel counts as up
+const CONNECT_GRACE: Duration = Duration::from_secs(3);
+
+/// Placeholder in relay arguments replaced by the backend port
+const PORT_PLACEHOLDER: &str = "{port}";
+
+/// How the backend is exposed
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TunnelConfig {
+    /// `ssh -R remote_port:127.0.0.1:<backend port> [user@]host`
+    Ssh {
+        host: String,
+        user: Option<String>,
+        ssh_port: Option<u16>,
+        remote_port: u16,
+        identity_file: Option<std::path::PathBuf>,
+    },
+    /// Any relay client; `{port}` in the arguments is the backend port
+    Relay { command: String, args: Vec<String> },
+}
+
+/// Remote access settings, persisted in app settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RemoteAccessSettings {
+    pub tunnel: Option<TunnelConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelState {
+    Stopped,
+    Starting,
+    Running,
+    Failed,
+}
+
+/// Payload of `remote-access-status` and result of `get_remote_access_status`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RemoteAccessStatus {
+    pub state: TunnelState,
+    /// Where the backend can be reached remotely, if known
+    pub endpoint: Option<String>,
+    /// Token remote clients must send as `Authorization: Bearer`
+    pub auth_token: Option<String>,
+    pub error: Option<String>,
+}
+
+struct Tunnel {
+    config: TunnelConfig,
+    child: CommandChild,
+    local_port: u16,
+}
+
+static TUNNEL: Mutex<Option<Tunnel>> = Mutex::new(None);
+
+static STATUS: Mutex<RemoteAccessStatus> = Mutex::new(RemoteAccessStatus {
+    state: TunnelState::Stopped,
+    endpoint: None,
+    auth_token: None,
+    error: None,
+});
+
+fn current_status() -> RemoteAccessStatus {
+    STATUS
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or(RemoteAccessStatus {
+            state: TunnelState::Stopped,
+            endpoint: None,
+            auth_token: None,
+            error: None,
+        })
+}
+
+fn set_status(app: &AppHandle, state: TunnelState, endpoint: Option<String>, error: Option<String>) {
+    let status = RemoteAccessStatus {
+        state,
+        endpoint,
+        auth_token: sidecar::get_auth_token().filter(|_| state == TunnelState::Running),
+        error,
+    };
+    if let Ok(mut guard) = STATUS.lock() {
+        *guard = status.clone();
+    }
+    if let Err(e) = app.emit("remote-access-status", status) {
+        log::error!("Failed to emit remote-access-status event: {}", e);
+    }
+}
+
+/// Program, arguments, and expected endpoint for a tunnel
+fn command_line(config: &TunnelConfig, local_port: u16) -> (String, Vec<String>, Option<String>) {
+    match config {
+        TunnelConfig::Ssh {
+            host,
+            user,
+            ssh_port,
+            remote_port,
+            identity_file,
+        } => {
+            let mut args = vec![
+                "-N".to_string(),
+                "-o".to_string(),
+                "ExitOnForwardFailure=yes".to_string(),
+                "-o".to_string(),
+                "ServerAliveInterval=30".to_string(),
+                // Never wait on a password or host key prompt nobody can see
+                "-o".to_string(),
+                "BatchMode=yes".to_string(),
+                "-R".to_string(),
+                format!("{}:127.0.0.1:{}", remote_port, local_port),
+            ];
+            if let Some(port) = ssh_port {
+                args.push("-p".to_string());
+                args.push(port.to_string());
+            }
+            if let Some(identity) = identity_file {
+                args.push("-i".to_string());
+                args.push(identity.to_string_lossy().to_string());
+            }
+            // A host or user starting with '-' must not be read as an option
+            args.push("--".to_string());
+            args.push(match user {
+                Some(user) => format!("{}@{}", user, host),
+                None => host.clone(),
+            });
+            ("ssh".to_string(), args, Some(format!("{}:{}", host, remote_port)))
+        }
+        TunnelConfig::Relay { command, args } => {
+            let port = local_port.to_string();
+            let args = args
+                .iter()
+                .map(|arg| arg.replace(PORT_PLACEHOLDER, &port))
+                .collect();
+            (command.clone(), args, None)
+        }
+    }
+}
+
+/// First http(s) URL in a line of relay output
+fn find_url(line: &str) -> Option<String> {
+    line.split_whitespace()
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|word| word.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '/').to_string())
+}
+
+fn start(app: &AppHandle, config: TunnelConfig) -> Result<(), String> {
+    let local_port = sidecar::get_sidecar_port();
+    if local_port == 0 {
+        return Err("Remote access needs the backend on a TCP port".to_string());
+    }
+
+    stop(app);
+
+    let (program, args, endpoint) = command_line(&config, local_port);
+    log::info!("Starting remote access tunnel via {}", program);
+    set_status(app, TunnelState::Starting, endpoint.clone(), None);
+
+    let (mut rx, child) = match app.shell().command(&program).args(&args).spawn() {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            let error = format!("Failed to start {}: {}", program, e);
+            set_status(app, TunnelState::Failed, None, Some(error.clone()));
+            return Err(error);
+        }
+    };
+    let pid = child.pid();
+
+    if let Ok(mut guard) = TUNNEL.lock() {
+        *guard = Some(Tunnel {
+            config,
+            child,
+            local_port,
+        });
+    }
+
+    let is_current = move || {
+        TUNNEL
+            .lock()
+            .map(|guard| guard.as_ref().map(|t| t.child.pid()) == Some(pid))
+            .unwrap_or(false)
+    };
+
+    // Without an explicit signal, a tunnel that stays up is considered connected
+    let app_handle = app.clone();
+    let grace_endpoint = endpoint.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(CONNECT_GRACE).await;
+        if is_current() && current_status().state == TunnelState::Starting {
+            set_status(&app_handle, TunnelState::Running, grace_endpoint, None);
+        }
+    });
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last_error = None;
+
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) | CommandEvent::Stderr(line) => {
+                    let line = String::from_utf8_lossy(&line).trim().to_string();
+                    log::debug!("[tunnel] {}", line);
+
+                    if let Some(url) = find_url(&line).filter(|_| endpoint.is_none()) {
+                        if is_current() {
+                            set_status(&app_handle, TunnelState::Running, Some(url), None);
+                        }
+                    }
+                    if !line.is_empty() {
+                        last_error = Some(line);
+                    }
+                }
+                CommandEvent::Terminated(payload) => {
+                    log::info!("Remote access tunnel exited with code: {:?}", payload.code);
+                    if is_current() {
+                        if let Ok(mut guard) = TUNNEL.lock() {
+                            *guard = None;
+                        }
+                        let error = last_error
+                            .take()
+                            .unwrap_or_else(|| format!("Tunnel exited with code {:?}", payload.code));
+                        set_status(&app_handle, TunnelState::Failed, None, Some(error));
+                    }
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn stop(app: &AppHandle) {
+    let tunnel = TUNNEL.lock().ok().and_then(|mut guard| guard.take());
+    if let Some(tunnel) = tunnel {
+        log::info!("Stopping remote access tunnel");
+        if let Err(e) = tunnel.child.kill() {
+            log::warn!("Failed to stop tunnel: {}", e);
+        }
+        set_status(app, TunnelState::Stopped, None, None);
+    }
+}
+
+/// Keep a running tunnel pointed at the backend across restarts, and stop
+/// it if the `remote_access` flag is turned off
+pub fn init(app: &AppHandle) {
+    let app_handle = app.clone();
+    app.listen_any("feature-flags-changed", move |_| {
+        if !crate::feature_flags::is_on("remote_access") {
+            stop(&app_handle);
+        }
+    });
+
+    let app_handle = app.clone();
+    app.listen_any("backend-ready", move |_| {
+        if !crate::feature_flags::is_on("remote_access") {
+            return;
+        }
+        let restart = TUNNEL
+            .lock()
+            .ok()
+            .and_then(|guard| {
+                guard
+                    .as_ref()
+                    .filter(|t| t.local_port != sidecar::get_sidecar_port())
+                    .map(|t| t.config.clone())
+            });
+        if let Some(config) = restart {
+            if let Err(e) = start(&app_handle, config) {
+                log::error!("Failed to restart remote access tunnel: {}", e);
+            }
+        }
+    });
+}
+
+/// Start exposing the backend remotely
+///
+/// A given config is saved for next time; otherwise the saved one is used.
+#[tauri::command]
+pub async fn start_remote_access(app: AppHandle, config: Option<TunnelConfig>) -> Result<RemoteAccessStatus, String> {
+    if !crate::feature_flags::is_on("remote_access") {
+        return Err("Remote access is disabled by feature flag".to_string());
+    }
+
+    let config = match config {
+        Some(config) => {
+            let mut current = settings::current();
+            current.remote_access.tunnel = Some(config.clone());
+            settings::save(&app, current)?;
+            config
+        }
+        None => settings::current()
+            .remote_access
+            .tunnel
+            .ok_or_else(|| "No remote access tunnel configured".to_string())?,
+    };
+
+    start(&app, config)?;
+    Ok(current_status())
+}
+
+/// Stop the remote access tunnel
+#[tauri::command]
+pub async fn stop_remote_access(app: AppHandle) -> Result<(), String> {
+    stop(&app);
+    Ok(())
+}
+
+/// Get the remote access tunnel status
+#[tauri::command]
+pub async fn get_remote_access_status() -> Result<RemoteAccessStatus, String> {
+    Ok(current_status())
+}
@@ -0,0 +1,185 @@
+// Sidecar resource monitoring
+//
+// Samples the backend process's CPU and memory, emits warnings when usage
+// crosses the configured thresholds, and optionally restarts the backend
+// once memory exceeds a hard cap (long agent sessions can leak in Node).
+
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, System};
+use tauri::{AppHandle, Emitter};
+
+use crate::{settings, sidecar};
+
+/// How often the sidecar is sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Consecutive samples above the CPU threshold before warning
+const CPU_WARN_SAMPLES: u32 = 3;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Resource thresholds, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Sustained CPU usage (percent of one core) that triggers a warning
+    pub cpu_warn_percent: f32,
+    /// Resident memory that triggers a warning
+    pub memory_warn_mb: u64,
+    /// Resident memory that triggers a restart when auto_restart is on
+    pub memory_hard_cap_mb: Option<u64>,
+    pub auto_restart: bool,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            cpu_warn_percent: 90.0,
+            memory_warn_mb: 2048,
+            memory_hard_cap_mb: None,
+            auto_restart: false,
+        }
+    }
+}
+
+/// A single resource sample of the sidecar process
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ResourceUsage {
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// Payload of the `backend-resource-warning` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ResourceWarningPayload {
+    pub kind: &'static str,
+    pub usage: ResourceUsage,
+    pub threshold: f64,
+}
+
+// CPU usage is computed between refreshes, so the System must persist
+static SYSTEM: OnceLock<Mutex<System>> = OnceLock::new();
+
+static LAST_USAGE: RwLock<Option<ResourceUsage>> = RwLock::new(None);
+
+/// Sample CPU and memory for a process
+fn sample(pid: u32) -> Option<ResourceUsage> {
+    let system = SYSTEM.get_or_init(|| Mutex::new(System::new()));
+    let mut system = system.lock().ok()?;
+
+    let sys_pid = Pid::from_u32(pid);
+    if !system.refresh_process_specifics(sys_pid, ProcessRefreshKind::new().with_cpu().with_memory()) {
+        return None;
+    }
+
+    let process = system.process(sys_pid)?;
+    Some(ResourceUsage {
+        pid,
+        cpu_percent: process.cpu_usage(),
+        memory_bytes: process.memory(),
+    })
+}
+
+fn emit_warning(app: &AppHandle, kind: &'static str, usage: &ResourceUsage, threshold: f64) {
+    log::warn!("Backend {} usage above threshold: {:?}", kind, usage);
+
+    let payload = ResourceWarningPayload {
+        kind,
+        usage: usage.clone(),
+        threshold,
+    };
+    if let Err(e) = app.emit("backend-resource-warning", payload) {
+        log::error!("Failed to emit backend-resource-warning event: {}", e);
+    }
+}
+
+/// Start the background resource monitor
+pub fn start_monitor(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut cpu_high_samples = 0u32;
+        let mut memory_warned = false;
+
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+
+            let usage = match sidecar::get_sidecar_pid().await.and_then(sample) {
+                Some(usage) => usage,
+                None => {
+                    cpu_high_samples = 0;
+                    memory_warned = false;
+                    continue;
+                }
+            };
+
+            if let Ok(mut guard) = LAST_USAGE.write() {
+                *guard = Some(usage.clone());
+            }
+
+            let limits = settings::current().resources;
+
+            // Warn once per excursion rather than on every sample
+            if usage.cpu_percent >= limits.cpu_warn_percent {
+                cpu_high_samples += 1;
+                if cpu_high_samples == CPU_WARN_SAMPLES {
+                    emit_warning(&app, "cpu", &usage, f64::from(limits.cpu_warn_percent));
+                }
+            } else {
+                cpu_high_samples = 0;
+            }
+
+            let memory_mb = usage.memory_bytes / BYTES_PER_MB;
+            if memory_mb >= limits.memory_warn_mb {
+                if !memory_warned {
+                    memory_warned = true;
+                    emit_warning(&app, "memory", &usage, limits.memory_warn_mb as f64);
+                }
+            } else {
+                memory_warned = false;
+            }
+
+            if let Some(cap) = limits.memory_hard_cap_mb.filter(|_| limits.auto_restart) {
+                if memory_mb >= cap {
+                    log::error!(
+                        "Backend memory {} MB exceeds hard cap {} MB; restarting",
+                        memory_mb,
+                        cap
+                    );
+
+                    if let Err(e) = app.emit("backend-resource-restart", usage.clone()) {
+                        log::error!("Failed to emit backend-resource-restart event: {}", e);
+                    }
+                    if let Err(e) = sidecar::restart_backend(app.clone(), None).await {
+                        log::error!("Failed to restart backend: {}", e);
+                    }
+
+                    cpu_high_samples = 0;
+                    memory_warned = false;
+                }
+            }
+        }
+    });
+}
+
+/// Get the latest CPU and memory usage of the backend process
+#[tauri::command]
+pub async fn get_backend_resource_usage() -> Result<ResourceUsage, String> {
+    let pid = sidecar::get_sidecar_pid()
+        .await
+        .ok_or_else(|| "Backend not started".to_string())?;
+
+    // A fresh sample has no CPU baseline; prefer the monitor's last sample
+    if let Some(usage) = LAST_USAGE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .filter(|usage| usage.pid == pid)
+    {
+        return Ok(usage);
+    }
+
+    sample(pid).ok_or_else(|| "Failed to read backend process usage".to_string())
+}
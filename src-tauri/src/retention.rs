@@ -0,0 +1,218 @@
+// Data retention policies
+//
+// Periodically deletes logs, terminal recordings, audit entries, and crash
+// reports older than the configured number of days, and lets users purge a
+// category immediately. Audit entries include the deep link log, whose
+// database is pruned by row.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+use crate::{deeplink_audit, profiles, settings};
+
+/// How often the cleanup job runs
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Categories of data covered by retention settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionCategory {
+    Logs,
+    TerminalRecordings,
+    AuditEntries,
+    CrashReports,
+}
+
+impl RetentionCategory {
+    pub const ALL: [RetentionCategory; 4] = [
+        RetentionCategory::Logs,
+        RetentionCategory::TerminalRecordings,
+        RetentionCategory::AuditEntries,
+        RetentionCategory::CrashReports,
+    ];
+}
+
+/// Days to keep each category (0 keeps data forever)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    pub logs_days: u32,
+    pub terminal_recordings_days: u32,
+    pub audit_entries_days: u32,
+    pub crash_reports_days: u32,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            logs_days: 14,
+            terminal_recordings_days: 30,
+            audit_entries_days: 90,
+            crash_reports_days: 30,
+        }
+    }
+}
+
+impl RetentionSettings {
+    fn days_for(&self, category: RetentionCategory) -> u32 {
+        match category {
+            RetentionCategory::Logs => self.logs_days,
+            RetentionCategory::TerminalRecordings => self.terminal_recordings_days,
+            RetentionCategory::AuditEntries => self.audit_entries_days,
+            RetentionCategory::CrashReports => self.crash_reports_days,
+        }
+    }
+}
+
+/// Result of cleaning up a single category
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PurgeResult {
+    pub category: RetentionCategory,
+    pub files_removed: u64,
+    pub bytes_freed: u64,
+    /// Database entries deleted (the deep link log)
+    pub entries_removed: u64,
+}
+
+/// Directory holding the data for a category
+pub fn category_dir(app: &AppHandle, category: RetentionCategory) -> Result<PathBuf, String> {
+    let data_dir = || profiles::data_dir(app);
+
+    match category {
+        RetentionCategory::Logs => app
+            .path()
+            .app_log_dir()
+            .map_err(|e| format!("Failed to resolve log directory: {}", e)),
+        RetentionCategory::TerminalRecordings => Ok(data_dir()?.join("recordings")),
+        RetentionCategory::AuditEntries => Ok(data_dir()?.join("audit")),
+        RetentionCategory::CrashReports => Ok(data_dir()?.join("crash-reports")),
+    }
+}
+
+/// Remove files under `dir` last modified before `cutoff` (all files if None)
+fn remove_files(dir: &Path, cutoff: Option<SystemTime>, result: &mut PurgeResult) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+
+        if deeplink_audit::is_database_file(&path) {
+            continue;
+        }
+        if metadata.is_dir() {
+            remove_files(&path, cutoff, result);
+            // Only succeeds once the directory is empty
+            let _ = std::fs::remove_dir(&path);
+            continue;
+        }
+
+        let expired = match (cutoff, metadata.modified()) {
+            (None, _) => true,
+            (Some(cutoff), Ok(modified)) => modified < cutoff,
+            (Some(_), Err(_)) => false,
+        };
+
+        if expired {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    result.files_removed += 1;
+                    result.bytes_freed += metadata.len();
+                }
+                Err(e) => log::warn!("Failed to remove {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+/// Delete a category's data from before `cutoff` (all of it if None)
+fn purge(app: &AppHandle, category: RetentionCategory, cutoff: Option<SystemTime>) -> Result<PurgeResult, String> {
+    let dir = category_dir(app, category)?;
+    let mut result = PurgeResult {
+        category,
+        files_removed: 0,
+        bytes_freed: 0,
+        entries_removed: 0,
+    };
+    remove_files(&dir, cutoff, &mut result);
+    if category == RetentionCategory::AuditEntries {
+        result.entries_removed = deeplink_audit::prune(cutoff)?;
+    }
+    Ok(result)
+}
+
+/// Apply the configured retention to every category
+pub fn run_cleanup(app: &AppHandle) -> Vec<PurgeResult> {
+    let retention = settings::current().retention;
+    let now = SystemTime::now();
+
+    RetentionCategory::ALL
+        .iter()
+        .filter_map(|&category| {
+            let days = retention.days_for(category);
+            if days == 0 {
+                return None;
+            }
+
+            let cutoff = now.checked_sub(Duration::from_secs(u64::from(days) * SECS_PER_DAY))?;
+            match purge(app, category, Some(cutoff)) {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    log::warn!("Retention cleanup of {:?} failed: {}", category, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Start the periodic cleanup job
+pub fn start_cleanup_job(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let cleanup_app = app.clone();
+            let results = tauri::async_runtime::spawn_blocking(move || run_cleanup(&cleanup_app))
+                .await
+                .unwrap_or_default();
+
+            for result in results.iter().filter(|r| r.files_removed > 0 || r.entries_removed > 0) {
+                log::info!(
+                    "Retention cleanup removed {} files ({} bytes) and {} entries from {:?}",
+                    result.files_removed,
+                    result.bytes_freed,
+                    result.entries_removed,
+                    result.category
+                );
+            }
+
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+        }
+    });
+}
+
+/// Immediately delete all data in the given categories
+#[tauri::command]
+pub async fn purge_now(
+    app: AppHandle,
+    categories: Vec<RetentionCategory>,
+) -> Result<Vec<PurgeResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        categories
+            .into_iter()
+            .map(|category| purge(&app, category, None))
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Purge task failed: {}", e))?
+}
@@ -0,0 +1,59 @@
+// HTML sanitization
+//
+// Agent-generated HTML previews are cleaned natively with ammonia before
+// the webview renders them, rather than relying only on frontend
+// sanitizers.
+
+use ammonia::Builder;
+
+/// How much markup survives sanitization
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizePolicy {
+    /// Basic text formatting and links only
+    Strict,
+    /// ammonia's default allowlist
+    #[default]
+    Standard,
+    /// Standard plus `class` attributes (syntax highlighting) and images
+    /// served over mux-asset://
+    Preview,
+}
+
+/// Tags kept by the strict policy
+const STRICT_TAGS: [&str; 16] = [
+    "a", "b", "blockquote", "br", "code", "em", "i", "li", "ol", "p", "pre", "s", "strong", "sub",
+    "sup", "ul",
+];
+
+fn builder(policy: SanitizePolicy) -> Builder<'static> {
+    match policy {
+        SanitizePolicy::Strict => {
+            let mut builder = Builder::empty();
+            builder
+                .add_tags(STRICT_TAGS)
+                .add_tag_attributes("a", ["href"])
+                .add_url_schemes(["http", "https", "mailto"]);
+            builder
+        }
+        SanitizePolicy::Standard => Builder::default(),
+        SanitizePolicy::Preview => {
+            let mut builder = Builder::default();
+            builder
+                .add_generic_attributes(["class"])
+                .add_url_schemes([crate::asset_protocol::SCHEME]);
+            builder
+        }
+    }
+}
+
+/// Sanitize HTML with the given policy
+pub fn sanitize(input: &str, policy: SanitizePolicy) -> String {
+    builder(policy).clean(input).to_string()
+}
+
+/// Sanitize untrusted HTML before rendering it in the webview
+#[tauri::command]
+pub async fn sanitize_html(input: String, policy: Option<SanitizePolicy>) -> Result<String, String> {
+    Ok(sanitize(&input, policy.unwrap_or_default()))
+}
@@ -0,0 +1,337 @@
+// Session bundle export and import
+//
+// Packages selected terminal recordings, scrollback captured by the UI, and
+// audit entries into a portable .tar.gz so a teammate can reproduce an
+// agent session. Bundles are also saved as .mux files, which the app is
+// registered to open. Imports are unpacked into their own directory rather
+// than merged into local recordings and audit logs.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::profiles;
+use crate::retention::{category_dir, RetentionCategory};
+use crate::storage::{self, UnpackLimits};
+
+/// Manifest file name at the root of a bundle
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Current bundle format
+const FORMAT_VERSION: u32 = 1;
+
+/// Largest manifest accepted
+const MAX_MANIFEST_BYTES: u64 = 1024 * 1024;
+
+/// How much an imported bundle may unpack to
+const IMPORT_LIMITS: UnpackLimits = UnpackLimits {
+    max_entries: 10_000,
+    max_bytes: 2 * 1024 * 1024 * 1024,
+};
+
+/// Top-level directories a bundle may contain
+const RECORDINGS_DIR: &str = "recordings";
+const SCROLLBACK_DIR: &str = "scrollback";
+const AUDIT_DIR: &str = "audit";
+
+/// Scrollback of one terminal, serialized by the frontend
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ScrollbackEntry {
+    pub name: String,
+    pub content: String,
+}
+
+/// What to include in an exported bundle
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ExportRequest {
+    /// Recording file names from the recordings directory
+    pub recordings: Vec<String>,
+    pub scrollback: Vec<ScrollbackEntry>,
+    pub include_audit: bool,
+}
+
+/// Description of a bundle's contents
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at_secs: u64,
+    pub recordings: Vec<String>,
+    pub scrollback: Vec<String>,
+    pub audit: Vec<String>,
+}
+
+/// Result of importing a bundle
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImportResult {
+    pub manifest: BundleManifest,
+    /// Directory the bundle was unpacked into
+    pub directory: PathBuf,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Reject names that could escape their directory
+fn check_name(name: &str) -> Result<(), String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(format!("Invalid file name: {}", name)),
+    }
+}
+
+/// File names directly inside `dir`
+fn list_files(dir: &Path) -> Vec<String> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn append_bytes<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(now_secs());
+    header.set_cksum();
+    builder.append_data(&mut header, path, bytes)
+}
+
+fn write_bundle(
+    destination: &Path,
+    recordings_dir: &Path,
+    audit_dir: &Path,
+    request: &ExportRequest,
+) -> Result<BundleManifest, String> {
+    for name in &request.recordings {
+        check_name(name)?;
+        if !recordings_dir.join(name).is_file() {
+            return Err(format!("Recording not found: {}", name));
+        }
+    }
+    for entry in &request.scrollback {
+        check_name(&entry.name)?;
+    }
+
+    let audit = if request.include_audit {
+        list_files(audit_dir)
+    } else {
+        Vec::new()
+    };
+
+    // The compressed bundle is no larger than its inputs, so reserve that
+    let file_size = |path: PathBuf| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let required: u64 = request
+        .recordings
+        .iter()
+        .map(|name| file_size(recordings_dir.join(name)))
+        .chain(audit.iter().map(|name| file_size(audit_dir.join(name))))
+        .chain(request.scrollback.iter().map(|entry| entry.content.len() as u64))
+        .sum();
+    let parent = destination
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    storage::ensure_free_space(parent, required)?;
+
+    let manifest = BundleManifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at_secs: now_secs(),
+        recordings: request.recordings.clone(),
+        scrollback: request.scrollback.iter().map(|e| e.name.clone()).collect(),
+        audit,
+    };
+
+    let file = File::create(destination)
+        .map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let result = (|| -> std::io::Result<()> {
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        append_bytes(&mut builder, MANIFEST_FILE, &manifest_json)?;
+
+        for name in &manifest.recordings {
+            builder.append_path_with_name(
+                recordings_dir.join(name),
+                format!("{}/{}", RECORDINGS_DIR, name),
+            )?;
+        }
+        for entry in &request.scrollback {
+            append_bytes(
+                &mut builder,
+                &format!("{}/{}", SCROLLBACK_DIR, entry.name),
+                entry.content.as_bytes(),
+            )?;
+        }
+        for name in &manifest.audit {
+            builder.append_path_with_name(audit_dir.join(name), format!("{}/{}", AUDIT_DIR, name))?;
+        }
+
+        builder.into_inner()?.finish()?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(destination);
+        return Err(format!("Failed to write bundle: {}", e));
+    }
+
+    Ok(manifest)
+}
+
+fn check_format(manifest: &BundleManifest) -> Result<(), String> {
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format {} is newer than supported ({}); update the app",
+            manifest.format_version, FORMAT_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Read and check a bundle's manifest without unpacking it
+///
+/// Bundles are written with the manifest first, so only the first entry is
+/// read; nothing else is decompressed.
+pub(crate) fn read_manifest(source: &Path) -> Result<BundleManifest, String> {
+    let file =
+        File::open(source).map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut entries = archive
+        .entries()
+        .map_err(|e| format!("Invalid bundle: {}", e))?;
+    let entry = entries
+        .next()
+        .ok_or("Bundle is empty")?
+        .map_err(|e| format!("Invalid bundle: {}", e))?;
+    let is_manifest = entry
+        .path()
+        .map(|path| path == Path::new(MANIFEST_FILE))
+        .unwrap_or(false);
+    if !is_manifest || !entry.header().entry_type().is_file() {
+        return Err("Bundle has no manifest".to_string());
+    }
+    if entry.size() > MAX_MANIFEST_BYTES {
+        return Err(format!("Bundle manifest is larger than {} bytes", MAX_MANIFEST_BYTES));
+    }
+
+    let manifest: BundleManifest = serde_json::from_reader(entry.take(MAX_MANIFEST_BYTES))
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    check_format(&manifest)?;
+    for name in manifest.recordings.iter().chain(&manifest.scrollback).chain(&manifest.audit) {
+        check_name(name)?;
+    }
+    Ok(manifest)
+}
+
+fn read_bundle(source: &Path, target: &Path) -> Result<BundleManifest, String> {
+    let file =
+        File::open(source).map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    std::fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+
+    storage::unpack_bounded(&mut archive, target, IMPORT_LIMITS, |path, entry_type| {
+        if !entry_type.is_file() {
+            return false;
+        }
+        let allowed = path == Path::new(MANIFEST_FILE)
+            || [RECORDINGS_DIR, SCROLLBACK_DIR, AUDIT_DIR]
+                .iter()
+                .any(|dir| path.parent() == Some(Path::new(dir)));
+        if !allowed {
+            log::warn!("Skipping unexpected bundle entry: {}", path.display());
+        }
+        allowed
+    })
+    .map_err(|e| format!("Invalid bundle: {}", e))?;
+
+    let manifest_path = target.join(MANIFEST_FILE);
+    let manifest_size = std::fs::metadata(&manifest_path)
+        .map_err(|_| "Bundle has no manifest".to_string())?
+        .len();
+    if manifest_size > MAX_MANIFEST_BYTES {
+        return Err(format!("Bundle manifest is larger than {} bytes", MAX_MANIFEST_BYTES));
+    }
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .map_err(|_| "Bundle has no manifest".to_string())?;
+    let manifest: BundleManifest =
+        serde_json::from_str(&manifest).map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    check_format(&manifest)?;
+
+    Ok(manifest)
+}
+
+/// Package recordings, scrollback, and audit entries into a .tar.gz bundle
+#[tauri::command]
+pub async fn export_session_bundle(
+    app: AppHandle,
+    request: ExportRequest,
+    destination: PathBuf,
+) -> Result<BundleManifest, String> {
+    let recordings_dir = category_dir(&app, RetentionCategory::TerminalRecordings)?;
+    let audit_dir = category_dir(&app, RetentionCategory::AuditEntries)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        write_bundle(&destination, &recordings_dir, &audit_dir, &request)
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
+}
+
+/// Unpack a session bundle into its own directory under `imports`
+#[tauri::command]
+pub async fn import_session_bundle(app: AppHandle, source: PathBuf) -> Result<ImportResult, String> {
+    let stem = source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| {
+            name.trim_end_matches(".tar.gz")
+                .trim_end_matches(".tgz")
+                .trim_end_matches(".mux")
+        })
+        .unwrap_or("bundle")
+        .to_string();
+    let target = profiles::data_dir(&app)?
+        .join("imports")
+        .join(format!("{}-{}", now_secs(), stem));
+
+    let directory = target.clone();
+    let manifest = tauri::async_runtime::spawn_blocking(move || {
+        let result = read_bundle(&source, &target);
+        if result.is_err() {
+            let _ = std::fs::remove_dir_all(&target);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))??;
+
+    Ok(ImportResult {
+        manifest,
+        directory,
+    })
+}
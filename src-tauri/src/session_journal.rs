@@ -0,0 +1,353 @@
+// Session state journal
+//
+// Session-state changes (terminals opened, closed or moved to another
+// directory, the active project switching) are appended to a write-ahead
+// journal as they happen, one JSON line each, and synced to disk by a
+// writer thread so callers holding other locks never wait on the disk.
+// On clean shutdown the journal is folded into a snapshot file
+// and truncated. After a crash the journal still holds entries, so startup
+// replays them over the last snapshot; the restored session reflects the
+// last moments of activity rather than the last clean exit. A torn final
+// line from the crash is ignored. The recovered state is also kept in a
+// file of its own until the UI has read it, so a second crash before then
+// doesn't lose it.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use tauri::AppHandle;
+
+use crate::profiles;
+
+/// Append-only journal of changes since the last snapshot
+const JOURNAL_FILE: &str = "session.journal";
+
+/// Session state as of the last compaction
+const SNAPSHOT_FILE: &str = "session.json";
+
+/// A crashed session's state, kept until the UI has read it
+const RECOVERED_FILE: &str = "session.recovered.json";
+
+/// A change to session state
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    TerminalOpened { id: u32, cwd: Option<String> },
+    TerminalClosed { id: u32 },
+    TerminalCwdChanged { id: u32, cwd: String },
+    ProjectSwitched { project: Option<String> },
+}
+
+/// A terminal open in the session
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionTerminal {
+    pub id: u32,
+    pub cwd: Option<String>,
+}
+
+/// Session state rebuilt from the snapshot and journal
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub project: Option<String>,
+    pub terminals: Vec<SessionTerminal>,
+}
+
+/// The previous session, for the UI to restore
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecoveredSession {
+    pub state: SessionState,
+    /// Whether the previous session ended without a clean shutdown
+    pub crashed: bool,
+}
+
+/// Work for the writer thread, which owns the journal file so appends and
+/// fsyncs never run under a caller's lock
+enum WriteOp {
+    Append(String),
+    Compact(SessionState, mpsc::Sender<Result<(), String>>),
+}
+
+struct Journal {
+    /// Open terminals by id, kept sorted for stable snapshots
+    terminals: BTreeMap<u32, Option<String>>,
+    project: Option<String>,
+    writer: mpsc::Sender<WriteOp>,
+}
+
+impl Journal {
+    fn apply(&mut self, event: &SessionEvent) {
+        apply(&mut self.terminals, &mut self.project, event);
+    }
+
+    fn state(&self) -> SessionState {
+        SessionState {
+            project: self.project.clone(),
+            terminals: self
+                .terminals
+                .iter()
+                .map(|(id, cwd)| SessionTerminal {
+                    id: *id,
+                    cwd: cwd.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Queue a compaction of the current state; the receiver gets its result
+    fn compact(&self) -> Result<mpsc::Receiver<Result<(), String>>, String> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.writer
+            .send(WriteOp::Compact(self.state(), reply_tx))
+            .map_err(|_| "Session journal writer stopped".to_string())?;
+        Ok(reply_rx)
+    }
+}
+
+static JOURNAL: Mutex<Option<Journal>> = Mutex::new(None);
+
+static RECOVERED: Mutex<Option<RecoveredSession>> = Mutex::new(None);
+
+fn apply(terminals: &mut BTreeMap<u32, Option<String>>, project: &mut Option<String>, event: &SessionEvent) {
+    match event {
+        SessionEvent::TerminalOpened { id, cwd } => {
+            terminals.insert(*id, cwd.clone());
+        }
+        SessionEvent::TerminalClosed { id } => {
+            terminals.remove(id);
+        }
+        SessionEvent::TerminalCwdChanged { id, cwd } => {
+            if let Some(entry) = terminals.get_mut(id) {
+                *entry = Some(cwd.clone());
+            }
+        }
+        SessionEvent::ProjectSwitched { project: next } => *project = next.clone(),
+    }
+}
+
+/// Write a state file atomically
+fn write_state(path: &Path, state: &SessionState) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize session state: {}", e))?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, contents)
+        .and_then(|_| std::fs::rename(&temp, path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Write the snapshot atomically, then empty the journal; returns the
+/// emptied journal for further appends
+fn compact(dir: &Path, state: &SessionState) -> Result<File, String> {
+    write_state(&dir.join(SNAPSHOT_FILE), state)?;
+    let file = File::create(dir.join(JOURNAL_FILE))
+        .map_err(|e| format!("Failed to truncate session journal: {}", e))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to truncate session journal: {}", e))?;
+    Ok(file)
+}
+
+/// Load the snapshot and replay the journal over it
+fn replay(dir: &Path) -> (SessionState, bool) {
+    let snapshot: SessionState = std::fs::read_to_string(dir.join(SNAPSHOT_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+
+    let mut terminals: BTreeMap<u32, Option<String>> = snapshot
+        .terminals
+        .into_iter()
+        .map(|terminal| (terminal.id, terminal.cwd))
+        .collect();
+    let mut project = snapshot.project;
+
+    let mut replayed = 0;
+    if let Ok(file) = File::open(dir.join(JOURNAL_FILE)) {
+        for line in BufReader::new(file).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            // A crash mid-append leaves a torn last line
+            let Ok(event) = serde_json::from_str::<SessionEvent>(&line) else {
+                continue;
+            };
+            apply(&mut terminals, &mut project, &event);
+            replayed += 1;
+        }
+    }
+
+    let state = SessionState {
+        project,
+        terminals: terminals
+            .into_iter()
+            .map(|(id, cwd)| SessionTerminal { id, cwd })
+            .collect(),
+    };
+    (state, replayed > 0)
+}
+
+/// Append and compact on a thread of its own, in the order queued
+fn run_writer(dir: PathBuf, mut file: File, ops: mpsc::Receiver<WriteOp>) {
+    for op in ops {
+        match op {
+            WriteOp::Append(line) => {
+                let result = writeln!(file, "{}", line).and_then(|_| file.sync_data());
+                if let Err(e) = result {
+                    log::warn!("Failed to append to session journal: {}", e);
+                }
+            }
+            WriteOp::Compact(state, reply) => {
+                let result = compact(&dir, &state).map(|emptied| file = emptied);
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+/// The previous session: the one replayed now if it left anything behind,
+/// else one recovered earlier that the UI never read
+fn previous_session(dir: &Path) -> RecoveredSession {
+    let (state, crashed) = replay(dir);
+    if crashed || !state.terminals.is_empty() {
+        return RecoveredSession { state, crashed };
+    }
+    std::fs::read_to_string(dir.join(RECOVERED_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .map(|state| RecoveredSession { state, crashed: true })
+        .unwrap_or(RecoveredSession { state, crashed: false })
+}
+
+/// Recover the previous session and start a fresh journal
+pub fn init(app: &AppHandle) {
+    let dir = match profiles::data_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Session journal disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::warn!("Session journal disabled: {}", e);
+        return;
+    }
+
+    let recovered = previous_session(&dir);
+    if recovered.crashed {
+        log::warn!("Previous session ended uncleanly; recovered state from the journal");
+        // Kept until the UI reads it, so crashing again before then
+        // doesn't lose it to this session's snapshot
+        if let Err(e) = write_state(&dir.join(RECOVERED_FILE), &recovered.state) {
+            log::warn!("{}", e);
+        }
+    }
+    // Terminals from the previous process are gone; this session's state
+    // starts from the project alone
+    let project = recovered.state.project.clone();
+    if let Ok(mut slot) = RECOVERED.lock() {
+        *slot = Some(recovered);
+    }
+    let fresh = SessionState {
+        project: project.clone(),
+        terminals: Vec::new(),
+    };
+    let file = match compact(&dir, &fresh) {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("Session journal disabled: {}", e);
+            return;
+        }
+    };
+
+    let (writer, ops) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("session-journal".to_string())
+        .spawn(move || run_writer(dir, file, ops));
+    if let Err(e) = spawned {
+        log::warn!("Session journal disabled: failed to start writer: {}", e);
+        return;
+    }
+    if let Ok(mut journal) = JOURNAL.lock() {
+        *journal = Some(Journal {
+            terminals: BTreeMap::new(),
+            project,
+            writer,
+        });
+    }
+}
+
+/// Queue a change for the journal
+///
+/// Callers may hold other locks (the PTY map), so the write and fsync
+/// happen on the writer thread; entries are still written in order.
+pub fn record(event: SessionEvent) {
+    let Ok(mut guard) = JOURNAL.lock() else {
+        return;
+    };
+    let Some(journal) = guard.as_mut() else {
+        return;
+    };
+
+    journal.apply(&event);
+    let line = match serde_json::to_string(&event) {
+        Ok(line) => line,
+        Err(e) => {
+            log::warn!("Failed to serialize session event: {}", e);
+            return;
+        }
+    };
+    if journal.writer.send(WriteOp::Append(line)).is_err() {
+        log::warn!("Session journal writer stopped; dropping event");
+    }
+}
+
+/// Fold the journal into the snapshot, keeping it open for new entries
+pub fn flush() -> Result<(), String> {
+    let reply = {
+        let guard = JOURNAL.lock().map_err(|e| format!("Lock error: {}", e))?;
+        match guard.as_ref() {
+            Some(journal) => journal.compact()?,
+            None => return Ok(()),
+        }
+    };
+    reply
+        .recv()
+        .map_err(|_| "Session journal writer stopped".to_string())?
+}
+
+/// Fold the journal into the snapshot on clean shutdown
+pub fn shutdown() {
+    let Some(journal) = JOURNAL.lock().ok().and_then(|mut guard| guard.take()) else {
+        return;
+    };
+    let result = journal
+        .compact()
+        .and_then(|reply| reply.recv().map_err(|_| "Session journal writer stopped".to_string())?);
+    if let Err(e) = result {
+        log::warn!("Failed to compact session journal: {}", e);
+    }
+    // Dropping the journal closes the channel, which ends the writer
+}
+
+/// Get the session recovered at startup
+///
+/// Once the UI has it, the copy kept on disk for it is no longer needed.
+#[tauri::command]
+pub async fn get_recovered_session(app: AppHandle) -> Result<Option<RecoveredSession>, String> {
+    let recovered = RECOVERED
+        .lock()
+        .map(|recovered| recovered.clone())
+        .map_err(|e| format!("Lock error: {}", e))?;
+    if let Ok(dir) = profiles::data_dir(&app) {
+        let _ = std::fs::remove_file(dir.join(RECOVERED_FILE));
+    }
+    Ok(recovered)
+}
+
+/// Record the project the UI switched to
+#[tauri::command]
+pub async fn set_session_project(project: Option<String>) -> Result<(), String> {
+    record(SessionEvent::ProjectSwitched { project });
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// Persisted application settings
+//
+// Settings are stored as JSON in the active profile's data directory and
+// cached in memory. Each subsystem owns its own section struct; this module only handles
+// loading, saving, and exposing the combined document to the frontend.
+
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use tauri::{AppHandle, Emitter};
+
+use crate::deeplink::DeepLinkSettings;
+use crate::discovery::DiscoverySettings;
+use crate::feature_flags::FeatureFlagSettings;
+use crate::native_control::NativeControlSettings;
+use crate::orpc_bridge::BridgeSettings;
+use crate::overlay::OverlaySettings;
+use crate::profiles;
+use crate::prompt::PromptSettings;
+use crate::remote_access::RemoteAccessSettings;
+use crate::resources::ResourceLimits;
+use crate::retention::RetentionSettings;
+use crate::sidecar::SidecarConfig;
+use crate::sidecar_registry::SidecarSpec;
+use crate::terminal::TerminalSettings;
+use crate::tray::TraySettings;
+use crate::updater::UpdateSettings;
+
+/// Settings file name inside the profile data directory
+const SETTINGS_FILE: &str = "settings.json";
+
+/// All persisted settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub retention: RetentionSettings,
+    pub sidecar: SidecarConfig,
+    pub resources: ResourceLimits,
+    /// App log level (error, warn, info, debug, trace)
+    pub log_level: Option<String>,
+    /// Auxiliary sidecars started alongside the backend
+    pub sidecars: Vec<SidecarSpec>,
+    pub discovery: DiscoverySettings,
+    pub bridge: BridgeSettings,
+    pub remote_access: RemoteAccessSettings,
+    pub prompt: PromptSettings,
+    pub terminal: TerminalSettings,
+    pub native_control: NativeControlSettings,
+    pub feature_flags: FeatureFlagSettings,
+    pub overlay: OverlaySettings,
+    pub updates: UpdateSettings,
+    pub deep_links: DeepLinkSettings,
+    pub tray: TraySettings,
+}
+
+static SETTINGS: OnceLock<RwLock<AppSettings>> = OnceLock::new();
+
+fn settings_cell() -> &'static RwLock<AppSettings> {
+    SETTINGS.get_or_init(|| RwLock::new(AppSettings::default()))
+}
+
+/// Path of the settings file
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(profiles::data_dir(app)?.join(SETTINGS_FILE))
+}
+
+/// Load settings from disk into the in-memory cache
+///
+/// A missing file yields defaults; a corrupt file is logged and replaced
+/// by defaults on the next save.
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    let path = settings_path(app)?;
+
+    let settings = match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid settings file {}: {}", path.display(), e);
+            AppSettings::default()
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => AppSettings::default(),
+        Err(e) => return Err(format!("Failed to read settings: {}", e)),
+    };
+
+    if let Ok(mut guard) = settings_cell().write() {
+        *guard = settings;
+    }
+
+    Ok(())
+}
+
+/// Get a copy of the current settings
+pub fn current() -> AppSettings {
+    settings_cell()
+        .read()
+        .map(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// Replace the current settings and persist them
+pub fn save(app: &AppHandle, settings: AppSettings) -> Result<(), String> {
+    let path = settings_path(app)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write settings: {}", e))?;
+
+    if let Ok(mut guard) = settings_cell().write() {
+        *guard = settings.clone();
+    }
+
+    if let Err(e) = app.emit("settings-changed", settings) {
+        log::error!("Failed to emit settings-changed event: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Get the current settings
+#[tauri::command]
+pub async fn get_settings() -> Result<AppSettings, String> {
+    Ok(current())
+}
+
+/// Replace and persist the settings
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, settings: AppSettings) -> Result<(), String> {
+    save(&app, settings)
+}
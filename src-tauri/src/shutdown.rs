@@ -0,0 +1,85 @@
+// Graceful shutdown
+//
+// Quitting (from the tray's Quit item, closing the main window or Cmd+Q)
+// goes through one coordinator so the app winds down in order: the frontend is told the app
+// is closing, window and session state are saved, terminals are closed,
+// auxiliary sidecars are stopped, and the backend is stopped once the
+// calls it is serving have finished. Only then does the app exit. A
+// watchdog exits anyway if any step hangs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::{app_windows, orpc_bridge, overlay, session_journal, sidecar_registry, terminal, window_state};
+
+/// Longest the backend may take to finish in-flight calls
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest the whole shutdown may take before the app exits regardless
+const WATCHDOG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Extra time given to a forced exit before the process is killed
+const FORCED_EXIT_GRACE: Duration = Duration::from_secs(5);
+
+/// Set once a shutdown has started
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Run each shutdown step, logging failures so later steps still run
+async fn wind_down(app: &AppHandle) {
+    if let Err(e) = app.emit("app-closing", ()) {
+        log::error!("Failed to emit app-closing event: {}", e);
+    }
+    // The overlay would otherwise keep the app running
+    overlay::close(app);
+
+    if let Err(e) = window_state::save(app) {
+        log::warn!("Shutdown: {}", e);
+    }
+    app_windows::save_all(app);
+    if let Err(e) = session_journal::flush() {
+        log::warn!("Shutdown: could not flush session state: {}", e);
+    }
+
+    terminal::shutdown_all().await;
+
+    let registry = sidecar_registry::registry();
+    for status in registry.status() {
+        if let Err(e) = registry.stop(&status.name) {
+            log::warn!("Shutdown: could not stop sidecar {}: {}", status.name, e);
+        }
+    }
+
+    if let Err(e) = orpc_bridge::stop_backend(DRAIN_TIMEOUT, true).await {
+        log::warn!("Shutdown: could not stop the backend: {}", e);
+    }
+}
+
+/// Exit if the shutdown hangs
+fn spawn_watchdog(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(WATCHDOG_TIMEOUT);
+        log::warn!("Shutdown did not finish in {}s; exiting", WATCHDOG_TIMEOUT.as_secs());
+        app.exit(0);
+
+        std::thread::sleep(FORCED_EXIT_GRACE);
+        std::process::exit(1);
+    });
+}
+
+/// Shut the app down in order and exit; later calls while a shutdown is
+/// running are ignored
+pub fn quit(app: &AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("Shutting down");
+    spawn_watchdog(app);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        wind_down(&app).await;
+        app.exit(0);
+    });
+}
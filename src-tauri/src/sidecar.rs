@@ -2,23 +2,183 @@
 //
 // This module manages the Node.js backend sidecar process lifecycle:
 // - Spawning the sidecar on app startup
-// - Tracking the dynamically assigned port
+// - Tracking the dynamically assigned port (or socket / named pipe path)
 // - Graceful shutdown on app quit
 // - Event emission for backend readiness
 
-use std::sync::atomic::{AtomicU16, Ordering};
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::ShellExt;
 use tokio::sync::Mutex;
 
+use crate::process_tree::ProcessTree;
+use crate::{backend_version, commands, crash_loop, integrity, orphan, orpc_bridge, profiles, settings, sidecar_update};
+
 /// Global sidecar state
 static SIDECAR_PORT: AtomicU16 = AtomicU16::new(0);
 
+/// Port announced by the most recent sidecar; kept across restarts to
+/// detect when a new sidecar comes up on a different port
+static LAST_ANNOUNCED_PORT: AtomicU16 = AtomicU16::new(0);
+
+/// Unix socket or named pipe the sidecar listens on when using the socket
+/// transport
+static SIDECAR_SOCKET: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Bearer token the sidecar requires on its API, regenerated per spawn
+static SIDECAR_AUTH_TOKEN: RwLock<Option<String>> = RwLock::new(None);
+
 /// Sidecar process handle
 static SIDECAR_PROCESS: std::sync::OnceLock<Arc<Mutex<Option<tauri_plugin_shell::process::CommandChild>>>> = 
     std::sync::OnceLock::new();
 
+/// Pid of a sidecar adopted from a previous run (0 if none); it has no
+/// CommandChild handle
+static ADOPTED_PID: AtomicU32 = AtomicU32::new(0);
+
+/// Descendant tracking for the running sidecar
+static SIDECAR_TREE: std::sync::Mutex<Option<ProcessTree>> = std::sync::Mutex::new(None);
+
+/// Sidecars whose exit is expected and not reported: standbys that never
+/// went live and backends replaced by a blue/green restart
+static QUIET_PIDS: std::sync::Mutex<Vec<u32>> = std::sync::Mutex::new(Vec::new());
+
+/// How often an adopted sidecar is checked for having exited
+const ADOPTED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Counter giving each spawned sidecar its own socket path
+#[cfg(any(unix, windows))]
+static SOCKET_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Longest a replaced backend may take to finish in-flight calls
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time to wait for the port announcement before giving up
+const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 30;
+
+/// Number of stderr lines kept for startup diagnostics
+const STDERR_TAIL_LINES: usize = 50;
+
+/// Payload of the `backend-startup-timeout` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StartupTimeoutPayload {
+    pub timeout_secs: u64,
+    pub stderr_tail: Vec<String>,
+}
+
+/// Base name of the bundled backend binary
+const SIDECAR_NAME: &str = "mup-server";
+
+/// Payload of the `backend-binary-unavailable` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BinaryUnavailablePayload {
+    pub host_triple: String,
+    pub searched: Vec<PathBuf>,
+}
+
+/// Backend log levels accepted by MUX_LOG_LEVEL
+const LOG_LEVELS: [&str; 4] = ["error", "warn", "info", "debug"];
+
+/// How the bridge connects to the sidecar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendTransport {
+    /// Loopback TCP port
+    #[default]
+    Tcp,
+    /// Unix domain socket, or a named pipe on Windows; avoids exposing a
+    /// localhost port
+    Socket,
+}
+
+/// Launch configuration for the sidecar, persisted in app settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SidecarConfig {
+    /// Backend data directory (MUX_HOME); defaults to the profile's location
+    pub data_dir: Option<PathBuf>,
+    /// Working directory of the sidecar process
+    pub working_dir: Option<PathBuf>,
+    /// Backend log level (error, warn, info, debug)
+    pub log_level: Option<String>,
+    /// Backend experiments to force on, by ID (e.g. "system-1"), passed as
+    /// a comma-separated list in MUX_FEATURE_FLAGS
+    pub feature_flags: Vec<String>,
+    /// Port to listen on instead of a random free port
+    pub preferred_port: Option<u16>,
+    /// Listen on all interfaces (TCP only) so companion devices on the
+    /// local network can reach the backend; requests still need the token
+    pub listen_on_lan: bool,
+    /// Transport between the bridge and the sidecar
+    pub transport: BackendTransport,
+}
+
+/// How `restart_backend` replaces the running sidecar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    /// Stop the sidecar, then start a new one
+    #[default]
+    Stop,
+    /// Start a new sidecar, switch to it once ready, then drain and stop
+    /// the old one
+    BlueGreen,
+}
+
+/// Where a sidecar announced it is listening
+enum Listening {
+    Port(u16),
+    Socket(PathBuf),
+}
+
+/// A sidecar started alongside the running one, not yet serving the app
+struct Standby {
+    child: tauri_plugin_shell::process::CommandChild,
+    auth_token: String,
+    ready: tokio::sync::oneshot::Receiver<Listening>,
+    /// Fires if the standby is waiting for the running sidecar to release
+    /// the data directory
+    waiting: tokio::sync::oneshot::Receiver<()>,
+}
+
+/// A sidecar taken out of service by a restart, to be stopped once its
+/// calls have drained
+struct Replaced {
+    child: Option<tauri_plugin_shell::process::CommandChild>,
+    tree: Option<ProcessTree>,
+    pid: u32,
+    socket: Option<PathBuf>,
+}
+
+impl Replaced {
+    fn stop(self) {
+        log::info!("Stopping replaced sidecar (pid {})", self.pid);
+        match self.child {
+            Some(child) => {
+                let root_killed = self.tree.map(|tree| tree.kill_descendants()).unwrap_or(false);
+                if let Err(e) = child.kill() {
+                    if !root_killed {
+                        log::warn!("Failed to kill replaced sidecar: {}", e);
+                    }
+                }
+            }
+            None => {
+                if let Some(tree) = self.tree {
+                    tree.kill_all();
+                }
+            }
+        }
+        // Each spawn listens on its own socket
+        if let Some(socket) = self.socket {
+            remove_socket(&socket);
+        }
+    }
+}
+
 /// Get the sidecar port (0 if not started yet)
 pub fn get_sidecar_port() -> u16 {
     SIDECAR_PORT.load(Ordering::SeqCst)
@@ -27,6 +187,149 @@ pub fn get_sidecar_port() -> u16 {
 /// Set the sidecar port
 pub fn set_sidecar_port(port: u16) {
     SIDECAR_PORT.store(port, Ordering::SeqCst);
+    orpc_bridge::set_ready(is_backend_ready());
+}
+
+/// Payload of the `backend-port-changed` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PortChangedPayload {
+    pub previous_port: u16,
+    pub port: u16,
+}
+
+/// Record a port announcement, returning the previous port if it changed
+fn announce_port(port: u16) -> Option<u16> {
+    let previous = LAST_ANNOUNCED_PORT.swap(port, Ordering::SeqCst);
+    set_sidecar_port(port);
+    Some(previous).filter(|previous| *previous != 0 && *previous != port)
+}
+
+/// Get the sidecar's socket or named pipe (None when using TCP or not started)
+pub fn get_sidecar_socket() -> Option<PathBuf> {
+    SIDECAR_SOCKET.read().ok().and_then(|guard| guard.clone())
+}
+
+fn set_sidecar_socket(socket: Option<PathBuf>) {
+    if let Ok(mut guard) = SIDECAR_SOCKET.write() {
+        *guard = socket;
+    }
+    orpc_bridge::set_ready(is_backend_ready());
+}
+
+/// Forget the socket of a sidecar that has stopped, removing its file
+fn clear_sidecar_socket() {
+    if let Some(socket) = get_sidecar_socket() {
+        remove_socket(&socket);
+    }
+    set_sidecar_socket(None);
+}
+
+/// Get the sidecar's process ID (None if not running)
+pub async fn get_sidecar_pid() -> Option<u32> {
+    let process_handle = SIDECAR_PROCESS.get_or_init(|| Arc::new(Mutex::new(None)));
+    let guard = process_handle.lock().await;
+    guard
+        .as_ref()
+        .map(|child| child.pid())
+        .or_else(|| Some(ADOPTED_PID.load(Ordering::SeqCst)).filter(|pid| *pid != 0))
+}
+
+/// Take over a sidecar left running by a previous app instance
+pub fn adopt(record: &orphan::PidRecord) {
+    ADOPTED_PID.store(record.pid, Ordering::SeqCst);
+    if let Ok(mut tree) = SIDECAR_TREE.lock() {
+        *tree = Some(ProcessTree::attach(record.pid));
+    }
+    if let Ok(mut guard) = SIDECAR_AUTH_TOKEN.write() {
+        *guard = record.auth_token.clone();
+    }
+    set_sidecar_socket(record.socket.clone());
+    LAST_ANNOUNCED_PORT.store(record.port, Ordering::SeqCst);
+    set_sidecar_port(record.port);
+}
+
+/// Emit readiness for an adopted sidecar and start watching for its exit
+pub fn announce_adopted(app: &AppHandle) {
+    if let Err(e) = app.emit("backend-ready", get_sidecar_port()) {
+        log::error!("Failed to emit backend-ready event: {}", e);
+    }
+    tauri::async_runtime::spawn(backend_version::handshake(app.clone()));
+    
+    let pid = ADOPTED_PID.load(Ordering::SeqCst);
+    if pid != 0 {
+        tauri::async_runtime::spawn(watch_adopted(app.clone(), pid));
+    }
+}
+
+/// Report an adopted sidecar's exit the way the output handler does for
+/// spawned ones
+///
+/// Adopted sidecars aren't our children, so there is no exit status to wait
+/// on; the pid is polled instead. Watching stops once the sidecar is
+/// terminated or replaced through us, which clears `ADOPTED_PID`.
+async fn watch_adopted(app: AppHandle, pid: u32) {
+    loop {
+        tokio::time::sleep(ADOPTED_POLL_INTERVAL).await;
+        
+        if ADOPTED_PID.load(Ordering::SeqCst) != pid {
+            return;
+        }
+        let alive = tauri::async_runtime::spawn_blocking(move || orphan::is_sidecar_alive(pid))
+            .await
+            .unwrap_or(true);
+        if alive {
+            continue;
+        }
+        // Lost a race with terminate_sidecar or a restart
+        if ADOPTED_PID.compare_exchange(pid, 0, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return;
+        }
+        
+        log::warn!("[sidecar] Adopted process {} exited", pid);
+        if let Ok(mut tree) = SIDECAR_TREE.lock() {
+            *tree = None;
+        }
+        orphan::remove_record();
+        set_sidecar_port(0);
+        clear_sidecar_socket();
+        backend_version::clear();
+        orpc_bridge::mark_backend_down();
+        crash_loop::backend_died(None);
+        
+        if let Err(e) = app.emit("backend-terminated", None::<i32>) {
+            log::error!("Failed to emit backend-terminated event: {}", e);
+        }
+        return;
+    }
+}
+
+/// Persist where the sidecar is listening for orphan recovery
+fn record_running(pid: u32) {
+    orphan::write_record(&orphan::PidRecord {
+        pid,
+        port: get_sidecar_port(),
+        socket: get_sidecar_socket(),
+        auth_token: get_auth_token(),
+    });
+}
+
+/// Whether the sidecar has announced where it is listening
+pub fn is_backend_ready() -> bool {
+    get_sidecar_port() != 0 || get_sidecar_socket().is_some()
+}
+
+/// Get the token to send as `Authorization: Bearer <token>`
+pub fn get_auth_token() -> Option<String> {
+    SIDECAR_AUTH_TOKEN.read().ok().and_then(|guard| guard.clone())
+}
+
+/// Generate a random hex token
+pub(crate) fn generate_auth_token() -> String {
+    use rand::RngCore;
+    
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 /// Sidecar management commands
@@ -43,13 +346,13 @@ pub async fn get_backend_port() -> Result<u16, String> {
 /// Check if backend is healthy
 #[tauri::command]
 pub async fn check_backend_health() -> Result<bool, String> {
-    let port = get_sidecar_port();
-    if port == 0 {
-        return Ok(false);
-    }
+    let base_url = match orpc_bridge::get_backend_url() {
+        Ok(url) => url,
+        Err(_) => return Ok(false),
+    };
 
-    let client = reqwest::Client::new();
-    let url = format!("http://127.0.0.1:{}/health", port);
+    let client = orpc_bridge::ensure_client()?;
+    let url = format!("{}/health", base_url);
     
     match client.get(&url).timeout(std::time::Duration::from_secs(2)).send().await {
         Ok(resp) => Ok(resp.status().is_success()),
@@ -67,30 +370,303 @@ fn parse_port_from_line(line: &str) -> Option<u16> {
     }
 }
 
+/// The backend emits "MUX_SERVER_WAITING" while another backend holds its
+/// data directory
+fn is_waiting_line(line: &str) -> bool {
+    line.trim() == "MUX_SERVER_WAITING"
+}
+
+/// Parse socket path from sidecar stdout
+/// The backend emits "MUX_SERVER_SOCKET:<path>" when started with --socket
+fn parse_socket_from_line(line: &str) -> Option<PathBuf> {
+    line.strip_prefix("MUX_SERVER_SOCKET:")
+        .map(|path| PathBuf::from(path.trim()))
+        .filter(|path| !path.as_os_str().is_empty())
+}
+
+/// Socket path for a new sidecar; each spawn gets its own so a standby
+/// doesn't remove the socket of the sidecar it replaces
+#[cfg(unix)]
+fn socket_path() -> PathBuf {
+    let n = SOCKET_COUNTER.fetch_add(1, Ordering::SeqCst);
+    std::env::temp_dir().join(format!("mup-{}-{}.sock", std::process::id(), n))
+}
+
+/// Named pipe for a new sidecar; Node listens on these like on a socket
+#[cfg(windows)]
+fn socket_path() -> PathBuf {
+    let n = SOCKET_COUNTER.fetch_add(1, Ordering::SeqCst);
+    PathBuf::from(format!(r"\\.\pipe\mup-{}-{}", std::process::id(), n))
+}
+
+/// Remove a sidecar's socket file; the backend only does so itself when
+/// it shuts down cleanly. Named pipes go away with their server.
+fn remove_socket(path: &Path) {
+    #[cfg(unix)]
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            log::warn!("Failed to remove sidecar socket {}: {}", path.display(), e);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// CPU architecture of the machine, which may differ from the build target
+/// when running translated (Rosetta, Windows x64 emulation on ARM64)
+fn host_arch() -> String {
+    if cfg!(target_os = "macos") && commands::check_is_rosetta() {
+        return "aarch64".to_string();
+    }
+    
+    #[cfg(windows)]
+    if let Some(arch) = windows_native_arch() {
+        return arch.to_string();
+    }
+    
+    if cfg!(target_os = "linux") {
+        if let Ok(output) = std::process::Command::new("uname").arg("-m").output() {
+            match String::from_utf8_lossy(&output.stdout).trim() {
+                "aarch64" | "arm64" => return "aarch64".to_string(),
+                arch if arch.starts_with("armv7") => return "armv7".to_string(),
+                "x86_64" => return "x86_64".to_string(),
+                _ => {}
+            }
+        }
+    }
+    
+    std::env::consts::ARCH.to_string()
+}
+
+/// Native architecture of a Windows machine. The environment can't tell:
+/// an x64 process emulated on ARM64 sees PROCESSOR_ARCHITECTURE=AMD64 and
+/// no PROCESSOR_ARCHITEW6432, so ask the OS for the native machine type.
+#[cfg(windows)]
+fn windows_native_arch() -> Option<&'static str> {
+    use windows_sys::Win32::System::SystemInformation::{
+        IMAGE_FILE_MACHINE, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+    let mut process_machine: IMAGE_FILE_MACHINE = 0;
+    let mut native_machine: IMAGE_FILE_MACHINE = 0;
+    // SAFETY: the pseudo handle needs no closing and both out pointers are valid
+    let ok = unsafe { IsWow64Process2(GetCurrentProcess(), &mut process_machine, &mut native_machine) };
+    if ok == 0 {
+        return None;
+    }
+    match native_machine {
+        IMAGE_FILE_MACHINE_ARM64 => Some("aarch64"),
+        IMAGE_FILE_MACHINE_AMD64 => Some("x86_64"),
+        _ => None,
+    }
+}
+
+/// Target triple of the machine, for picking a backend build
+pub(crate) fn host_triple() -> String {
+    target_triple(&host_arch())
+}
+
+/// Target triple for an architecture on the current OS
+fn target_triple(arch: &str) -> String {
+    match std::env::consts::OS {
+        "macos" => format!("{}-apple-darwin", arch),
+        "windows" => format!("{}-pc-windows-msvc", arch),
+        _ if arch == "armv7" => "armv7-unknown-linux-gnueabihf".to_string(),
+        _ => format!("{}-unknown-linux-gnu", arch),
+    }
+}
+
+/// Candidate paths for a bundled binary, best match first
+///
+/// Prefers a binary built for the host architecture, then one for the
+/// architecture this app was built for, then the plain bundled name. The
+/// build's own binary sits next to the app binary; builds for another
+/// architecture are bundled as resources.
+pub(crate) fn binary_candidates(app: &AppHandle, base_name: &str) -> Result<Vec<PathBuf>, String> {
+    let exe_dir = tauri::process::current_binary(&app.env())
+        .map_err(|e| format!("Failed to locate app binary: {}", e))?
+        .parent()
+        .map(|dir| dir.to_path_buf())
+        .ok_or_else(|| "App binary has no parent directory".to_string())?;
+    let mut dirs = vec![exe_dir];
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        if !dirs.contains(&resource_dir) {
+            dirs.push(resource_dir);
+        }
+    }
+    
+    let host_triple = target_triple(&host_arch());
+    let build_triple = target_triple(std::env::consts::ARCH);
+    
+    let mut names = vec![format!("{}-{}", base_name, host_triple)];
+    if build_triple != host_triple {
+        names.push(format!("{}-{}", base_name, build_triple));
+    }
+    names.push(base_name.to_string());
+    
+    Ok(names
+        .iter()
+        .flat_map(|name| {
+            dirs.iter()
+                .map(move |dir| dir.join(format!("{}{}", name, std::env::consts::EXE_SUFFIX)))
+        })
+        .collect())
+}
+
+/// Locate the backend binary best matching this machine
+fn resolve_sidecar_binary(app: &AppHandle) -> Result<PathBuf, String> {
+    let searched = binary_candidates(app, SIDECAR_NAME)?;
+    
+    if let Some(path) = searched.iter().find(|path| path.is_file()) {
+        log::info!("Using backend binary {}", path.display());
+        return Ok(path.clone());
+    }
+    
+    let host_triple = target_triple(&host_arch());
+    let payload = BinaryUnavailablePayload {
+        host_triple: host_triple.clone(),
+        searched,
+    };
+    if let Err(e) = app.emit("backend-binary-unavailable", payload) {
+        log::error!("Failed to emit backend-binary-unavailable event: {}", e);
+    }
+    
+    Err(format!(
+        "No compatible backend binary for {}. Reinstall the build for this platform.",
+        host_triple
+    ))
+}
+
+/// Startup timeout, overridable via MUX_SIDECAR_STARTUP_TIMEOUT_SECS
+fn startup_timeout() -> Duration {
+    let secs = std::env::var("MUX_SIDECAR_STARTUP_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
 /// Spawn the sidecar process
-pub fn spawn_sidecar(app: &AppHandle) -> Result<(), String> {
+///
+/// Must not be called from an async context (the process handle is stored
+/// with a blocking lock); use `spawn_blocking` from commands.
+pub fn spawn_sidecar(app: &AppHandle, config: &SidecarConfig) -> Result<(), String> {
+    spawn_process(app, config, false).map(|_| ())
+}
+
+/// Spawn a sidecar process; a standby is returned instead of becoming the
+/// running sidecar, and reports readiness through its channel
+fn spawn_process(app: &AppHandle, config: &SidecarConfig, standby: bool) -> Result<Option<Standby>, String> {
     log::info!("Starting mup-server sidecar...");
     
-    // Get the sidecar command
-    let sidecar = app
-        .shell()
-        .sidecar("mup-server")
-        .map_err(|e| format!("Failed to create sidecar command: {}", e))?;
+    // Get the sidecar command for the best matching binary
+    // A backend installed by `update_sidecar` runs while it is newer than
+    // the bundled one; its signature is verified before it is returned
+    let binary = match sidecar_update::installed_binary(app) {
+        Some(binary) => binary,
+        None => {
+            let binary = resolve_sidecar_binary(app)?;
+            integrity::verify_binary(app, &binary, SIDECAR_NAME)?;
+            binary
+        }
+    };
+    let mut sidecar = app.shell().command(binary);
+    
+    // Data directory: explicit setting, else isolated per profile
+    let mux_home = match config.data_dir {
+        Some(ref dir) => Some(dir.clone()),
+        None => profiles::backend_home(app)?,
+    };
+    if let Some(mux_home) = mux_home {
+        sidecar = sidecar.arg("--mux-home").arg(mux_home);
+    }
+    
+    if let Some(ref dir) = config.working_dir {
+        sidecar = sidecar.current_dir(dir);
+    }
+    
+    if let Some(port) = config.preferred_port {
+        sidecar = sidecar.arg("--port").arg(port.to_string());
+    }
+    
+    if config.listen_on_lan && (config.transport == BackendTransport::Tcp || cfg!(not(any(unix, windows)))) {
+        sidecar = sidecar.arg("--host").arg("0.0.0.0");
+    }
+    
+    for (key, value) in config_env(config)? {
+        sidecar = sidecar.env(key, value);
+    }
+    
+    // Let the backend request native actions
+    for (key, value) in crate::native_control::sidecar_env() {
+        sidecar = sidecar.env(key, value);
+    }
+    
+    match config.transport {
+        BackendTransport::Tcp => {}
+        #[cfg(any(unix, windows))]
+        BackendTransport::Socket => {
+            let path = socket_path();
+            // A stale socket from a previous run would make listen() fail
+            remove_socket(&path);
+            sidecar = sidecar.arg("--socket").arg(path);
+        }
+        #[cfg(not(any(unix, windows)))]
+        BackendTransport::Socket => {
+            log::warn!("Socket transport is not supported on this platform; using TCP");
+        }
+    }
+    
+    // Only this process knows the token, so other local processes can't
+    // use the backend's HTTP port
+    let auth_token = generate_auth_token();
+    sidecar = sidecar.env("MUX_SERVER_AUTH_TOKEN", &auth_token);
+    if !standby {
+        if let Ok(mut guard) = SIDECAR_AUTH_TOKEN.write() {
+            *guard = Some(auth_token.clone());
+        }
+    }
     
     // Spawn the process
     let (mut rx, child) = sidecar
         .spawn()
         .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
-    
-    // Store the process handle
-    let process_handle = SIDECAR_PROCESS.get_or_init(|| Arc::new(Mutex::new(None)));
-    {
-        let mut guard = process_handle.blocking_lock();
-        *guard = Some(child);
-    }
+    let pid = child.pid();
     
     let app_handle = app.clone();
     
+    // Set once the port is announced or the process exits
+    let settled = Arc::new(AtomicBool::new(false));
+    let stderr_tail = Arc::new(std::sync::Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES)));
+    
+    // A standby reports where it listens to the restart instead of taking
+    // over; the restart enforces its startup timeout
+    let (mut standby_tx, mut waiting_tx, standby) = if standby {
+        if let Ok(mut quiet) = QUIET_PIDS.lock() {
+            quiet.push(pid);
+        }
+        let (tx, ready) = tokio::sync::oneshot::channel();
+        let (waiting_tx, waiting) = tokio::sync::oneshot::channel();
+        (Some(tx), Some(waiting_tx), Some(Standby { child, auth_token, ready, waiting }))
+    } else {
+        // Track the process tree so agent subprocesses die with the sidecar
+        if let Ok(mut tree) = SIDECAR_TREE.lock() {
+            *tree = Some(ProcessTree::attach(pid));
+        }
+        
+        // Store the process handle
+        let process_handle = SIDECAR_PROCESS.get_or_init(|| Arc::new(Mutex::new(None)));
+        {
+            let mut guard = process_handle.blocking_lock();
+            *guard = Some(child);
+        }
+        
+        spawn_startup_watchdog(app.clone(), settled.clone(), stderr_tail.clone());
+        (None, None, None)
+    };
+    
     // Handle sidecar output in background
     tauri::async_runtime::spawn(async move {
         use tauri_plugin_shell::process::CommandEvent;
@@ -101,33 +677,115 @@ pub fn spawn_sidecar(app: &AppHandle) -> Result<(), String> {
                     let line_str = String::from_utf8_lossy(&line);
                     log::debug!("[sidecar stdout] {}", line_str.trim());
                     
+                    // A standby only goes live once the restart switches to it
+                    if let Some(tx) = standby_tx.take() {
+                        if is_waiting_line(&line_str) {
+                            if let Some(waiting_tx) = waiting_tx.take() {
+                                let _ = waiting_tx.send(());
+                            }
+                            standby_tx = Some(tx);
+                            continue;
+                        }
+                        let listening = parse_port_from_line(&line_str)
+                            .map(Listening::Port)
+                            .or_else(|| parse_socket_from_line(&line_str).map(Listening::Socket));
+                        match listening {
+                            Some(listening) => {
+                                settled.store(true, Ordering::SeqCst);
+                                let _ = tx.send(listening);
+                            }
+                            None => standby_tx = Some(tx),
+                        }
+                        continue;
+                    }
+                    
                     // Check for port announcement
                     if let Some(port) = parse_port_from_line(&line_str) {
                         log::info!("Sidecar announced port: {}", port);
-                        set_sidecar_port(port);
+                        settled.store(true, Ordering::SeqCst);
+                        let previous_port = announce_port(port);
+                        record_running(pid);
+                        
+                        // Let in-flight UI operations retarget the new port
+                        if let Some(previous_port) = previous_port {
+                            log::info!("Backend port changed from {} to {}", previous_port, port);
+                            let payload = PortChangedPayload { previous_port, port };
+                            if let Err(e) = app_handle.emit("backend-port-changed", payload) {
+                                log::error!("Failed to emit backend-port-changed event: {}", e);
+                            }
+                        }
                         
                         // Emit backend ready event
                         if let Err(e) = app_handle.emit("backend-ready", port) {
                             log::error!("Failed to emit backend-ready event: {}", e);
                         }
+                        
+                        // Verify the bundled backend matches this build
+                        tauri::async_runtime::spawn(backend_version::handshake(app_handle.clone()));
+                    } else if let Some(socket) = parse_socket_from_line(&line_str) {
+                        log::info!("Sidecar announced socket: {}", socket.display());
+                        settled.store(true, Ordering::SeqCst);
+                        set_sidecar_socket(Some(socket));
+                        record_running(pid);
+                        
+                        // Port 0 tells the frontend the backend has no TCP port
+                        if let Err(e) = app_handle.emit("backend-ready", 0u16) {
+                            log::error!("Failed to emit backend-ready event: {}", e);
+                        }
+                        
+                        tauri::async_runtime::spawn(backend_version::handshake(app_handle.clone()));
                     }
                 }
                 CommandEvent::Stderr(line) => {
-                    log::warn!("[sidecar stderr] {}", String::from_utf8_lossy(&line).trim());
+                    let line_str = String::from_utf8_lossy(&line).trim().to_string();
+                    log::warn!("[sidecar stderr] {}", line_str);
+                    
+                    if let Ok(mut tail) = stderr_tail.lock() {
+                        if tail.len() == STDERR_TAIL_LINES {
+                            tail.pop_front();
+                        }
+                        tail.push_back(line_str);
+                    }
                 }
                 CommandEvent::Error(err) => {
                     log::error!("[sidecar error] {}", err);
                 }
                 CommandEvent::Terminated(payload) => {
                     log::info!("[sidecar] Process terminated with code: {:?}", payload.code);
+                    settled.store(true, Ordering::SeqCst);
                     
-                    // Clear process handle
+                    // Standbys and replaced sidecars exit without affecting
+                    // the running backend
+                    let quiet = QUIET_PIDS
+                        .lock()
+                        .map(|mut quiet| {
+                            let found = quiet.contains(&pid);
+                            quiet.retain(|p| *p != pid);
+                            found
+                        })
+                        .unwrap_or(false);
+                    if quiet {
+                        break;
+                    }
+                    
+                    // Clear process handle, unless a restart already replaced it
                     let process_handle = SIDECAR_PROCESS.get_or_init(|| Arc::new(Mutex::new(None)));
                     let mut guard = process_handle.lock().await;
-                    *guard = None;
-                    
-                    // Clear port
-                    set_sidecar_port(0);
+                    if guard.as_ref().map(|c| c.pid()) == Some(pid) {
+                        *guard = None;
+                        if let Ok(mut tree) = SIDECAR_TREE.lock() {
+                            *tree = None;
+                        }
+                        orphan::remove_record();
+                        
+                        // Clear port
+                        set_sidecar_port(0);
+                        clear_sidecar_socket();
+                        backend_version::clear();
+                        orpc_bridge::mark_backend_down();
+                        crash_loop::backend_died(payload.code);
+                    }
+                    drop(guard);
                     
                     // Emit termination event
                     if let Err(e) = app_handle.emit("backend-terminated", payload.code) {
@@ -140,11 +798,69 @@ pub fn spawn_sidecar(app: &AppHandle) -> Result<(), String> {
         }
     });
     
-    Ok(())
+    Ok(standby)
+}
+
+/// Kill the sidecar if it has not announced its port within the startup timeout
+fn spawn_startup_watchdog(
+    app: AppHandle,
+    settled: Arc<AtomicBool>,
+    stderr_tail: Arc<std::sync::Mutex<VecDeque<String>>>,
+) {
+    let timeout = startup_timeout();
+    
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        
+        if settled.load(Ordering::SeqCst) {
+            return;
+        }
+        
+        let stderr_tail: Vec<String> = stderr_tail
+            .lock()
+            .map(|tail| tail.iter().cloned().collect())
+            .unwrap_or_default();
+        
+        log::error!(
+            "Sidecar did not announce a port within {}s; killing it",
+            timeout.as_secs()
+        );
+        
+        let payload = StartupTimeoutPayload {
+            timeout_secs: timeout.as_secs(),
+            stderr_tail,
+        };
+        if let Err(e) = app.emit("backend-startup-timeout", payload) {
+            log::error!("Failed to emit backend-startup-timeout event: {}", e);
+        }
+        
+        if let Err(e) = terminate_sidecar().await {
+            log::error!("{}", e);
+        }
+    });
+}
+
+/// Environment variables the sidecar gets from its configuration, on top
+/// of the app's own environment (the auth token is added per spawn)
+pub(crate) fn config_env(config: &SidecarConfig) -> Result<Vec<(&'static str, String)>, String> {
+    let mut env = Vec::new();
+    
+    if let Some(ref level) = config.log_level {
+        let level = level.to_lowercase();
+        if !LOG_LEVELS.contains(&level.as_str()) {
+            return Err(format!("Invalid backend log level: {}", level));
+        }
+        env.push(("MUX_LOG_LEVEL", level));
+    }
+    
+    if !config.feature_flags.is_empty() {
+        env.push(("MUX_FEATURE_FLAGS", config.feature_flags.join(",")));
+    }
+    
+    Ok(env)
 }
 
 /// Terminate the sidecar process
-#[allow(dead_code)]
 pub async fn terminate_sidecar() -> Result<(), String> {
     log::info!("Terminating mup-server sidecar...");
     
@@ -152,12 +868,201 @@ pub async fn terminate_sidecar() -> Result<(), String> {
     let mut guard = process_handle.lock().await;
     
     if let Some(child) = guard.take() {
-        child
-            .kill()
-            .map_err(|e| format!("Failed to kill sidecar: {}", e))?;
-        log::info!("Sidecar process killed");
+        // Descendants first: once the sidecar dies they are reparented and
+        // can no longer be found from its pid
+        let tree = SIDECAR_TREE.lock().ok().and_then(|mut tree| tree.take());
+        let root_killed = tree.map(|tree| tree.kill_descendants()).unwrap_or(false);
+        
+        match child.kill() {
+            Ok(()) => log::info!("Sidecar process killed"),
+            Err(e) if root_killed => log::debug!("Sidecar already exited with its job: {}", e),
+            Err(e) => return Err(format!("Failed to kill sidecar: {}", e)),
+        }
+    } else if ADOPTED_PID.swap(0, Ordering::SeqCst) != 0 {
+        // Adopted sidecars have no handle; kill them by pid
+        if let Some(tree) = SIDECAR_TREE.lock().ok().and_then(|mut tree| tree.take()) {
+            tree.kill_all();
+            log::info!("Adopted sidecar process killed");
+        }
     }
     
+    orphan::remove_record();
+    
     set_sidecar_port(0);
+    clear_sidecar_socket();
     Ok(())
 }
+
+/// Restart the sidecar with the current settings
+///
+/// Blue/green restarts keep the current backend serving until the new one
+/// is ready. They need a randomly assigned port, so with a preferred port
+/// (or no running backend) they fall back to a plain restart.
+#[tauri::command]
+pub async fn restart_backend(app: AppHandle, mode: Option<RestartMode>) -> Result<(), String> {
+    let config = settings::current().sidecar;
+    
+    if mode == Some(RestartMode::BlueGreen) {
+        if config.preferred_port.is_some() {
+            log::warn!("Blue/green restart needs a random port; restarting in place");
+        } else if is_backend_ready() {
+            return restart_blue_green(app, config).await;
+        }
+    }
+    
+    terminate_sidecar().await?;
+    
+    tauri::async_runtime::spawn_blocking(move || spawn_sidecar(&app, &config))
+        .await
+        .map_err(|e| format!("Restart task failed: {}", e))?
+}
+
+/// Start a standby sidecar, switch to it once ready, then drain and stop
+/// the one it replaces
+///
+/// Two backends never share the data directory: a standby that finds it
+/// held announces that it is waiting, and the running backend is drained
+/// and stopped before the standby takes over.
+async fn restart_blue_green(app: AppHandle, config: SidecarConfig) -> Result<(), String> {
+    log::info!("Starting standby sidecar for blue/green restart");
+    
+    let app_handle = app.clone();
+    let standby = tauri::async_runtime::spawn_blocking(move || spawn_process(&app_handle, &config, true))
+        .await
+        .map_err(|e| format!("Restart task failed: {}", e))??
+        .ok_or_else(|| "Standby sidecar was not created".to_string())?;
+    let Standby { child, auth_token, mut ready, waiting } = standby;
+    let pid = child.pid();
+    
+    // Keep the running backend if the standby never becomes ready
+    let first = tokio::time::timeout(startup_timeout(), async {
+        tokio::select! {
+            listening = &mut ready => listening.map(Some),
+            Ok(()) = waiting => Ok(None),
+        }
+    })
+    .await;
+    let (listening, old_stopped) = match first {
+        Ok(Ok(Some(listening))) => (listening, false),
+        Ok(Ok(None)) => {
+            log::info!("Standby sidecar is waiting for the data directory; draining the running one");
+            drain_and_detach().await.stop();
+            
+            match tokio::time::timeout(startup_timeout(), ready).await {
+                Ok(Ok(listening)) => (listening, true),
+                failed => {
+                    let _ = child.kill();
+                    let reason = match failed {
+                        Ok(_) => "exited before it was ready".to_string(),
+                        Err(_) => format!("did not become ready within {}s", startup_timeout().as_secs()),
+                    };
+                    // The old backend is gone, so start one the usual way
+                    log::warn!("Standby sidecar {}; starting a new sidecar", reason);
+                    terminate_sidecar().await?;
+                    let config = settings::current().sidecar;
+                    return tauri::async_runtime::spawn_blocking(move || spawn_sidecar(&app, &config))
+                        .await
+                        .map_err(|e| format!("Restart task failed: {}", e))?;
+                }
+            }
+        }
+        Ok(Err(_)) => return Err("Standby sidecar exited before it was ready".to_string()),
+        Err(_) => {
+            let _ = child.kill();
+            return Err(format!(
+                "Standby sidecar did not become ready within {}s",
+                startup_timeout().as_secs()
+            ));
+        }
+    };
+    
+    // Switch the app over to the standby
+    let old_endpoint = orpc_bridge::endpoint_key();
+    let replaced = if old_stopped { None } else { Some(detach_running().await) };
+    let process_handle = SIDECAR_PROCESS.get_or_init(|| Arc::new(Mutex::new(None)));
+    *process_handle.lock().await = Some(child);
+    if let Ok(mut tree) = SIDECAR_TREE.lock() {
+        *tree = Some(ProcessTree::attach(pid));
+    }
+    if let Ok(mut quiet) = QUIET_PIDS.lock() {
+        quiet.retain(|p| *p != pid);
+    }
+    if let Ok(mut guard) = SIDECAR_AUTH_TOKEN.write() {
+        *guard = Some(auth_token);
+    }
+    
+    let ready_port = match listening {
+        Listening::Port(port) => {
+            let previous_port = announce_port(port);
+            set_sidecar_socket(None);
+            if let Some(previous_port) = previous_port {
+                log::info!("Backend port changed from {} to {}", previous_port, port);
+                let payload = PortChangedPayload { previous_port, port };
+                if let Err(e) = app.emit("backend-port-changed", payload) {
+                    log::error!("Failed to emit backend-port-changed event: {}", e);
+                }
+            }
+            port
+        }
+        Listening::Socket(socket) => {
+            set_sidecar_socket(Some(socket));
+            set_sidecar_port(0);
+            0
+        }
+    };
+    record_running(pid);
+    log::info!("Switched to standby sidecar (pid {})", pid);
+    
+    if let Err(e) = app.emit("backend-ready", ready_port) {
+        log::error!("Failed to emit backend-ready event: {}", e);
+    }
+    tauri::async_runtime::spawn(backend_version::handshake(app.clone()));
+    
+    // Let calls already sent to the old backend finish, then stop it
+    if let Some(replaced) = replaced {
+        drain(&old_endpoint).await;
+        replaced.stop();
+    }
+    
+    Ok(())
+}
+
+/// Wait until the calls in flight to an endpoint (unary calls, streams and
+/// transfers) have finished, or the drain timeout passes
+async fn drain(endpoint: &str) {
+    let remaining = orpc_bridge::wait_for_calls(endpoint, DRAIN_TIMEOUT).await;
+    if remaining > 0 {
+        log::warn!("Stopping the replaced sidecar with {} call(s) still running", remaining);
+    }
+}
+
+/// Hold new calls, drain the running sidecar and take it out of service
+async fn drain_and_detach() -> Replaced {
+    orpc_bridge::set_ready(false);
+    drain(&orpc_bridge::endpoint_key()).await;
+    detach_running().await
+}
+
+/// Take the running sidecar out of service so stopping it isn't treated
+/// as a crash
+async fn detach_running() -> Replaced {
+    let process_handle = SIDECAR_PROCESS.get_or_init(|| Arc::new(Mutex::new(None)));
+    let child = process_handle.lock().await.take();
+    let tree = SIDECAR_TREE.lock().ok().and_then(|mut tree| tree.take());
+    let pid = child
+        .as_ref()
+        .map(|child| child.pid())
+        .unwrap_or_else(|| ADOPTED_PID.swap(0, Ordering::SeqCst));
+    // Adopted sidecars have no output handler to report their exit
+    if child.is_some() {
+        if let Ok(mut quiet) = QUIET_PIDS.lock() {
+            quiet.push(pid);
+        }
+    }
+    Replaced {
+        child,
+        tree,
+        pid,
+        socket: get_sidecar_socket(),
+    }
+}
@@ -0,0 +1,360 @@
+// Auxiliary sidecar registry
+//
+// Besides the mup-server backend (managed by `sidecar`), the app can run
+// further helper processes such as an indexing service or a local model
+// runner. Each is declared in settings with its own binary, port
+// announcement prefix, and health endpoint, and is tracked here by name.
+// Only binaries bundled with the app (`bundle.externalBin`) can be run, so
+// settings can't point the app at an arbitrary executable.
+// Lifecycle events carry the sidecar's name so the frontend can tell them
+// apart.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::process_tree::ProcessTree;
+use crate::{settings, sidecar};
+
+/// Name reported for the main backend in `list_sidecars`
+const BACKEND_NAME: &str = "mup-server";
+
+/// Declaration of an auxiliary sidecar, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SidecarSpec {
+    /// Unique name used in commands and events
+    pub name: String,
+    /// Base name of the bundled binary (resolved like the backend binary)
+    pub binary: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    /// Stdout prefix announcing the port, e.g. "INDEXER_PORT:"
+    pub port_prefix: Option<String>,
+    /// HTTP path answering 2xx when healthy, e.g. "/health"
+    pub health_path: Option<String>,
+    /// Start with the app
+    pub autostart: bool,
+}
+
+impl Default for SidecarSpec {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            binary: String::new(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            port_prefix: None,
+            health_path: None,
+            autostart: true,
+        }
+    }
+}
+
+/// Status of a sidecar as reported to the frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SidecarStatus {
+    pub name: String,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub running: bool,
+}
+
+/// Payload of the `sidecar-ready` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SidecarReadyPayload {
+    pub name: String,
+    pub port: Option<u16>,
+}
+
+/// Payload of the `sidecar-terminated` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SidecarTerminatedPayload {
+    pub name: String,
+    pub code: Option<i32>,
+}
+
+struct RunningSidecar {
+    spec: SidecarSpec,
+    child: CommandChild,
+    tree: ProcessTree,
+    port: Option<u16>,
+}
+
+/// Running auxiliary sidecars, keyed by name
+pub struct SidecarRegistry {
+    running: Mutex<HashMap<String, RunningSidecar>>,
+}
+
+static REGISTRY: OnceLock<SidecarRegistry> = OnceLock::new();
+
+/// The global registry
+pub fn registry() -> &'static SidecarRegistry {
+    REGISTRY.get_or_init(|| SidecarRegistry {
+        running: Mutex::new(HashMap::new()),
+    })
+}
+
+/// Whether `binary` is the base name of a binary bundled with the app
+fn is_bundled(app: &AppHandle, binary: &str) -> bool {
+    app.config()
+        .bundle
+        .external_bin
+        .iter()
+        .flatten()
+        .filter_map(|path| Path::new(path).file_name())
+        .any(|name| name == binary)
+}
+
+/// Parse a port announced with `prefix`
+fn parse_port(line: &str, prefix: &str) -> Option<u16> {
+    line.strip_prefix(prefix)
+        .and_then(|port| port.trim().parse().ok())
+}
+
+impl SidecarRegistry {
+    /// Spawn a sidecar and start tracking it
+    pub fn spawn(&self, app: &AppHandle, spec: SidecarSpec) -> Result<(), String> {
+        if spec.name.is_empty() || spec.name == BACKEND_NAME {
+            return Err(format!("Invalid sidecar name: '{}'", spec.name));
+        }
+
+        let mut running = self.running.lock().map_err(|e| e.to_string())?;
+        if running.contains_key(&spec.name) {
+            return Err(format!("Sidecar '{}' is already running", spec.name));
+        }
+
+        if !is_bundled(app, &spec.binary) {
+            return Err(format!(
+                "Sidecar '{}' must use a binary bundled with the app, not '{}'",
+                spec.name, spec.binary
+            ));
+        }
+        let binary = sidecar::binary_candidates(app, &spec.binary)?
+            .into_iter()
+            .find(|path| path.is_file())
+            .ok_or_else(|| format!("No binary found for sidecar '{}'", spec.name))?;
+
+        log::info!("Starting sidecar '{}' ({})", spec.name, binary.display());
+
+        let (mut rx, child) = app
+            .shell()
+            .command(binary)
+            .args(&spec.args)
+            .envs(spec.env.clone())
+            .spawn()
+            .map_err(|e| format!("Failed to spawn sidecar '{}': {}", spec.name, e))?;
+        let pid = child.pid();
+
+        let name = spec.name.clone();
+        let port_prefix = spec.port_prefix.clone();
+        running.insert(
+            name.clone(),
+            RunningSidecar {
+                spec,
+                child,
+                tree: ProcessTree::attach(pid),
+                port: None,
+            },
+        );
+        drop(running);
+
+        // Without a port announcement the sidecar is ready once spawned
+        if port_prefix.is_none() {
+            emit_ready(app, &name, None);
+        }
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                match event {
+                    CommandEvent::Stdout(line) => {
+                        let line = String::from_utf8_lossy(&line);
+                        log::debug!("[{} stdout] {}", name, line.trim());
+
+                        let port = port_prefix
+                            .as_deref()
+                            .and_then(|prefix| parse_port(&line, prefix));
+                        if let Some(port) = port {
+                            log::info!("Sidecar '{}' announced port: {}", name, port);
+                            registry().set_port(&name, pid, port);
+                            emit_ready(&app, &name, Some(port));
+                        }
+                    }
+                    CommandEvent::Stderr(line) => {
+                        log::warn!("[{} stderr] {}", name, String::from_utf8_lossy(&line).trim());
+                    }
+                    CommandEvent::Error(err) => {
+                        log::error!("[{} error] {}", name, err);
+                    }
+                    CommandEvent::Terminated(payload) => {
+                        log::info!("Sidecar '{}' terminated with code: {:?}", name, payload.code);
+                        registry().forget(&name, pid);
+
+                        let payload = SidecarTerminatedPayload {
+                            name: name.clone(),
+                            code: payload.code,
+                        };
+                        if let Err(e) = app.emit("sidecar-terminated", payload) {
+                            log::error!("Failed to emit sidecar-terminated event: {}", e);
+                        }
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stop a sidecar and its descendants
+    pub fn stop(&self, name: &str) -> Result<(), String> {
+        let entry = self
+            .running
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove(name)
+            .ok_or_else(|| format!("Sidecar '{}' is not running", name))?;
+
+        log::info!("Stopping sidecar '{}'", name);
+        let root_killed = entry.tree.kill_descendants();
+        match entry.child.kill() {
+            Ok(()) => Ok(()),
+            Err(_) if root_killed => Ok(()),
+            Err(e) => Err(format!("Failed to kill sidecar '{}': {}", name, e)),
+        }
+    }
+
+    /// Status of every running auxiliary sidecar
+    pub fn status(&self) -> Vec<SidecarStatus> {
+        let running = match self.running.lock() {
+            Ok(running) => running,
+            Err(_) => return Vec::new(),
+        };
+
+        running
+            .iter()
+            .map(|(name, entry)| SidecarStatus {
+                name: name.clone(),
+                pid: Some(entry.child.pid()),
+                port: entry.port,
+                running: true,
+            })
+            .collect()
+    }
+
+    /// Probe a sidecar's health endpoint
+    ///
+    /// Sidecars without a health endpoint are healthy while running.
+    pub async fn check_health(&self, name: &str) -> Result<bool, String> {
+        let (port, health_path) = {
+            let running = self.running.lock().map_err(|e| e.to_string())?;
+            match running.get(name) {
+                Some(entry) => (entry.port, entry.spec.health_path.clone()),
+                None => return Ok(false),
+            }
+        };
+
+        let (port, path) = match (port, health_path) {
+            (Some(port), Some(path)) => (port, path),
+            (_, None) => return Ok(true),
+            (None, Some(_)) => return Ok(false),
+        };
+
+        let url = format!("http://127.0.0.1:{}{}", port, path);
+        match crate::orpc_bridge::loopback_client()?
+            .get(&url)
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await
+        {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn set_port(&self, name: &str, pid: u32, port: u16) {
+        if let Ok(mut running) = self.running.lock() {
+            if let Some(entry) = running.get_mut(name).filter(|e| e.child.pid() == pid) {
+                entry.port = Some(port);
+            }
+        }
+    }
+
+    /// Drop an exited sidecar, unless it was already replaced
+    fn forget(&self, name: &str, pid: u32) {
+        if let Ok(mut running) = self.running.lock() {
+            if running.get(name).map(|e| e.child.pid()) == Some(pid) {
+                running.remove(name);
+            }
+        }
+    }
+}
+
+fn emit_ready(app: &AppHandle, name: &str, port: Option<u16>) {
+    let payload = SidecarReadyPayload {
+        name: name.to_string(),
+        port,
+    };
+    if let Err(e) = app.emit("sidecar-ready", payload) {
+        log::error!("Failed to emit sidecar-ready event: {}", e);
+    }
+}
+
+/// Start every configured sidecar marked for autostart
+pub fn start_all(app: &AppHandle) {
+    for spec in settings::current().sidecars {
+        if !spec.autostart {
+            continue;
+        }
+        let name = spec.name.clone();
+        if let Err(e) = registry().spawn(app, spec) {
+            log::error!("Failed to start sidecar '{}': {}", name, e);
+        }
+    }
+}
+
+/// List the backend and all running auxiliary sidecars
+#[tauri::command]
+pub async fn list_sidecars() -> Result<Vec<SidecarStatus>, String> {
+    let backend_pid = sidecar::get_sidecar_pid().await;
+    let mut sidecars = vec![SidecarStatus {
+        name: BACKEND_NAME.to_string(),
+        pid: backend_pid,
+        port: Some(sidecar::get_sidecar_port()).filter(|port| *port != 0),
+        running: backend_pid.is_some(),
+    }];
+    sidecars.extend(registry().status());
+    Ok(sidecars)
+}
+
+/// Start a configured auxiliary sidecar by name
+#[tauri::command]
+pub async fn start_named_sidecar(app: AppHandle, name: String) -> Result<(), String> {
+    let spec = settings::current()
+        .sidecars
+        .into_iter()
+        .find(|spec| spec.name == name)
+        .ok_or_else(|| format!("Unknown sidecar '{}'", name))?;
+    registry().spawn(&app, spec)
+}
+
+/// Stop an auxiliary sidecar by name
+#[tauri::command]
+pub async fn stop_named_sidecar(name: String) -> Result<(), String> {
+    registry().stop(&name)
+}
+
+/// Check an auxiliary sidecar's health, or the backend's for "mup-server"
+#[tauri::command]
+pub async fn check_sidecar_health(name: String) -> Result<bool, String> {
+    if name == BACKEND_NAME {
+        return sidecar::check_backend_health().await;
+    }
+    registry().check_health(&name).await
+}
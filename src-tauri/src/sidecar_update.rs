@@ -0,0 +1,270 @@
+// Backend (sidecar) updates
+//
+// The bundled mup-server is built alongside the app and reports the same
+// version. After every handshake the running backend's version is checked
+// against the one expected, so an app update that left a stale binary
+// behind is reported with `sidecar-version-mismatch` instead of failing in
+// confusing ways later.
+//
+// Backend fixes can also ship without an app release. `update_sidecar`
+// fetches the backend manifest and downloads the build for this machine.
+// It verifies the build's minisign signature against the updater key and
+// installs it, with the signature, into the app's data directory; the
+// signature is checked again every time the binary is about to run, since
+// a file in the data directory is as easy to replace as any other. The
+// backend is then restarted
+// blue/green onto the new binary. An installed backend runs instead of the
+// bundled one while it is newer and within the range this build supports,
+// so the next app update supersedes it and it is removed.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend_version::{self, BackendVersion};
+use crate::sidecar::{self, RestartMode};
+use crate::version::is_newer;
+use crate::{offline_update, update_hooks, update_policy, update_proxy};
+
+/// Manifest of the latest backend build for each platform
+const MANIFEST_URL: &str = "https://github.com/Coder/mup/releases/latest/download/mup-server.json";
+
+/// Installed backend and its state file, in the app's local data directory
+const SIDECAR_DIR: &str = "sidecar";
+const STATE_FILE: &str = "installed.json";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A backend installed by `update_sidecar`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct InstalledSidecar {
+    version: String,
+    /// Binary file name in the sidecar directory
+    file: String,
+    /// Base64 minisign signature of the binary, from the manifest
+    signature: String,
+}
+
+/// Latest backend builds, by target triple
+#[derive(Debug, serde::Deserialize)]
+struct SidecarManifest {
+    version: String,
+    notes: Option<String>,
+    platforms: HashMap<String, SidecarBuild>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SidecarBuild {
+    url: String,
+    /// Base64 minisign signature of the binary
+    signature: String,
+}
+
+/// Payload of the `sidecar-version-mismatch` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SidecarMismatchPayload {
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Backend version installed by `update_sidecar`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SidecarUpdate {
+    pub version: String,
+    pub notes: Option<String>,
+}
+
+fn sidecar_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_local_data_dir()
+        .map(|dir| dir.join(SIDECAR_DIR))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+fn load_installed(app: &AppHandle) -> Option<InstalledSidecar> {
+    let path = sidecar_dir(app).ok()?.join(STATE_FILE);
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_installed(app: &AppHandle, installed: Option<&InstalledSidecar>) -> Result<(), String> {
+    let path = sidecar_dir(app)?.join(STATE_FILE);
+    let Some(installed) = installed else {
+        let _ = std::fs::remove_file(path);
+        return Ok(());
+    };
+    let contents = serde_json::to_string_pretty(installed)
+        .map_err(|e| format!("Failed to serialize backend state: {}", e))?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save backend state: {}", e))
+}
+
+/// Remove binaries other than `keep`; one still running stays until the
+/// next cleanup on Windows
+fn remove_binaries(app: &AppHandle, keep: Option<&str>) {
+    let Ok(entries) = sidecar_dir(app).and_then(|dir| std::fs::read_dir(dir).map_err(|e| e.to_string())) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name != STATE_FILE && Some(name.as_str()) != keep {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Whether an installed backend should run instead of the bundled one
+fn is_current(app: &AppHandle, installed: &InstalledSidecar) -> bool {
+    is_newer(&installed.version, &app.package_info().version.to_string())
+        && backend_version::is_supported(&installed.version)
+}
+
+/// The installed backend binary to run, if there is a current one
+///
+/// An installed backend the bundled one has caught up with, or one whose
+/// signature no longer verifies, is removed.
+pub fn installed_binary(app: &AppHandle) -> Option<PathBuf> {
+    let installed = load_installed(app)?;
+    if !is_current(app, &installed) {
+        log::info!("Removing backend {}; the bundled backend supersedes it", installed.version);
+        let _ = save_installed(app, None);
+        remove_binaries(app, None);
+        return None;
+    }
+
+    let path = sidecar_dir(app).ok()?.join(&installed.file);
+    let verified = std::fs::read(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        .and_then(|bytes| offline_update::verify_signature(app, &bytes, &installed.signature));
+    match verified {
+        Ok(_) => {
+            log::info!("Using updated backend {} at {}", installed.version, path.display());
+            Some(path)
+        }
+        Err(e) => {
+            log::error!(
+                "Updated backend {} is missing or damaged ({}); using the bundled one",
+                installed.version,
+                e
+            );
+            let _ = save_installed(app, None);
+            remove_binaries(app, None);
+            None
+        }
+    }
+}
+
+/// Version the running backend should report
+fn expected_version(app: &AppHandle) -> String {
+    load_installed(app)
+        .filter(|installed| is_current(app, installed))
+        .map(|installed| installed.version)
+        .unwrap_or_else(|| app.package_info().version.to_string())
+}
+
+/// Check the running backend is the version this app expects
+///
+/// Development backends only report a commit and aren't checked.
+pub fn verify_backend(app: &AppHandle, backend: &BackendVersion) {
+    let Some(actual) = backend.version.clone() else {
+        return;
+    };
+    let expected = expected_version(app);
+    if backend_version::parse_version(&actual) == backend_version::parse_version(&expected) {
+        return;
+    }
+
+    log::error!("Backend reports version {} but {} was expected", actual, expected);
+    let payload = SidecarMismatchPayload { expected, actual };
+    if let Err(e) = app.emit("sidecar-version-mismatch", payload) {
+        log::error!("Failed to emit sidecar-version-mismatch event: {}", e);
+    }
+}
+
+/// Store a verified backend binary and make it the one to run
+fn install(app: &AppHandle, version: &str, bytes: &[u8], signature: &str) -> Result<InstalledSidecar, String> {
+    let dir = sidecar_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backend directory: {}", e))?;
+
+    let file = format!("mup-server-{}{}", version, std::env::consts::EXE_SUFFIX);
+    let path = dir.join(&file);
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to store backend: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to store backend: {}", e))?;
+    }
+
+    let installed = InstalledSidecar {
+        version: version.to_string(),
+        file,
+        signature: signature.to_string(),
+    };
+    save_installed(app, Some(&installed))?;
+    Ok(installed)
+}
+
+/// Update the backend without reinstalling the app, if a newer build it
+/// supports is available. Returns `None` when the backend is up to date.
+#[tauri::command]
+pub async fn update_sidecar(app: AppHandle) -> Result<Option<SidecarUpdate>, String> {
+    update_policy::ensure_updates_allowed()?;
+    // Backend builds are only published alongside public releases
+    if update_policy::current().update_url.is_some() {
+        return Err("Backend updates come with app updates from your organization's update server".to_string());
+    }
+
+    let client = update_proxy::client(MANIFEST_URL, REQUEST_TIMEOUT)?;
+    let manifest: SidecarManifest = client
+        .get(MANIFEST_URL)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch backend manifest: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid backend manifest: {}", e))?;
+
+    if !is_newer(&manifest.version, &expected_version(&app)) {
+        return Ok(None);
+    }
+    if !backend_version::is_supported(&manifest.version) {
+        return Err(format!("Backend {} needs a newer app; update the app instead", manifest.version));
+    }
+    let triple = sidecar::host_triple();
+    let build = manifest
+        .platforms
+        .get(&triple)
+        .ok_or_else(|| format!("No backend {} build for {}", manifest.version, triple))?;
+
+    let bytes = client
+        .get(&build.url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download backend: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download backend: {}", e))?;
+    offline_update::verify_signature(&app, &bytes, &build.signature)?;
+
+    let previous = load_installed(&app);
+    let installed = install(&app, &manifest.version, &bytes, &build.signature)?;
+
+    // Keep the running backend, and the previous state, if the new one
+    // doesn't come up
+    if let Err(e) = sidecar::restart_backend(app.clone(), Some(RestartMode::BlueGreen)).await {
+        save_installed(&app, previous.as_ref())?;
+        let _ = std::fs::remove_file(sidecar_dir(&app)?.join(&installed.file));
+        // A plain restart has already stopped the old backend
+        update_hooks::resume(&app).await;
+        return Err(format!("Backend {} failed to start: {}", manifest.version, e));
+    }
+    remove_binaries(&app, Some(&installed.file));
+
+    Ok(Some(SidecarUpdate {
+        version: manifest.version,
+        notes: manifest.notes,
+    }))
+}
@@ -0,0 +1,116 @@
+// Single-instance enforcement
+//
+// Only one copy of the app runs per user. Launching it again, including
+// the OS launching it to open a `mux://` URL on Windows and Linux, hands
+// the new process's arguments to the running instance and exits before
+// setup, so no second window or backend is started. Deep links and .mux
+// files in the arguments are routed to the frontend and the main window is
+// brought to the front. A launch asking for another profile or guest mode
+// offers to restart into it, and restarts wait for the instance they
+// replace to exit so they aren't forwarded back to it.
+
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::deeplink_audit::DeepLinkSource;
+use crate::{deeplink, file_open, profiles};
+
+/// Set on a relaunched instance to the PID of the instance it replaces
+pub const RELAUNCH_ENV: &str = "MUX_RELAUNCH_PARENT";
+
+/// Longest wait for the replaced instance to exit
+const RELAUNCH_WAIT: Duration = Duration::from_secs(30);
+
+/// Before taking the lock, wait for the instance this one replaces (on a
+/// profile switch) to exit
+pub fn wait_for_previous_instance() {
+    let Some(pid) = std::env::var(RELAUNCH_ENV).ok().and_then(|pid| pid.parse::<u32>().ok()) else {
+        return;
+    };
+    std::env::remove_var(RELAUNCH_ENV);
+
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    let deadline = Instant::now() + RELAUNCH_WAIT;
+    while system.refresh_process(pid) {
+        if Instant::now() >= deadline {
+            log::warn!("Previous instance {} is still running", pid);
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Plugin that forwards later launches to this instance; register it
+/// before any other plugin
+pub fn plugin() -> tauri::plugin::TauriPlugin<Wry> {
+    tauri_plugin_single_instance::init(|app, argv, cwd| on_second_instance(app, argv, cwd))
+}
+
+fn on_second_instance(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    log::info!("Another launch was forwarded to this instance");
+    focus_main_window(app);
+
+    let args = &argv[1.min(argv.len())..];
+    if let Some(target) = other_profile(args) {
+        offer_restart(app, target, args.to_vec());
+        return;
+    }
+
+    // Relative paths are relative to the second launch's directory
+    for arg in argv.iter().skip(1).filter(|arg| file_open::is_session_file(arg)) {
+        file_open::open(app, Path::new(&cwd).join(arg));
+    }
+
+    for url in argv.into_iter().skip(1).filter(|arg| deeplink::is_deep_link(arg)) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = deeplink::forward(&app, &url, DeepLinkSource::SecondInstance).await {
+                log::warn!("Ignoring forwarded deep link {}: {}", url, e);
+            }
+        });
+    }
+}
+
+/// Profile (or guest mode) a forwarded launch asks for, if not this one's
+fn other_profile(args: &[String]) -> Option<String> {
+    if profiles::guest_arg(args) {
+        return (!profiles::is_guest()).then(|| profiles::GUEST_PROFILE.to_string());
+    }
+    profiles::profile_arg(args).filter(|name| name != profiles::active())
+}
+
+/// Only one instance runs, so ask before restarting into another profile
+fn offer_restart(app: &AppHandle, target: String, args: Vec<String>) {
+    let message = format!(
+        "MUP is running with the {} profile. Restart it with the {} profile?",
+        profiles::active(),
+        target
+    );
+    let app_handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Switch profile?")
+        .buttons(MessageDialogButtons::OkCancelCustom("Restart".to_string(), "Cancel".to_string()))
+        .show(move |confirmed| {
+            if !confirmed {
+                log::info!("Declined to restart with the {} profile", target);
+                return;
+            }
+            if let Err(e) = profiles::relaunch(&app_handle, &args) {
+                log::error!("Failed to restart with the {} profile: {}", target, e);
+            }
+        });
+}
+
+pub(crate) fn focus_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
@@ -0,0 +1,39 @@
+// Full state snapshot
+//
+// A hard webview reload loses everything the frontend learned from events.
+// This gathers the state the UI needs to rebuild itself (backend, open
+// terminals, update status, deep links not yet handled, the unread badge)
+// so recovery takes a single round trip instead of one command per
+// subsystem.
+
+use crate::deeplink::{self, DeepLinkPayload};
+use crate::dock;
+use crate::health::{self, BackendHealth};
+use crate::terminal::{self, PtySummary};
+use crate::updater::{self, UpdateStatus};
+
+/// State needed to reconstruct the UI after a reload
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StateSnapshot {
+    pub backend: BackendHealth,
+    pub terminals: Vec<PtySummary>,
+    pub update_status: Option<UpdateStatus>,
+    /// Deep links waiting for the frontend, oldest first
+    pub pending_deep_links: Vec<DeepLinkPayload>,
+    /// Unread count shown on the dock or taskbar badge
+    pub badge_count: Option<u32>,
+}
+
+/// Get everything the UI needs after a webview reload
+#[tauri::command]
+pub async fn get_full_state_snapshot() -> Result<StateSnapshot, String> {
+    let (backend, terminals) = tokio::join!(health::backend_health(), terminal::list_ptys());
+
+    Ok(StateSnapshot {
+        backend,
+        terminals,
+        update_status: updater::last_status(),
+        pending_deep_links: deeplink::pending_deep_links(),
+        badge_count: dock::badge_count(),
+    })
+}
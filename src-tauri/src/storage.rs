@@ -0,0 +1,148 @@
+// Storage helpers
+//
+// Disk space queries shared by health reporting and operations that need
+// to check free space before writing large files, and bounded unpacking
+// of untrusted archives.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Free and total space of the volume containing a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct DiskSpace {
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Query disk space for the volume containing `path`
+///
+/// Walks up to the nearest existing ancestor so paths that have not been
+/// created yet can still be checked.
+pub fn disk_space(path: &Path) -> Result<DiskSpace, String> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| format!("No existing ancestor for {}", path.display()))?;
+
+    let available_bytes = fs2::available_space(existing)
+        .map_err(|e| format!("Failed to query free space: {}", e))?;
+    let total_bytes = fs2::total_space(existing)
+        .map_err(|e| format!("Failed to query disk size: {}", e))?;
+
+    Ok(DiskSpace {
+        available_bytes,
+        total_bytes,
+    })
+}
+
+/// Storage failures, serialized with a `kind` tag for the frontend
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageError {
+    InsufficientDiskSpace {
+        path: PathBuf,
+        required_bytes: u64,
+        available_bytes: u64,
+    },
+    Unavailable {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::InsufficientDiskSpace {
+                path,
+                required_bytes,
+                available_bytes,
+            } => write!(
+                f,
+                "Insufficient disk space at {}: {} bytes required, {} bytes available",
+                path.display(),
+                required_bytes,
+                available_bytes
+            ),
+            StorageError::Unavailable { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<StorageError> for String {
+    fn from(e: StorageError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Fail unless the volume containing `path` has at least `required_bytes` free
+pub fn ensure_free_space(path: &Path, required_bytes: u64) -> Result<(), StorageError> {
+    let space = disk_space(path).map_err(|message| StorageError::Unavailable { message })?;
+
+    if space.available_bytes < required_bytes {
+        return Err(StorageError::InsufficientDiskSpace {
+            path: path.to_path_buf(),
+            required_bytes,
+            available_bytes: space.available_bytes,
+        });
+    }
+
+    Ok(())
+}
+
+/// How much an archive may unpack to
+#[derive(Debug, Clone, Copy)]
+pub struct UnpackLimits {
+    pub max_entries: usize,
+    pub max_bytes: u64,
+}
+
+/// Unpack the entries of a tar archive that `keep` accepts into `target`
+///
+/// Fails once the archive has more entries or larger files than `limits`
+/// allow (as a gzip bomb would) or the target volume runs short of space.
+/// Whatever was already unpacked is left for the caller to remove.
+pub fn unpack_bounded<R: Read>(
+    archive: &mut tar::Archive<R>,
+    target: &Path,
+    limits: UnpackLimits,
+    mut keep: impl FnMut(&Path, tar::EntryType) -> bool,
+) -> Result<(), String> {
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Invalid archive: {}", e))?;
+    let mut total_bytes: u64 = 0;
+    for (index, entry) in entries.enumerate() {
+        if index >= limits.max_entries {
+            return Err(format!("Archive has more than {} entries", limits.max_entries));
+        }
+        let mut entry = entry.map_err(|e| format!("Invalid archive: {}", e))?;
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid archive entry: {}", e))?
+            .into_owned();
+        if !keep(&path, entry.header().entry_type()) {
+            continue;
+        }
+
+        total_bytes = total_bytes.saturating_add(entry.size());
+        if total_bytes > limits.max_bytes {
+            return Err(format!("Archive unpacks to more than {} bytes", limits.max_bytes));
+        }
+        ensure_free_space(target, entry.size())?;
+
+        // unpack_in refuses paths that would escape the target
+        entry
+            .unpack_in(target)
+            .map_err(|e| format!("Failed to extract {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Check that a location has enough free space for an operation
+#[tauri::command]
+pub async fn check_disk_space(path: PathBuf, required_bytes: u64) -> Result<DiskSpace, StorageError> {
+    ensure_free_space(&path, required_bytes)?;
+    disk_space(&path).map_err(|message| StorageError::Unavailable { message })
+}
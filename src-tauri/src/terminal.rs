@@ -1,14 +1,29 @@
 // Terminal PTY management
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use regex::Regex;
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::sync::Arc;
 use tauri::{Emitter, Window};
 use tokio::sync::Mutex;
 
+use crate::command_history::CommandTracker;
+use crate::prompt::PromptDetector;
+use crate::session_journal::{self, SessionEvent};
+
 // PTY ID counter
 static NEXT_PTY_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1);
 
+// Pre-spawned shells handed out by `create_pty_internal`, so shells with
+// slow rc files still open instantly
+static WARM_POOL: std::sync::Mutex<Vec<WarmPty>> = std::sync::Mutex::new(Vec::new());
+
+// Set while a background refill of the warm pool is running
+static REFILLING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Most shells kept warm, however the setting is configured
+const MAX_WARM_PTYS: usize = 2;
+
 // PTY reader wrapper that implements Send
 struct PtyReader {
     reader: Box<dyn Read + Send>,
@@ -27,7 +42,162 @@ unsafe impl Send for PtyWriter {}
 struct PtyInstance {
     reader: PtyReader,
     writer: PtyWriter,
-    _child: Box<dyn portable_pty::Child + Send>,
+    master: Box<dyn MasterPty + Send>,
+    cols: u16,
+    rows: u16,
+    // Logical (CSS) pixel size of the terminal viewport, if known
+    logical_pixels: Option<(u16, u16)>,
+    // Label of the window showing the terminal and that window's scale
+    // factor, which converts logical pixels into the physical size the PTY
+    // reports; windows on different displays can have different DPIs
+    window: Option<String>,
+    scale_factor: f64,
+    prompt: PromptDetector,
+    // Last title and working directory reported by the shell (OSC 0/2 and 7)
+    title: Option<String>,
+    cwd: Option<String>,
+    // Commands seen through shell-integration markers (OSC 133/633)
+    history: CommandTracker,
+    child: Box<dyn portable_pty::Child + Send>,
+}
+
+// Idle shell waiting in the warm pool
+struct WarmPty {
+    shell: String,
+    instance: PtyInstance,
+}
+
+/// Terminal settings, persisted in app settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TerminalSettings {
+    /// Idle shells kept pre-spawned for new terminals (0 disables the pool)
+    pub warm_pool_size: usize,
+}
+
+impl Default for TerminalSettings {
+    fn default() -> Self {
+        Self { warm_pool_size: 1 }
+    }
+}
+
+/// Signals deliverable to a terminal's foreground process
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalSignal {
+    /// Ctrl+C (SIGINT)
+    Interrupt,
+    /// Ctrl+\ (SIGQUIT)
+    Quit,
+    /// Ctrl+Z (SIGTSTP)
+    Suspend,
+    /// Ctrl+D (end of input)
+    Eof,
+    /// Kill the shell itself
+    Kill,
+}
+
+/// Action applied by `terminal_bulk`
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulkAction {
+    Close,
+    Signal { signal: TerminalSignal },
+    /// Ask the shell to clear the screen (Ctrl+L)
+    Clear,
+}
+
+/// Outcome of a bulk action for one PTY
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BulkResult {
+    pub id: u32,
+    pub error: Option<String>,
+}
+
+/// Summary of an open PTY, for restoring the UI
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PtySummary {
+    pub id: u32,
+    pub title: Option<String>,
+    pub cwd: Option<String>,
+    pub cols: u16,
+    pub rows: u16,
+    pub idle: bool,
+}
+
+/// Matches OSC title (0, 2) and working directory (7) reports
+fn osc_regex() -> &'static Regex {
+    static OSC: OnceLock<Regex> = OnceLock::new();
+    OSC.get_or_init(|| {
+        Regex::new(r"\x1b\](0|2|7);([^\x07\x1b]*)(?:\x07|\x1b\\)").expect("valid OSC regex")
+    })
+}
+
+impl PtyInstance {
+    /// Apply the stored size, converting logical pixels with the scale factor
+    fn apply_size(&self) -> Result<(), String> {
+        let scale = self.scale_factor;
+        let (pixel_width, pixel_height) = self
+            .logical_pixels
+            .map(|(w, h)| {
+                (
+                    (f64::from(w) * scale).round() as u16,
+                    (f64::from(h) * scale).round() as u16,
+                )
+            })
+            .unwrap_or((0, 0));
+        
+        self.master
+            .resize(PtySize {
+                rows: self.rows,
+                cols: self.cols,
+                pixel_width,
+                pixel_height,
+            })
+            .map_err(|e| format!("Failed to resize PTY: {}", e))
+    }
+    
+    /// Send control characters to the shell, as typing them would
+    fn send_control(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.writer
+            .writer
+            .write_all(bytes)
+            .and_then(|_| self.writer.writer.flush())
+            .map_err(|e| format!("Failed to write to PTY: {}", e))?;
+        self.prompt.input_sent();
+        Ok(())
+    }
+    
+    fn signal(&mut self, signal: TerminalSignal) -> Result<(), String> {
+        // The line discipline turns these into signals for the foreground job
+        match signal {
+            TerminalSignal::Interrupt => self.send_control(b"\x03"),
+            TerminalSignal::Quit => self.send_control(b"\x1c"),
+            TerminalSignal::Suspend => self.send_control(b"\x1a"),
+            TerminalSignal::Eof => self.send_control(b"\x04"),
+            TerminalSignal::Kill => self
+                .child
+                .kill()
+                .map_err(|e| format!("Failed to kill shell: {}", e)),
+        }
+    }
+    
+    /// Pick up title and working directory changes from output
+    fn scan_osc(&mut self, output: &[u8]) {
+        let text = String::from_utf8_lossy(output);
+        for captures in osc_regex().captures_iter(&text) {
+            let value = &captures[2];
+            if &captures[1] == "7" {
+                // file://host/path, percent-encoded
+                if let Ok(url) = url::Url::parse(value) {
+                    let path = percent_encoding::percent_decode_str(url.path()).decode_utf8_lossy();
+                    self.cwd = Some(path.into_owned());
+                }
+            } else {
+                self.title = Some(value.to_string());
+            }
+        }
+    }
 }
 
 type PtyMap = Arc<Mutex<HashMap<u32, PtyInstance>>>;
@@ -40,15 +210,18 @@ fn get_pty_map() -> &'static PtyMap {
     PTY_MAP.get_or_init(|| Arc::new(Mutex::new(HashMap::new())))
 }
 
-/// Create a new PTY with the default shell
-pub fn create_pty_internal() -> Result<u32, String> {
-    let pty_system = native_pty_system();
-
-    let shell = if cfg!(windows) {
+/// Shell new terminals run; they inherit the app's environment as is
+pub(crate) fn default_shell() -> String {
+    if cfg!(windows) {
         "cmd.exe".to_string()
     } else {
         std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string())
-    };
+    }
+}
+
+/// Spawn a shell in a new PTY
+fn spawn_instance(shell: &str) -> Result<PtyInstance, String> {
+    let pty_system = native_pty_system();
 
     let pty_size = PtySize {
         rows: 24,
@@ -61,7 +234,10 @@ pub fn create_pty_internal() -> Result<u32, String> {
         .openpty(pty_size)
         .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
+    let prompt = PromptDetector::new(shell);
     let cmd = CommandBuilder::new(shell);
+    // Without an explicit cwd the shell starts in the home directory
+    let cwd = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" });
     
     let child = pty_pair
         .slave
@@ -76,13 +252,108 @@ pub fn create_pty_internal() -> Result<u32, String> {
     let writer = pty_pair.master.take_writer()
         .map_err(|e| format!("Failed to get writer: {}", e))?;
 
-    let id = NEXT_PTY_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-
-    let pty_instance = PtyInstance {
+    Ok(PtyInstance {
         reader: PtyReader { reader },
         writer: PtyWriter { writer },
-        _child: child,
+        master: pty_pair.master,
+        cols: pty_size.cols,
+        rows: pty_size.rows,
+        logical_pixels: None,
+        window: None,
+        scale_factor: 1.0,
+        prompt,
+        title: None,
+        cwd: cwd.map(|dir| dir.to_string_lossy().to_string()),
+        history: CommandTracker::default(),
+        child,
+    })
+}
+
+/// Take a live shell from the warm pool, dropping any that exited or
+/// were started for a different shell
+fn take_warm(shell: &str) -> Option<PtyInstance> {
+    let mut pool = WARM_POOL.lock().ok()?;
+    while let Some(mut warm) = pool.pop() {
+        let alive = matches!(warm.instance.child.try_wait(), Ok(None));
+        if alive && warm.shell == shell {
+            return Some(warm.instance);
+        }
+    }
+    None
+}
+
+/// Top the warm pool up to the configured size in the background
+pub fn refill_pool() {
+    // Idle shells are the first thing given up when memory is short
+    if crate::memory_pressure::is_under_pressure() {
+        return;
+    }
+    if REFILLING.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return;
+    }
+    
+    std::thread::spawn(|| {
+        let target = crate::settings::current()
+            .terminal
+            .warm_pool_size
+            .min(MAX_WARM_PTYS);
+        let shell = default_shell();
+        
+        loop {
+            let missing = match WARM_POOL.lock() {
+                Ok(mut pool) => {
+                    pool.retain(|warm| warm.shell == shell);
+                    pool.truncate(target);
+                    target - pool.len()
+                }
+                Err(_) => break,
+            };
+            if missing == 0 {
+                break;
+            }
+            
+            match spawn_instance(&shell) {
+                Ok(instance) => {
+                    if let Ok(mut pool) = WARM_POOL.lock() {
+                        pool.push(WarmPty {
+                            shell: shell.clone(),
+                            instance,
+                        });
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to pre-spawn terminal shell: {}", e);
+                    break;
+                }
+            }
+        }
+        
+        REFILLING.store(false, std::sync::atomic::Ordering::SeqCst);
+    });
+}
+
+/// Kill the idle shells in the warm pool
+pub fn drain_pool() {
+    let drained = match WARM_POOL.lock() {
+        Ok(mut pool) => std::mem::take(&mut *pool),
+        Err(_) => return,
     };
+    for mut warm in drained {
+        let _ = warm.instance.child.kill();
+    }
+}
+
+/// Create a new PTY with the default shell, using a warm one if available
+pub fn create_pty_internal() -> Result<u32, String> {
+    let shell = default_shell();
+    let pty_instance = match take_warm(&shell) {
+        Some(instance) => instance,
+        None => spawn_instance(&shell)?,
+    };
+    refill_pool();
+
+    let id = NEXT_PTY_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let cwd = pty_instance.cwd.clone();
 
     let rt = tokio::runtime::Handle::try_current()
         .map_err(|e| format!("No runtime: {}", e))?;
@@ -91,6 +362,7 @@ pub fn create_pty_internal() -> Result<u32, String> {
         let mut map = get_pty_map().lock().await;
         map.insert(id, pty_instance);
     });
+    session_journal::record(SessionEvent::TerminalOpened { id, cwd });
 
     Ok(id)
 }
@@ -109,6 +381,7 @@ pub fn write_to_pty_internal(pty_id: u32, data: &[u8]) -> Result<(), String> {
             pty.writer.writer
                 .flush()
                 .map_err(|e| format!("Failed to flush PTY: {}", e))?;
+            pty.prompt.input_sent();
             Ok(())
         } else {
             Err(format!("PTY {} not found", pty_id))
@@ -128,6 +401,16 @@ pub fn read_from_pty_internal(pty_id: u32) -> Result<Vec<u8>, String> {
             match pty.reader.reader.read(&mut buffer) {
                 Ok(n) => {
                     buffer.truncate(n);
+                    pty.prompt.feed(&buffer);
+                    let cwd = pty.cwd.clone();
+                    pty.scan_osc(&buffer);
+                    if let Some(changed) = pty.cwd.as_ref().filter(|next| cwd.as_ref() != Some(*next)) {
+                        session_journal::record(SessionEvent::TerminalCwdChanged {
+                            id: pty_id,
+                            cwd: changed.clone(),
+                        });
+                    }
+                    pty.history.feed(&buffer, pty.cwd.as_deref());
                     Ok(buffer)
                 }
                 Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
@@ -142,23 +425,60 @@ pub fn read_from_pty_internal(pty_id: u32) -> Result<Vec<u8>, String> {
 }
 
 /// Resize PTY
-pub fn resize_pty_internal(pty_id: u32, _cols: u16, _rows: u16) -> Result<(), String> {
+///
+/// Pixel sizes are logical (CSS) pixels; they are scaled to physical pixels
+/// using the scale factor of the window showing the terminal, given as its
+/// label and scale factor when known.
+pub fn resize_pty_internal(
+    pty_id: u32,
+    cols: u16,
+    rows: u16,
+    logical_pixels: Option<(u16, u16)>,
+    window: Option<(String, f64)>,
+) -> Result<(), String> {
     let rt = tokio::runtime::Handle::try_current()
         .map_err(|e| format!("No runtime: {}", e))?;
     
     rt.block_on(async {
         let mut map = get_pty_map().lock().await;
-        if let Some(_pty) = map.get_mut(&pty_id) {
-            // Note: We can't resize through the reader/writer directly
-            // This would require storing the MasterPty separately
-            // For now, we return success but don't actually resize
-            Ok(())
+        if let Some(pty) = map.get_mut(&pty_id) {
+            pty.cols = cols;
+            pty.rows = rows;
+            if logical_pixels.is_some() {
+                pty.logical_pixels = logical_pixels;
+            }
+            // A terminal moved to a detached window resizes from there
+            if let Some((label, scale_factor)) = window {
+                pty.window = Some(label);
+                pty.scale_factor = scale_factor;
+            }
+            pty.apply_size()
         } else {
             Err(format!("PTY {} not found", pty_id))
         }
     })
 }
 
+/// Recompute physical pixel sizes of the PTYs shown in a window after its
+/// DPI changed
+pub fn rescale_window(label: &str, scale_factor: f64) {
+    tauri::async_runtime::block_on(async {
+        let mut map = get_pty_map().lock().await;
+        let shown = map
+            .iter_mut()
+            .filter(|(_, pty)| pty.window.as_deref() == Some(label));
+        for (pty_id, pty) in shown {
+            pty.scale_factor = scale_factor;
+            if pty.logical_pixels.is_none() {
+                continue;
+            }
+            if let Err(e) = pty.apply_size() {
+                log::warn!("Failed to rescale PTY {}: {}", pty_id, e);
+            }
+        }
+    });
+}
+
 /// Close PTY
 pub fn close_pty_internal(pty_id: u32) -> Result<(), String> {
     let rt = tokio::runtime::Handle::try_current()
@@ -167,6 +487,7 @@ pub fn close_pty_internal(pty_id: u32) -> Result<(), String> {
     rt.block_on(async {
         let mut map = get_pty_map().lock().await;
         if map.remove(&pty_id).is_some() {
+            session_journal::record(SessionEvent::TerminalClosed { id: pty_id });
             Ok(())
         } else {
             Err(format!("PTY {} not found", pty_id))
@@ -174,6 +495,74 @@ pub fn close_pty_internal(pty_id: u32) -> Result<(), String> {
     })
 }
 
+/// Kill every shell on quit
+///
+/// Terminals aren't journaled as closed, so the session can bring them
+/// back on the next launch.
+pub async fn shutdown_all() {
+    drain_pool();
+    
+    let closed: Vec<PtyInstance> = get_pty_map().lock().await.drain().map(|(_, pty)| pty).collect();
+    for mut pty in closed {
+        if let Err(e) = pty.child.kill() {
+            log::debug!("Shell already exited: {}", e);
+        }
+    }
+}
+
+/// Summaries of all open PTYs, ordered by ID
+pub async fn list_ptys() -> Vec<PtySummary> {
+    let map = get_pty_map().lock().await;
+    let mut ptys: Vec<PtySummary> = map
+        .iter()
+        .map(|(id, pty)| PtySummary {
+            id: *id,
+            title: pty.title.clone(),
+            cwd: pty.cwd.clone(),
+            cols: pty.cols,
+            rows: pty.rows,
+            idle: pty.prompt.is_idle(),
+        })
+        .collect();
+    ptys.sort_by_key(|pty| pty.id);
+    ptys
+}
+
+/// Tauri command: Apply one action to several terminals
+///
+/// Runs under a single lock; each PTY gets its own result, so one missing
+/// or failing terminal doesn't stop the rest.
+#[tauri::command]
+pub async fn terminal_bulk(action: BulkAction, ids: Vec<u32>) -> Result<Vec<BulkResult>, String> {
+    let mut map = get_pty_map().lock().await;
+    
+    let results = ids
+        .into_iter()
+        .map(|id| {
+            let result = match action {
+                BulkAction::Close => map
+                    .remove(&id)
+                    .map(|_| session_journal::record(SessionEvent::TerminalClosed { id }))
+                    .ok_or_else(|| format!("PTY {} not found", id)),
+                BulkAction::Signal { signal } => map
+                    .get_mut(&id)
+                    .ok_or_else(|| format!("PTY {} not found", id))
+                    .and_then(|pty| pty.signal(signal)),
+                BulkAction::Clear => map
+                    .get_mut(&id)
+                    .ok_or_else(|| format!("PTY {} not found", id))
+                    .and_then(|pty| pty.send_control(b"\x0c")),
+            };
+            BulkResult {
+                id,
+                error: result.err(),
+            }
+        })
+        .collect();
+    
+    Ok(results)
+}
+
 /// Tauri command: Create terminal
 #[tauri::command]
 pub async fn create_terminal(window: Window) -> Result<u32, String> {
@@ -195,10 +584,33 @@ pub async fn terminal_read(pty_id: u32) -> Result<Vec<u8>, String> {
     read_from_pty_internal(pty_id)
 }
 
+/// Tauri command: Whether the terminal's shell is idle at its prompt
+#[tauri::command]
+pub async fn terminal_is_idle(pty_id: u32) -> Result<bool, String> {
+    let map = get_pty_map().lock().await;
+    map.get(&pty_id)
+        .map(|pty| pty.prompt.is_idle())
+        .ok_or_else(|| format!("PTY {} not found", pty_id))
+}
+
 /// Tauri command: Resize terminal
+///
+/// `pixel_width`/`pixel_height` are the viewport size in logical pixels.
 #[tauri::command]
-pub async fn terminal_resize(pty_id: u32, cols: u16, rows: u16) -> Result<(), String> {
-    resize_pty_internal(pty_id, cols, rows)
+pub async fn terminal_resize(
+    window: Window,
+    pty_id: u32,
+    cols: u16,
+    rows: u16,
+    pixel_width: Option<u16>,
+    pixel_height: Option<u16>,
+) -> Result<(), String> {
+    let logical_pixels = pixel_width.zip(pixel_height);
+    let shown_in = window
+        .scale_factor()
+        .ok()
+        .map(|scale_factor| (window.label().to_string(), scale_factor));
+    resize_pty_internal(pty_id, cols, rows, logical_pixels, shown_in)
 }
 
 /// Tauri command: Close terminal
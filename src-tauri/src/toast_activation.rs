@@ -0,0 +1,62 @@
+// Notification activation
+//
+// Notifications carry a `mux://` URL as their protocol activation target.
+// Clicking one after the app has exited makes the OS relaunch the app with
+// that URL as an argument, so launch arguments are routed into the deep
+// link queue for the frontend to drain once it is ready. On Windows the
+// process's AppUserModelID is also registered so toasts are attributed to
+// this install and stay clickable from the Action Center.
+
+use tauri::AppHandle;
+
+use crate::deeplink;
+use crate::deeplink_audit::DeepLinkSource;
+#[cfg(windows)]
+use crate::win_registry;
+
+/// Deep link URLs passed on the command line
+fn launch_urls() -> Vec<String> {
+    std::env::args()
+        .skip(1)
+        .filter(|arg| deeplink::is_deep_link(arg))
+        .collect()
+}
+
+/// Register for toast activation and queue links the app was launched with
+pub fn init(app: &AppHandle) {
+    #[cfg(windows)]
+    if let Err(e) = register_app_user_model_id(app) {
+        log::warn!("Failed to register for toast activation: {}", e);
+    }
+
+    // Queued until the frontend drains them, after any confirmation
+    for url in launch_urls() {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match deeplink::forward(&app, &url, DeepLinkSource::LaunchArgument).await {
+                Ok(()) => log::info!("Queued deep link from launch arguments"),
+                Err(e) => log::warn!("Ignoring launch argument {}: {}", url, e),
+            }
+        });
+    }
+}
+
+/// Set the process AppUserModelID and describe it in the registry
+#[cfg(windows)]
+fn register_app_user_model_id(app: &AppHandle) -> Result<(), String> {
+    use windows_sys::Win32::System::Registry::HKEY_CURRENT_USER;
+    use windows_sys::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+
+    let app_id = app.config().identifier.clone();
+    let hr = unsafe { SetCurrentProcessExplicitAppUserModelID(win_registry::wide(&app_id).as_ptr()) };
+    if hr < 0 {
+        return Err(format!("SetCurrentProcessExplicitAppUserModelID failed: 0x{:08x}", hr));
+    }
+
+    win_registry::write_string(
+        HKEY_CURRENT_USER,
+        &format!("Software\\Classes\\AppUserModelId\\{}", app_id),
+        Some("DisplayName"),
+        &app.package_info().name,
+    )
+}
@@ -1,84 +1,629 @@
-// System tray implementation using tray-icon
-use tray_icon::{
-    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
-    TrayIconBuilder, Icon,
-};
-use tauri::{AppHandle, Emitter, Manager};
+// System tray implementation using Tauri's built-in tray icon
+//
+// The menu is rebuilt by the TrayManager whenever its contents change:
+// recent projects pushed by the frontend with `update_tray_menu`, quick
+// actions set with `set_tray_quick_actions`, and the open terminals, which
+// are watched directly.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::path::BaseDirectory;
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Window, Wry};
 
-/// Create and initialize the system tray
-pub fn create_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Get the path to the tray icon - use fallback for dev mode
-    let icon_path = app.path().resolve("icons/32x32.png", tauri::path::BaseDirectory::Resource);
+use crate::deeplink::{self, DeepLinkPayload};
+use crate::deeplink_audit::DeepLinkSource;
+use crate::notifications::{self, NotificationRequest};
+use crate::{profiles, settings, shutdown, terminal, tray_status};
+
+/// ID of the app's tray icon
+pub const TRAY_ID: &str = "main";
+
+/// Menu item IDs
+const NEW_CHAT_ID: &str = "new_chat";
+const SETTINGS_ID: &str = "settings";
+const QUIT_ID: &str = "quit";
+
+/// Menu ID prefix for profile switch items
+const PROFILE_ITEM_PREFIX: &str = "profile:";
+
+/// Menu ID prefixes for recent project (by index) and terminal (by PTY ID)
+/// items
+const PROJECT_ITEM_PREFIX: &str = "project:";
+const TERMINAL_ITEM_PREFIX: &str = "terminal:";
+
+/// Menu ID prefix for quick actions (by index)
+const ACTION_ITEM_PREFIX: &str = "action:";
+
+/// Most entries shown in each submenu
+const MAX_RECENT_PROJECTS: usize = 10;
+const MAX_TERMINALS: usize = 15;
+const MAX_QUICK_ACTIONS: usize = 20;
+
+/// How often open terminals are checked for changes
+const TERMINAL_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How often to check for a tray host that wasn't there at startup
+#[cfg(target_os = "linux")]
+const HOST_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Whether the tray icon is showing
+static TRAY_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Monochrome tray glyphs: a template image macOS tints for the menu bar,
+/// and variants for light and dark Windows taskbars
+#[cfg(target_os = "macos")]
+const TEMPLATE_ICON: &[u8] = include_bytes!("../icons/tray/tray-template.png");
+#[cfg(windows)]
+const LIGHT_TASKBAR_ICON: &[u8] = include_bytes!("../icons/tray/tray-light.png");
+#[cfg(windows)]
+const DARK_TASKBAR_ICON: &[u8] = include_bytes!("../icons/tray/tray-dark.png");
+
+/// Full-color app icon, used where no monochrome glyph fits
+static APP_ICON: OnceLock<Image<'static>> = OnceLock::new();
+
+/// Tray behavior, persisted in app settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TraySettings {
+    /// Hide the main window to the tray instead of quitting when it is
+    /// closed; the backend keeps running agent tasks
+    pub close_to_tray: bool,
+    /// Hide the main window to the tray when it is minimized
+    pub minimize_to_tray: bool,
+    /// Whether the user has been told the app keeps running in the tray
+    pub close_notice_shown: bool,
+}
+
+/// A project in the "Recent Projects" submenu
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TrayProject {
+    pub name: String,
+    pub path: String,
+}
+
+/// Tray menu contents set by the frontend
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct TrayMenuUpdate {
+    /// Most recent first
+    pub recent_projects: Vec<TrayProject>,
+}
+
+/// A frontend-defined tray shortcut, e.g. "Run tests"
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TrayQuickAction {
+    pub id: String,
+    pub label: String,
+}
+
+/// Payload of the `tray-action` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TrayActionPayload {
+    pub id: String,
+}
+
+/// An open terminal in the "Active Terminals" submenu
+#[derive(Debug, Clone, PartialEq)]
+struct TrayTerminal {
+    id: u32,
+    label: String,
+}
+
+/// Runtime contents of the tray menu
+pub struct TrayManager {
+    quick_actions: Vec<TrayQuickAction>,
+    recent_projects: Vec<TrayProject>,
+    terminals: Vec<TrayTerminal>,
+}
+
+static MANAGER: Mutex<TrayManager> = Mutex::new(TrayManager {
+    quick_actions: Vec::new(),
+    recent_projects: Vec::new(),
+    terminals: Vec::new(),
+});
+
+impl TrayManager {
+    /// Build the menu for the current contents
+    fn build_menu(&self, app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+        let new_chat_item = MenuItem::with_id(app, NEW_CHAT_ID, "New Chat", true, None::<&str>)?;
+        let settings_item = MenuItem::with_id(app, SETTINGS_ID, "Settings", true, None::<&str>)?;
+        let separator = PredefinedMenuItem::separator(app)?;
+        let quit_item = MenuItem::with_id(app, QUIT_ID, "Quit MUP", true, None::<&str>)?;
+        
+        // Recent projects, by index into `recent_projects`
+        let projects_menu = Submenu::new(
+            app,
+            format!("Recent Projects ({})", self.recent_projects.len()),
+            !self.recent_projects.is_empty(),
+        )?;
+        for (index, project) in self.recent_projects.iter().enumerate() {
+            let item = MenuItem::with_id(
+                app,
+                format!("{}{}", PROJECT_ITEM_PREFIX, index),
+                &project.name,
+                true,
+                None::<&str>,
+            )?;
+            projects_menu.append(&item)?;
+        }
+        
+        // Open terminals; choosing one jumps to it
+        let terminals_menu = Submenu::new(
+            app,
+            format!("Active Terminals ({})", self.terminals.len()),
+            !self.terminals.is_empty(),
+        )?;
+        for terminal in self.terminals.iter().take(MAX_TERMINALS) {
+            let item = MenuItem::with_id(
+                app,
+                format!("{}{}", TERMINAL_ITEM_PREFIX, terminal.id),
+                &terminal.label,
+                true,
+                None::<&str>,
+            )?;
+            terminals_menu.append(&item)?;
+        }
+        
+        // Profile switcher, with the active profile checked
+        let profiles_menu = Submenu::new(app, "Profiles", true)?;
+        for name in profiles::profile_names(app) {
+            let item = CheckMenuItem::with_id(
+                app,
+                format!("{}{}", PROFILE_ITEM_PREFIX, name),
+                &name,
+                true,
+                name == profiles::active(),
+                None::<&str>,
+            )?;
+            profiles_menu.append(&item)?;
+        }
+        
+        // Quick actions go first, set off by a separator
+        let menu = Menu::new(app)?;
+        for (index, action) in self.quick_actions.iter().enumerate() {
+            let item = MenuItem::with_id(
+                app,
+                format!("{}{}", ACTION_ITEM_PREFIX, index),
+                &action.label,
+                true,
+                None::<&str>,
+            )?;
+            menu.append(&item)?;
+        }
+        if !self.quick_actions.is_empty() {
+            menu.append(&PredefinedMenuItem::separator(app)?)?;
+        }
+        
+        menu.append_items(&[
+            &new_chat_item,
+            &settings_item,
+            &projects_menu,
+            &terminals_menu,
+            &profiles_menu,
+            &separator,
+            &quit_item,
+        ])?;
+        Ok(menu)
+    }
+    
+    /// Rebuild the tray menu from the current contents
+    fn refresh(app: &AppHandle) {
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+        let menu = match MANAGER.lock() {
+            Ok(manager) => manager.build_menu(app),
+            Err(_) => return,
+        };
+        if let Err(e) = menu.and_then(|menu| tray.set_menu(Some(menu))) {
+            log::warn!("Failed to update tray menu: {}", e);
+        }
+    }
+    
+    /// Replace the recent projects
+    pub fn set_recent_projects(app: &AppHandle, mut projects: Vec<TrayProject>) {
+        projects.truncate(MAX_RECENT_PROJECTS);
+        if let Ok(mut manager) = MANAGER.lock() {
+            if manager.recent_projects == projects {
+                return;
+            }
+            manager.recent_projects = projects;
+        }
+        Self::refresh(app);
+    }
+    
+    /// Replace the quick actions
+    pub fn set_quick_actions(app: &AppHandle, mut actions: Vec<TrayQuickAction>) {
+        actions.truncate(MAX_QUICK_ACTIONS);
+        if let Ok(mut manager) = MANAGER.lock() {
+            if manager.quick_actions == actions {
+                return;
+            }
+            manager.quick_actions = actions;
+        }
+        Self::refresh(app);
+    }
+    
+    /// Keep "Active Terminals" in step with the open PTYs
+    fn watch_terminals(app: &AppHandle) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let terminals: Vec<TrayTerminal> = terminal::list_ptys()
+                    .await
+                    .into_iter()
+                    .map(|pty| TrayTerminal {
+                        id: pty.id,
+                        label: pty
+                            .title
+                            .filter(|title| !title.trim().is_empty())
+                            .or_else(|| {
+                                let cwd = pty.cwd?;
+                                let name = std::path::Path::new(&cwd).file_name()?;
+                                Some(name.to_string_lossy().to_string())
+                            })
+                            .unwrap_or_else(|| format!("Terminal {}", pty.id)),
+                    })
+                    .collect();
+                
+                let changed = match MANAGER.lock() {
+                    Ok(mut manager) if manager.terminals != terminals => {
+                        manager.terminals = terminals;
+                        true
+                    }
+                    _ => false,
+                };
+                if changed {
+                    Self::refresh(&app);
+                }
+                tokio::time::sleep(TERMINAL_POLL_INTERVAL).await;
+            }
+        });
+    }
+    
+    fn recent_project(index: usize) -> Option<TrayProject> {
+        MANAGER.lock().ok()?.recent_projects.get(index).cloned()
+    }
     
-    // Load the icon - use embedded icon as fallback
-    let icon = if let Ok(path) = icon_path {
-        Icon::from_path(path, None)?
+    fn quick_action(index: usize) -> Option<TrayQuickAction> {
+        MANAGER.lock().ok()?.quick_actions.get(index).cloned()
+    }
+}
+
+/// Payload of the `tray-unavailable` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TrayUnavailablePayload {
+    pub reason: String,
+    /// Whether the tray will be created if a host appears later
+    pub retrying: bool,
+}
+
+/// Whether a StatusNotifier host (the tray on most Linux desktops) is running
+///
+/// Without one the tray icon is silently invisible. If the session bus
+/// can't be queried, assume a host exists.
+#[cfg(target_os = "linux")]
+fn status_notifier_host_available() -> bool {
+    let output = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.NameHasOwner",
+            "string:org.kde.StatusNotifierWatcher",
+        ])
+        .output();
+    
+    match output {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).contains("boolean true")
+        }
+        _ => true,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn status_notifier_host_available() -> bool {
+    true
+}
+
+fn emit_unavailable(app: &AppHandle, reason: String, retrying: bool) {
+    log::warn!("System tray unavailable: {}", reason);
+    if let Err(e) = app.emit("tray-unavailable", TrayUnavailablePayload { reason, retrying }) {
+        log::error!("Failed to emit tray-unavailable event: {}", e);
+    }
+}
+
+/// Create the tray, or report why it can't be shown and keep retrying
+/// on Linux until a tray host appears
+pub fn init(app: &AppHandle) {
+    let result = if status_notifier_host_available() {
+        create_tray(app).map_err(|e| e.to_string())
     } else {
-        // Fallback: create a simple icon or skip tray creation in dev
-        return Ok(()); // Skip tray if icon not found (dev mode)
+        Err("No StatusNotifier host is running".to_string())
     };
     
-    // Create menu items
-    let new_chat_item = MenuItem::new("New Chat", true, None);
-    let settings_item = MenuItem::new("Settings", true, None);
-    let separator = PredefinedMenuItem::separator();
-    let quit_item = MenuItem::new("Quit", true, None);
+    match result {
+        Ok(()) => TRAY_AVAILABLE.store(true, Ordering::SeqCst),
+        Err(reason) => {
+            let retrying = cfg!(target_os = "linux");
+            emit_unavailable(app, reason, retrying);
+            #[cfg(target_os = "linux")]
+            spawn_host_retry(app);
+        }
+    }
+}
+
+/// Create the tray once a StatusNotifier host shows up (e.g. after a
+/// shell extension loads)
+#[cfg(target_os = "linux")]
+fn spawn_host_retry(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HOST_RETRY_INTERVAL);
+        if !status_notifier_host_available() {
+            continue;
+        }
+        
+        // GTK objects must be created on the main thread
+        let (tx, rx) = std::sync::mpsc::channel();
+        let app_handle = app.clone();
+        let scheduled = app.run_on_main_thread(move || {
+            let _ = tx.send(create_tray(&app_handle).map_err(|e| e.to_string()));
+        });
+        if scheduled.is_err() {
+            return;
+        }
+        
+        match rx.recv() {
+            Ok(Ok(())) => {
+                log::info!("Tray host appeared; system tray created");
+                TRAY_AVAILABLE.store(true, Ordering::SeqCst);
+                if let Err(e) = app.emit("tray-available", ()) {
+                    log::error!("Failed to emit tray-available event: {}", e);
+                }
+                return;
+            }
+            Ok(Err(e)) => log::warn!("Failed to create system tray: {}", e),
+            Err(_) => return,
+        }
+    });
+}
+
+/// Whether the system tray icon is showing
+#[tauri::command]
+pub async fn is_tray_available() -> Result<bool, String> {
+    Ok(TRAY_AVAILABLE.load(Ordering::SeqCst))
+}
+
+/// Create and initialize the system tray
+///
+/// The tray is owned by the app (under `TRAY_ID`) and its menu events are
+/// delivered on the app's event loop.
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    // Bundled app icon, falling back to the window icon in dev mode
+    let app_icon = match app.path().resolve("icons/32x32.png", BaseDirectory::Resource) {
+        Ok(path) if path.exists() => Image::from_path(path)?,
+        _ => match app.default_window_icon() {
+            Some(icon) => icon.clone().to_owned(),
+            None => return Ok(()),
+        },
+    };
+    let _ = APP_ICON.set(app_icon);
+    let Some((icon, is_template)) = themed_icon(false) else {
+        return Ok(());
+    };
     
-    // Create the menu
-    let menu = Menu::with_items(&[
-        &new_chat_item,
-        &settings_item,
-        &separator,
-        &quit_item,
-    ])?;
+    let menu = match MANAGER.lock() {
+        Ok(manager) => manager.build_menu(app)?,
+        Err(_) => Menu::new(app)?,
+    };
     
     // Build the tray icon with menu
-    let _tray = TrayIconBuilder::new()
-        .with_tooltip("MUP - Coder Multiplexer")
-        .with_icon(icon)
-        .with_menu(Box::new(menu))
-        .build()?;
-    
-    // Listen for menu events in a separate thread
-    let app_clone = app.clone();
-    std::thread::spawn(move || {
-        let menu_channel = MenuEvent::receiver();
-        while let Ok(event) = menu_channel.recv() {
-            handle_menu_event(&app_clone, event);
+    TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("MUP - Coder Multiplexer")
+        .icon(icon)
+        .icon_as_template(is_template)
+        .menu(&menu)
+        .on_menu_event(handle_menu_event)
+        .build(app)?;
+    
+    TrayManager::watch_terminals(app);
+    
+    // Show a status that was set before the tray existed
+    tray_status::apply(app);
+    Ok(())
+}
+
+/// Whether the Windows taskbar uses the dark theme (the taskbar follows
+/// the system mode, which can differ from the app mode)
+#[cfg(windows)]
+fn taskbar_is_dark() -> bool {
+    use windows_sys::Win32::System::Registry::HKEY_CURRENT_USER;
+    
+    let light = crate::win_registry::read_dword(
+        HKEY_CURRENT_USER,
+        "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize",
+        "SystemUsesLightTheme",
+    );
+    // Dark is the default when the value is missing
+    light.unwrap_or(0) == 0
+}
+
+/// Tray icon for the current platform and theme, and whether it is a
+/// template image; `badged` asks for an icon a colored badge shows up on
+pub(crate) fn themed_icon(badged: bool) -> Option<(Image<'static>, bool)> {
+    #[cfg(target_os = "macos")]
+    {
+        // Template images are drawn from alpha only, which would lose the badge
+        if badged {
+            return APP_ICON.get().cloned().map(|icon| (icon, false));
         }
-    });
+        Image::from_bytes(TEMPLATE_ICON).ok().map(|icon| (icon, true))
+    }
+    
+    #[cfg(windows)]
+    {
+        let _ = badged;
+        let bytes = if taskbar_is_dark() { DARK_TASKBAR_ICON } else { LIGHT_TASKBAR_ICON };
+        Image::from_bytes(bytes).ok().map(|icon| (icon, false))
+    }
     
+    // Linux panels vary too much for a monochrome glyph to be safe
+    #[cfg(not(any(target_os = "macos", windows)))]
+    {
+        let _ = badged;
+        APP_ICON.get().cloned().map(|icon| (icon, false))
+    }
+}
+
+/// Set the tray menu's recent projects; open terminals are tracked
+/// automatically
+#[tauri::command]
+pub async fn update_tray_menu(app: AppHandle, update: TrayMenuUpdate) -> Result<(), String> {
+    TrayManager::set_recent_projects(&app, update.recent_projects);
+    Ok(())
+}
+
+/// Set the quick actions shown at the top of the tray menu; choosing one
+/// emits `tray-action` with its ID
+#[tauri::command]
+pub async fn set_tray_quick_actions(app: AppHandle, actions: Vec<TrayQuickAction>) -> Result<(), String> {
+    if let Some(action) = actions.iter().find(|action| action.id.is_empty() || action.label.trim().is_empty()) {
+        return Err(format!("Quick action needs an ID and a label: {:?}", action));
+    }
+    TrayManager::set_quick_actions(&app, actions);
     Ok(())
 }
 
+/// Show and focus the main window
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Explain, once, that closing the window left the app running
+fn show_close_notice(app: &AppHandle) {
+    let mut current = settings::current();
+    if current.tray.close_notice_shown {
+        return;
+    }
+    
+    let notification = NotificationRequest {
+        title: "MUP is still running".to_string(),
+        body: Some("Agent tasks keep running in the background. Choose Quit MUP from the tray menu to exit.".to_string()),
+        url: None,
+    };
+    if let Err(e) = notifications::show(app, &notification) {
+        // Leave the flag unset so the notice is tried again next time
+        log::warn!("Failed to show close notice: {}", e);
+        return;
+    }
+    
+    current.tray.close_notice_shown = true;
+    if let Err(e) = settings::save(app, current) {
+        log::warn!("Failed to save tray settings: {}", e);
+    }
+}
+
+/// Hide the main window instead of closing it when close-to-tray is on;
+/// returns whether the close should be prevented
+pub fn hide_on_close(window: &Window) -> bool {
+    let tray_settings = settings::current().tray;
+    if window.label() != "main" || !tray_settings.close_to_tray || !TRAY_AVAILABLE.load(Ordering::SeqCst) {
+        return false;
+    }
+    
+    if let Err(e) = window.hide() {
+        log::warn!("Failed to hide window to tray: {}", e);
+        return false;
+    }
+    show_close_notice(window.app_handle());
+    true
+}
+
+/// Hide the main window when it is minimized and minimize-to-tray is on
+pub fn hide_on_minimize(window: &Window) {
+    let tray_settings = settings::current().tray;
+    if window.label() != "main" || !tray_settings.minimize_to_tray || !TRAY_AVAILABLE.load(Ordering::SeqCst) {
+        return;
+    }
+    if window.is_minimized().unwrap_or(false) {
+        let _ = window.hide();
+    }
+}
+
 /// Handle menu item events
 fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
-    match event.id.as_ref() {
-        id if id.contains("New Chat") => {
+    match event.id().as_ref() {
+        id if id.starts_with(PROFILE_ITEM_PREFIX) => {
+            let name = &id[PROFILE_ITEM_PREFIX.len()..];
+            if name != profiles::active() {
+                if let Err(e) = profiles::relaunch_with_profile(app, name) {
+                    log::error!("Failed to switch profile: {}", e);
+                }
+            }
+        }
+        id if id.starts_with(PROJECT_ITEM_PREFIX) => {
+            let project = id[PROJECT_ITEM_PREFIX.len()..]
+                .parse()
+                .ok()
+                .and_then(TrayManager::recent_project);
+            if let Some(project) = project {
+                let url = DeepLinkPayload::OpenProject {
+                    path: project.path,
+                    callback_id: None,
+                }
+                .to_url();
+                show_main_window(app);
+                let app = app.clone();
+                // Routed to the frontend like a mux://project/open link; waiting
+                // for its outcome surfaces projects that failed to open
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = deeplink::forward_and_wait(&app, &url, DeepLinkSource::Tray).await {
+                        log::warn!("Failed to open recent project: {}", e);
+                    }
+                });
+            }
+        }
+        id if id.starts_with(ACTION_ITEM_PREFIX) => {
+            let action = id[ACTION_ITEM_PREFIX.len()..]
+                .parse()
+                .ok()
+                .and_then(TrayManager::quick_action);
+            if let Some(action) = action {
+                if let Err(e) = app.emit("tray-action", TrayActionPayload { id: action.id }) {
+                    log::error!("Failed to emit tray-action event: {}", e);
+                }
+            }
+        }
+        id if id.starts_with(TERMINAL_ITEM_PREFIX) => {
+            if let Ok(pty_id) = id[TERMINAL_ITEM_PREFIX.len()..].parse::<u32>() {
+                let _ = app.emit("tray-focus-terminal", pty_id);
+                show_main_window(app);
+            }
+        }
+        NEW_CHAT_ID => {
             // Emit an event to the frontend to create a new chat
             let _ = app.emit("tray-new-chat", ());
-            
-            // Show and focus the main window
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
+            show_main_window(app);
         }
-        id if id.contains("Settings") => {
+        SETTINGS_ID => {
             // Emit an event to the frontend to open settings
             let _ = app.emit("tray-open-settings", ());
-            
-            // Show and focus the main window
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
+            show_main_window(app);
         }
-        id if id.contains("Quit") => {
-            // Exit the application
-            app.exit(0);
+        QUIT_ID => {
+            // Really quit, even when closing the window only hides it
+            shutdown::quit(app);
         }
         _ => {}
     }
 }
-
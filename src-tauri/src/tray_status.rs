@@ -0,0 +1,342 @@
+// Tray icon status indication
+//
+// The tray icon carries a colored dot and a tooltip line when something
+// needs attention: the backend stopped, an update is ready, or an agent
+// task is running. Backend and updater state are followed through their
+// events; the frontend reports what it knows (such as running agent
+// tasks) with `set_tray_status`. The most urgent status wins. The icon is
+// re-rendered when the system theme changes so the glyph underneath keeps
+// matching the menu bar or taskbar.
+//
+// Long-running work (update downloads, agent jobs reported with
+// `set_tray_progress`) replaces the badge with an animated progress dial
+// and adds the percentage to the tooltip, so it can be followed while the
+// window is hidden.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::{AppHandle, Listener};
+
+use crate::tray;
+use crate::updater::{self, UpdateStatus};
+
+/// Tooltip shown when nothing needs attention
+const BASE_TOOLTIP: &str = "MUP - Coder Multiplexer";
+
+/// Time between progress animation frames
+const FRAME_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Fraction of a turn the progress dial's highlight moves per frame
+const FRAME_STEP: f64 = 1.0 / 12.0;
+
+/// Dial colors (RGBA)
+const DIAL_TRACK: [u8; 4] = [0x80, 0x80, 0x80, 0xc0];
+const DIAL_FILL: [u8; 4] = [0x3b, 0x82, 0xf6, 0xff];
+const DIAL_HIGHLIGHT: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+/// Current animation frame
+static FRAME: AtomicU32 = AtomicU32::new(0);
+
+/// Whether the animation loop is running
+static ANIMATING: AtomicBool = AtomicBool::new(false);
+
+/// What the tray icon shows, ordered from least to most urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayStatus {
+    Healthy,
+    AgentRunning,
+    UpdateAvailable,
+    BackendCrashed,
+}
+
+impl TrayStatus {
+    /// Badge color (RGB), `None` for no badge
+    fn badge_color(self) -> Option<[u8; 3]> {
+        match self {
+            TrayStatus::Healthy => None,
+            TrayStatus::AgentRunning => Some([0x22, 0xc5, 0x5e]),
+            TrayStatus::UpdateAvailable => Some([0x3b, 0x82, 0xf6]),
+            TrayStatus::BackendCrashed => Some([0xe5, 0x48, 0x4d]),
+        }
+    }
+
+    fn tooltip(self) -> String {
+        match self {
+            TrayStatus::Healthy => BASE_TOOLTIP.to_string(),
+            TrayStatus::AgentRunning => format!("{}\nAgent task running", BASE_TOOLTIP),
+            TrayStatus::UpdateAvailable => format!("{}\nUpdate available", BASE_TOOLTIP),
+            TrayStatus::BackendCrashed => format!("{}\nBackend stopped", BASE_TOOLTIP),
+        }
+    }
+}
+
+/// Progress of a long-running task
+#[derive(Debug, Clone, PartialEq)]
+struct Progress {
+    /// 0 to 100
+    percent: f64,
+    label: String,
+}
+
+/// Status inputs, combined into the shown status
+struct Signals {
+    /// Last status set by the frontend
+    reported: TrayStatus,
+    backend_crashed: bool,
+    update_available: bool,
+    /// Task progress set by the frontend
+    task_progress: Option<Progress>,
+    /// Update download progress
+    update_progress: Option<Progress>,
+}
+
+static SIGNALS: Mutex<Signals> = Mutex::new(Signals {
+    reported: TrayStatus::Healthy,
+    backend_crashed: false,
+    update_available: false,
+    task_progress: None,
+    update_progress: None,
+});
+
+impl Signals {
+    fn status(&self) -> TrayStatus {
+        let mut status = self.reported;
+        if self.update_available {
+            status = status.max(TrayStatus::UpdateAvailable);
+        }
+        if self.backend_crashed {
+            status = status.max(TrayStatus::BackendCrashed);
+        }
+        status
+    }
+
+    /// Progress to show; the frontend's task wins over an update download
+    fn progress(&self) -> Option<Progress> {
+        self.task_progress.clone().or_else(|| self.update_progress.clone())
+    }
+}
+
+/// The status the tray icon currently shows
+pub fn current() -> TrayStatus {
+    SIGNALS.lock().map(|signals| signals.status()).unwrap_or(TrayStatus::Healthy)
+}
+
+/// Draw a status dot in the bottom-right corner of the icon
+fn badged(icon: &Image<'_>, color: [u8; 3]) -> Image<'static> {
+    let (width, height) = (icon.width() as i64, icon.height() as i64);
+    let mut rgba = icon.rgba().to_vec();
+    let radius = width.min(height) * 3 / 16;
+    let (cx, cy) = (width - radius - 1, height - radius - 1);
+    for y in (cy - radius).max(0)..=(cy + radius).min(height - 1) {
+        for x in (cx - radius).max(0)..=(cx + radius).min(width - 1) {
+            if (x - cx).pow(2) + (y - cy).pow(2) <= radius.pow(2) {
+                let offset = ((y * width + x) * 4) as usize;
+                rgba[offset..offset + 4].copy_from_slice(&[color[0], color[1], color[2], 0xff]);
+            }
+        }
+    }
+    Image::new_owned(rgba, width as u32, height as u32)
+}
+
+/// Draw a progress dial in the bottom-right corner of the icon, with a
+/// highlight on its rim that moves each frame
+fn with_dial(icon: &Image<'_>, percent: f64, frame: u32) -> Image<'static> {
+    let (width, height) = (icon.width() as i64, icon.height() as i64);
+    let mut rgba = icon.rgba().to_vec();
+    let radius = width.min(height) * 5 / 16;
+    let rim = (radius / 4).max(1);
+    let (cx, cy) = (width - radius - 1, height - radius - 1);
+    let filled = percent.clamp(0.0, 100.0) / 100.0;
+    let highlight = (frame as f64 * FRAME_STEP).fract();
+
+    for y in (cy - radius).max(0)..=(cy + radius).min(height - 1) {
+        for x in (cx - radius).max(0)..=(cx + radius).min(width - 1) {
+            let (dx, dy) = (x - cx, y - cy);
+            let distance_sq = dx.pow(2) + dy.pow(2);
+            if distance_sq > radius.pow(2) {
+                continue;
+            }
+            // Fraction of a turn clockwise from 12 o'clock
+            let turn = ((dx as f64).atan2(-dy as f64) / std::f64::consts::TAU).rem_euclid(1.0);
+            let on_rim = distance_sq >= (radius - rim).pow(2);
+            let near_highlight = {
+                let apart = (turn - highlight).abs();
+                apart.min(1.0 - apart) < FRAME_STEP / 2.0
+            };
+            let color = if on_rim && near_highlight {
+                DIAL_HIGHLIGHT
+            } else if turn < filled {
+                DIAL_FILL
+            } else {
+                DIAL_TRACK
+            };
+            let offset = ((y * width + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+    Image::new_owned(rgba, width as u32, height as u32)
+}
+
+/// Show the current status on the tray icon, if there is one
+pub fn apply(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(tray::TRAY_ID) else {
+        return;
+    };
+    let (status, progress) = match SIGNALS.lock() {
+        Ok(signals) => (signals.status(), signals.progress()),
+        Err(_) => return,
+    };
+    let overlaid = progress.is_some() || status.badge_color().is_some();
+    let Some((icon, is_template)) = tray::themed_icon(overlaid) else {
+        return;
+    };
+
+    // Progress takes the badge's corner while a task runs
+    let icon = match (&progress, status.badge_color()) {
+        (Some(progress), _) => with_dial(&icon, progress.percent, FRAME.load(Ordering::Relaxed)),
+        (None, Some(color)) => badged(&icon, color),
+        (None, None) => icon,
+    };
+    let mut tooltip = status.tooltip();
+    if let Some(progress) = &progress {
+        tooltip.push_str(&format!("\n{} {:.0}%", progress.label, progress.percent));
+    }
+
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        log::warn!("Failed to update tray icon: {}", e);
+    }
+    let _ = tray.set_icon_as_template(is_template);
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+fn has_progress() -> bool {
+    SIGNALS.lock().map(|signals| signals.progress().is_some()).unwrap_or(false)
+}
+
+/// Advance the progress animation until no task is in progress
+fn animate(app: &AppHandle) {
+    if ANIMATING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FRAME_INTERVAL).await;
+            if !has_progress() {
+                ANIMATING.store(false, Ordering::SeqCst);
+                // Keep going if progress was set again before the flag cleared
+                if !has_progress() || ANIMATING.swap(true, Ordering::SeqCst) {
+                    apply(&app);
+                    return;
+                }
+            }
+            FRAME.fetch_add(1, Ordering::Relaxed);
+            apply(&app);
+        }
+    });
+}
+
+/// Change a progress input, starting the animation if needed
+fn update_progress(app: &AppHandle, change: impl FnOnce(&mut Signals)) {
+    let active = match SIGNALS.lock() {
+        Ok(mut signals) => {
+            change(&mut signals);
+            signals.progress().is_some()
+        }
+        Err(_) => return,
+    };
+    apply(app);
+    if active {
+        animate(app);
+    }
+}
+
+/// Change one input and refresh the icon if the shown status changed
+fn update(app: &AppHandle, change: impl FnOnce(&mut Signals)) {
+    let changed = match SIGNALS.lock() {
+        Ok(mut signals) => {
+            let before = signals.status();
+            change(&mut signals);
+            signals.status() != before
+        }
+        Err(_) => false,
+    };
+    if changed {
+        apply(app);
+    }
+}
+
+fn update_available(status: &UpdateStatus) -> Option<bool> {
+    match status {
+        UpdateStatus::Available { .. } | UpdateStatus::Downloaded { .. } => Some(true),
+        UpdateStatus::Idle | UpdateStatus::UpToDate => Some(false),
+        _ => None,
+    }
+}
+
+/// Follow backend and updater events
+pub fn init(app: &AppHandle) {
+    if let Some(available) = updater::last_status().as_ref().and_then(update_available) {
+        update(app, |signals| signals.update_available = available);
+    }
+
+    let handle = app.clone();
+    app.listen_any("backend-ready", move |_| {
+        update(&handle, |signals| signals.backend_crashed = false);
+    });
+
+    let handle = app.clone();
+    app.listen_any("backend-terminated", move |_| {
+        update(&handle, |signals| signals.backend_crashed = true);
+    });
+
+    let handle = app.clone();
+    app.listen_any("update-status", move |event| {
+        let Ok(status) = serde_json::from_str::<UpdateStatus>(event.payload()) else {
+            return;
+        };
+        if let Some(available) = update_available(&status) {
+            update(&handle, |signals| signals.update_available = available);
+        }
+
+        let progress = match status {
+            UpdateStatus::Downloading { progress, total } if total > 0 => Some(Progress {
+                percent: progress as f64 * 100.0 / total as f64,
+                label: "Downloading update".to_string(),
+            }),
+            _ => None,
+        };
+        let changed = SIGNALS
+            .lock()
+            .map(|signals| signals.update_progress != progress)
+            .unwrap_or(false);
+        if changed {
+            update_progress(&handle, |signals| signals.update_progress = progress);
+        }
+    });
+}
+
+/// Set the status reported by the frontend, e.g. while an agent task runs;
+/// backend and update problems still take precedence
+#[tauri::command]
+pub async fn set_tray_status(app: AppHandle, status: TrayStatus) -> Result<(), String> {
+    update(&app, |signals| signals.reported = status);
+    Ok(())
+}
+
+/// Show a task's progress on the tray icon, `None` to clear it
+#[tauri::command]
+pub async fn set_tray_progress(app: AppHandle, percent: Option<f64>, label: Option<String>) -> Result<(), String> {
+    if percent.is_some_and(|percent| !percent.is_finite()) {
+        return Err("Progress must be a number between 0 and 100".to_string());
+    }
+    let progress = percent.map(|percent| Progress {
+        percent: percent.clamp(0.0, 100.0),
+        label: label.unwrap_or_else(|| "Working".to_string()),
+    });
+    update_progress(&app, |signals| signals.task_progress = progress);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+// Pre-update hooks
+//
+// Installing an update replaces the backend binary underneath running
+// work, so the app is quiesced first: the main window's state is saved,
+// terminal session state is flushed from the journal to its snapshot, and
+// the backend is stopped once the calls it is serving have finished. If
+// any step fails the install is aborted with an error naming the step, and
+// a backend that was already stopped is started again.
+
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::update_telemetry::{PhaseSpan, UpdatePhase};
+use crate::{orpc_bridge, session_journal, settings, sidecar, window_state};
+
+/// Longest the backend may take to finish in-flight calls before an update
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Prepare the app for an update to be installed
+pub async fn quiesce(app: &AppHandle, version: &str) -> Result<(), String> {
+    let span = PhaseSpan::start(app, UpdatePhase::Quiesce, Some(version.to_string()));
+
+    let result = async {
+        window_state::save(app).map_err(|e| format!("could not save window state: {}", e))?;
+        session_journal::flush().map_err(|e| format!("could not flush session state: {}", e))?;
+        orpc_bridge::stop_backend(DRAIN_TIMEOUT, false)
+            .await
+            .map_err(|e| format!("could not stop the backend: {}", e))
+    }
+    .await;
+
+    match result {
+        Ok(()) => {
+            span.succeed(app);
+            Ok(())
+        }
+        Err(e) => {
+            span.fail(app, "quiesce_failed", e.clone());
+            resume(app).await;
+            Err(format!("Update aborted: {}", e))
+        }
+    }
+}
+
+/// Start the backend again after an update didn't restart the app
+pub async fn resume(app: &AppHandle) {
+    if sidecar::is_backend_ready() {
+        return;
+    }
+
+    let app = app.clone();
+    let config = settings::current().sidecar;
+    let result = tauri::async_runtime::spawn_blocking(move || sidecar::spawn_sidecar(&app, &config))
+        .await
+        .map_err(|e| format!("Restart task failed: {}", e))
+        .and_then(|result| result);
+    if let Err(e) = result {
+        log::error!("Failed to restart backend after update: {}", e);
+    }
+}
@@ -0,0 +1,231 @@
+// Enterprise update policy
+//
+// Administrators can manage how the app updates through a policy file,
+// and on Windows and macOS through the platform's managed settings, which
+// take precedence over the file:
+//   - Windows: HKLM\SOFTWARE\Policies\mup (DisableUpdates, UpdateUrl,
+//     SilentInstall), or %ProgramData%\mup\update-policy.json
+//   - macOS: the com.mup.app managed preferences (same keys), or
+//     /Library/Application Support/mup/update-policy.json
+//   - Linux: /etc/mup/update-policy.json
+// Policy files only count when an administrator owns them (root on macOS
+// and Linux, where they must not be writable by others either), so a
+// standard user can't turn updates off or redirect them.
+// The policy can turn updates off, pin the update server, and force
+// silent installs: scheduled checks then download updates in the
+// background and install them when the app quits, without prompting or
+// showing the Windows installer. The policy is read once at startup.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Policy file name inside the platform's policy directory
+const POLICY_FILE: &str = "update-policy.json";
+
+/// Update policy set by an administrator
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UpdatePolicy {
+    /// Whether any policy source was found; the UI locks update controls
+    #[serde(skip_deserializing)]
+    pub managed: bool,
+    /// No update checks, downloads or installs
+    pub disable_updates: bool,
+    /// Update manifest URL replacing the built-in endpoints
+    pub update_url: Option<String>,
+    /// Install updates at quit without asking
+    pub silent_install: bool,
+}
+
+static POLICY: OnceLock<UpdatePolicy> = OnceLock::new();
+
+/// Policy file location for this platform
+fn policy_path() -> Option<PathBuf> {
+    #[cfg(windows)]
+    return std::env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join("mup").join(POLICY_FILE));
+
+    #[cfg(target_os = "macos")]
+    return Some(PathBuf::from("/Library/Application Support/mup").join(POLICY_FILE));
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    return Some(PathBuf::from("/etc/mup").join(POLICY_FILE));
+}
+
+/// Whether the file is owned by the Administrators group or SYSTEM
+#[cfg(windows)]
+fn is_admin_owned(path: &Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Authorization::{GetNamedSecurityInfoW, SE_FILE_OBJECT};
+    use windows_sys::Win32::Security::{
+        IsWellKnownSid, WinBuiltinAdministratorsSid, WinLocalSystemSid, OWNER_SECURITY_INFORMATION,
+        PSECURITY_DESCRIPTOR, PSID,
+    };
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut owner: PSID = std::ptr::null_mut();
+    let mut descriptor: PSECURITY_DESCRIPTOR = std::ptr::null_mut();
+    let status = unsafe {
+        GetNamedSecurityInfoW(
+            path.as_ptr(),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION,
+            &mut owner,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut descriptor,
+        )
+    };
+    if status != 0 {
+        return false;
+    }
+
+    // `owner` points into `descriptor`, so check it before freeing
+    let admin = unsafe {
+        IsWellKnownSid(owner, WinBuiltinAdministratorsSid) != 0 || IsWellKnownSid(owner, WinLocalSystemSid) != 0
+    };
+    unsafe { LocalFree(descriptor) };
+    admin
+}
+
+/// Whether the file is owned by root and only root can write it
+#[cfg(unix)]
+fn is_admin_owned(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).is_ok_and(|meta| meta.uid() == 0 && meta.mode() & 0o022 == 0)
+}
+
+fn read_file() -> Option<UpdatePolicy> {
+    let path = policy_path()?;
+    let contents = std::fs::read_to_string(&path).ok()?;
+    if !is_admin_owned(&path) {
+        log::warn!("Ignoring update policy {}: not owned by an administrator", path.display());
+        return None;
+    }
+    match serde_json::from_str(&contents) {
+        Ok(policy) => Some(policy),
+        Err(e) => {
+            log::warn!("Ignoring invalid update policy {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Values under HKLM\SOFTWARE\Policies\mup
+#[cfg(windows)]
+fn read_managed(policy: &mut UpdatePolicy) -> bool {
+    use crate::win_registry;
+    use windows_sys::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+    const SUBKEY: &str = "SOFTWARE\\Policies\\mup";
+    let dword = |name: &str| win_registry::read_dword(HKEY_LOCAL_MACHINE, SUBKEY, name).map(|value| value != 0);
+    let string = |name: &str| win_registry::read_string(HKEY_LOCAL_MACHINE, SUBKEY, Some(name));
+
+    let mut found = false;
+    if let Some(value) = dword("DisableUpdates") {
+        policy.disable_updates = value;
+        found = true;
+    }
+    if let Some(value) = string("UpdateUrl") {
+        policy.update_url = Some(value);
+        found = true;
+    }
+    if let Some(value) = dword("SilentInstall") {
+        policy.silent_install = value;
+        found = true;
+    }
+    found
+}
+
+/// Keys in the com.mup.app managed preferences (configuration profiles)
+#[cfg(target_os = "macos")]
+fn read_managed(policy: &mut UpdatePolicy) -> bool {
+    const DOMAIN: &str = "/Library/Managed Preferences/com.mup.app";
+
+    let read = |key: &str| -> Option<String> {
+        let output = std::process::Command::new("/usr/bin/defaults")
+            .args(["read", DOMAIN, key])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let flag = |key: &str| read(key).map(|value| value == "1" || value.eq_ignore_ascii_case("true"));
+
+    let mut found = false;
+    if let Some(value) = flag("DisableUpdates") {
+        policy.disable_updates = value;
+        found = true;
+    }
+    if let Some(value) = read("UpdateUrl") {
+        policy.update_url = Some(value);
+        found = true;
+    }
+    if let Some(value) = flag("SilentInstall") {
+        policy.silent_install = value;
+        found = true;
+    }
+    found
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn read_managed(_policy: &mut UpdatePolicy) -> bool {
+    false
+}
+
+fn load() -> UpdatePolicy {
+    let file = read_file();
+    let mut policy = file.clone().unwrap_or_default();
+    let managed = read_managed(&mut policy);
+    policy.managed = managed || file.is_some();
+    policy.update_url = policy.update_url.filter(|url| !url.trim().is_empty());
+    if policy.managed {
+        log::info!("Update policy is managed: {:?}", policy);
+    }
+    policy
+}
+
+/// The administrator's update policy
+pub fn current() -> &'static UpdatePolicy {
+    POLICY.get_or_init(load)
+}
+
+/// Run the Windows installer without any UI when policy forces silent
+/// installs; the updater plugin reads its install mode from the app config,
+/// so this must run before the app is built
+pub fn apply_install_mode(config: &mut tauri::Config) {
+    if !cfg!(windows) || !current().silent_install {
+        return;
+    }
+    let updater = config
+        .plugins
+        .0
+        .entry("updater".to_string())
+        .or_insert_with(|| serde_json::json!({}));
+    let Some(updater) = updater.as_object_mut() else {
+        return;
+    };
+    let windows = updater
+        .entry("windows")
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(windows) = windows.as_object_mut() {
+        windows.insert("installMode".to_string(), serde_json::json!("quiet"));
+    }
+}
+
+/// Error if the policy forbids updating
+pub fn ensure_updates_allowed() -> Result<(), String> {
+    if current().disable_updates {
+        return Err("Updates are disabled by your administrator".to_string());
+    }
+    Ok(())
+}
+
+/// Get the update policy, so the UI can lock managed controls
+#[tauri::command]
+pub async fn get_update_policy() -> Result<UpdatePolicy, String> {
+    Ok(current().clone())
+}
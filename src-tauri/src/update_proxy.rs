@@ -0,0 +1,239 @@
+// Update proxy resolution
+//
+// Update requests go through the proxy set in the update settings if there
+// is one. Otherwise the proxy environment variables (HTTPS_PROXY,
+// ALL_PROXY, NO_PROXY) apply, which the updater's HTTP client reads itself,
+// and failing those the system proxy: Internet Settings on Windows and the
+// network configuration (scutil) on macOS, including their bypass lists.
+// Proxy auto-config (PAC) scripts aren't evaluated.
+
+use crate::settings;
+
+/// Environment variables the HTTP client takes a proxy from
+const PROXY_ENV_VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"];
+
+/// A proxy from system settings with the hosts that bypass it
+struct SystemProxy {
+    url: String,
+    bypass: Vec<String>,
+}
+
+/// Parse a proxy setting, which may omit the scheme
+fn parse_proxy(value: &str) -> Result<url::Url, String> {
+    let value = value.trim();
+    let value = if value.contains("://") {
+        value.to_string()
+    } else {
+        format!("http://{}", value)
+    };
+    url::Url::parse(&value).map_err(|e| format!("Invalid proxy {}: {}", redact(&value), e))
+}
+
+/// Replace the credentials in any URLs in `text`, so proxy passwords
+/// quoted in errors don't reach the UI or the logs
+pub fn redact(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("://") {
+        let (head, tail) = rest.split_at(start + 3);
+        redacted.push_str(head);
+        let end = tail
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '"' | '\'' | ')' | '>' | ','))
+            .unwrap_or(tail.len());
+        let authority = &tail[..end];
+        match authority.rfind('@') {
+            Some(at) => {
+                redacted.push_str("***");
+                redacted.push_str(&authority[at..]);
+            }
+            None => redacted.push_str(authority),
+        }
+        rest = &tail[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Whether `host` matches a bypass list entry such as `*.corp` or `<local>`
+fn bypassed(host: &str, bypass: &[String]) -> bool {
+    bypass.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        if entry == "*" {
+            return true;
+        }
+        if entry == "<local>" {
+            return !host.contains('.');
+        }
+        let suffix = entry.trim_start_matches('*').trim_start_matches('.');
+        !suffix.is_empty() && (host == suffix || host.ends_with(&format!(".{}", suffix)))
+    })
+}
+
+/// HKCU Internet Settings (ProxyEnable, ProxyServer, ProxyOverride)
+#[cfg(windows)]
+fn system_proxy() -> Option<SystemProxy> {
+    use crate::win_registry;
+    use windows_sys::Win32::System::Registry::HKEY_CURRENT_USER;
+
+    const SUBKEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings";
+    let string = |name: &str| win_registry::read_string(HKEY_CURRENT_USER, SUBKEY, Some(name));
+
+    if win_registry::read_dword(HKEY_CURRENT_USER, SUBKEY, "ProxyEnable").unwrap_or(0) == 0 {
+        return None;
+    }
+
+    // Either one proxy for everything or per-protocol "https=host:port;..."
+    let server = string("ProxyServer")?;
+    let url = if server.contains('=') {
+        server
+            .split(';')
+            .filter_map(|part| part.split_once('='))
+            .find(|(protocol, _)| protocol.eq_ignore_ascii_case("https"))
+            .map(|(_, address)| address.to_string())?
+    } else {
+        server
+    };
+    let bypass = string("ProxyOverride")
+        .map(|value| value.split(';').map(str::to_string).collect())
+        .unwrap_or_default();
+    Some(SystemProxy { url, bypass })
+}
+
+/// The HTTPS proxy from `scutil --proxy`
+#[cfg(target_os = "macos")]
+fn system_proxy() -> Option<SystemProxy> {
+    let output = std::process::Command::new("/usr/sbin/scutil")
+        .arg("--proxy")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    let mut enabled = false;
+    let mut host = None;
+    let mut port = None;
+    let mut bypass = Vec::new();
+    let mut in_exceptions = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if in_exceptions {
+            if line == "}" {
+                in_exceptions = false;
+            } else if let Some((_, value)) = line.split_once(" : ") {
+                bypass.push(value.to_string());
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(" : ") else {
+            continue;
+        };
+        match key {
+            "HTTPSEnable" => enabled = value == "1",
+            "HTTPSProxy" => host = Some(value.to_string()),
+            "HTTPSPort" => port = Some(value.to_string()),
+            "ExceptionsList" => in_exceptions = true,
+            _ => {}
+        }
+    }
+
+    if !enabled {
+        return None;
+    }
+    let host = host?;
+    let url = match port {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    };
+    Some(SystemProxy { url, bypass })
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn system_proxy() -> Option<SystemProxy> {
+    None
+}
+
+/// The proxy update requests to `endpoint` should use, when the HTTP
+/// client wouldn't find it on its own
+pub fn resolve(configured: Option<&str>, endpoint: Option<&url::Url>) -> Result<Option<url::Url>, String> {
+    if let Some(proxy) = configured.filter(|proxy| !proxy.trim().is_empty()) {
+        return parse_proxy(proxy).map(Some);
+    }
+
+    // The HTTP client honours these, along with NO_PROXY
+    if PROXY_ENV_VARS.iter().any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty())) {
+        return Ok(None);
+    }
+
+    let Some(system) = system_proxy() else {
+        return Ok(None);
+    };
+    let host = endpoint
+        .and_then(|endpoint| endpoint.host_str())
+        .map(str::to_ascii_lowercase);
+    if host.is_some_and(|host| bypassed(&host, &system.bypass)) {
+        return Ok(None);
+    }
+    parse_proxy(&system.url).map(Some)
+}
+
+/// An HTTP client for update-related requests to `endpoint`
+pub fn client(endpoint: &str, timeout: std::time::Duration) -> Result<reqwest::Client, String> {
+    build(endpoint, reqwest::Client::builder().timeout(timeout))
+}
+
+/// HTTP client for long transfers to `endpoint`: instead of bounding the
+/// whole request, connecting and each wait for data are bounded, so a slow
+/// but progressing download is never cut off
+pub fn transfer_client(
+    endpoint: &str,
+    connect_timeout: std::time::Duration,
+    read_timeout: std::time::Duration,
+) -> Result<reqwest::Client, String> {
+    let builder = reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .read_timeout(read_timeout);
+    build(endpoint, builder)
+}
+
+fn build(endpoint: &str, builder: reqwest::ClientBuilder) -> Result<reqwest::Client, String> {
+    let endpoint = url::Url::parse(endpoint).ok();
+    let proxy = resolve(settings::current().updates.proxy.as_deref(), endpoint.as_ref())?;
+
+    let mut builder = builder.user_agent(concat!("mup/", env!("CARGO_PKG_VERSION")));
+    if let Some(proxy) = proxy {
+        let proxy = reqwest::Proxy::all(proxy.as_str())
+            .map_err(|e| format!("Invalid proxy: {}", redact(&e.to_string())))?;
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Whether an updater error is the proxy refusing our credentials
+pub fn is_auth_failure(error: &tauri_plugin_updater::Error) -> bool {
+    use tauri_plugin_updater::Error;
+
+    match error {
+        Error::Reqwest(e) if e.status().is_some_and(|status| status.as_u16() == 407) => true,
+        Error::Reqwest(_) | Error::Network(_) => is_auth_error(error),
+        _ => false,
+    }
+}
+
+/// Whether an HTTP error mentions a proxy authentication failure
+///
+/// Tunnel failures only surface as text somewhere in the source chain
+/// (a 407 on CONNECT reads "proxy authentication required"). A bare "407"
+/// isn't enough, since ports, sizes and URLs contain it.
+pub fn is_auth_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut text = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        text.push_str(": ");
+        text.push_str(&e.to_string());
+        source = e.source();
+    }
+    let text = text.to_ascii_lowercase();
+    text.contains("proxy auth")
+}
@@ -0,0 +1,347 @@
+// Update rollback
+//
+// Before an update is installed, the current installation is copied aside
+// (the .app bundle on macOS, the AppImage on Linux, the install directory
+// on Windows) together with its version. `rollback_update` restores it and
+// restarts, so users hit by a bad release can return to the version they
+// had. Only the most recent previous version is kept. The backup is first
+// copied next to the installation, then swapped in whole, so files the bad
+// version added don't survive the rollback. The bundle or AppImage is
+// swapped with one rename; on Windows, where a directory holding the
+// running executable can't be renamed, every installed file is renamed out
+// of the way and the staged files are renamed in, undoing the moves if any
+// step fails.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::{sidecar, storage};
+use crate::updater::{self, UpdateErrorKind, UpdateStatus};
+
+/// State file and backup, in the app's local data directory
+const STATE_FILE: &str = "previous-version.json";
+const BACKUP_DIR: &str = "previous-version";
+
+/// Suffix for files moved aside during a restore
+const ASIDE_SUFFIX: &str = "rollback-old";
+
+/// Suffix for the copy of the backup staged next to the installation
+const STAGED_SUFFIX: &str = "rollback-new";
+
+/// Minimum time between progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The installation kept for rollback
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct PreviousVersion {
+    version: String,
+    /// Installation the backup was taken from, and is restored to
+    target: PathBuf,
+}
+
+/// Version that `rollback_update` would restore
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RollbackInfo {
+    pub version: String,
+}
+
+fn rollback_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_local_data_dir()
+        .map(|dir| dir.join("updates"))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+fn load_previous(app: &AppHandle) -> Option<PreviousVersion> {
+    let path = rollback_dir(app).ok()?.join(STATE_FILE);
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// What an update replaces: the app bundle, AppImage or install directory
+pub(crate) fn install_target() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    return std::env::current_exe()
+        .ok()?
+        .ancestors()
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .map(Path::to_path_buf);
+
+    #[cfg(target_os = "linux")]
+    return std::env::var_os("APPIMAGE").map(PathBuf::from);
+
+    #[cfg(windows)]
+    return std::env::current_exe().ok()?.parent().map(Path::to_path_buf);
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    return None;
+}
+
+/// Join a relative path from `list_files`; empty means `root` itself
+fn at(root: &Path, relative: &Path) -> PathBuf {
+    if relative.as_os_str().is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(relative)
+    }
+}
+
+/// Relative paths of the files under `root` (or `root` itself if a file)
+fn list_files(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !root.is_dir() {
+        files.push(PathBuf::new());
+        return Ok(files);
+    }
+
+    let mut stack = vec![PathBuf::new()];
+    while let Some(relative) = stack.pop() {
+        for entry in std::fs::read_dir(root.join(&relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Copy one file, keeping symlinks (frameworks in app bundles) as links
+fn copy_file(source: &Path, target: &Path) -> std::io::Result<u64> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    #[cfg(unix)]
+    if source.symlink_metadata()?.file_type().is_symlink() {
+        std::os::unix::fs::symlink(std::fs::read_link(source)?, target)?;
+        return Ok(0);
+    }
+
+    std::fs::copy(source, target)
+}
+
+fn total_size(root: &Path, files: &[PathBuf]) -> u64 {
+    files
+        .iter()
+        .filter_map(|file| at(root, file).symlink_metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Keep a copy of the running installation before an update replaces it
+pub async fn backup_current(app: &AppHandle) -> Result<(), String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || backup(&app))
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))?
+}
+
+fn backup(app: &AppHandle) -> Result<(), String> {
+    let target = install_target().ok_or("Installation location is unknown")?;
+    let dir = rollback_dir(app)?;
+    let backup = dir.join(BACKUP_DIR);
+
+    // Only the version being replaced is kept
+    if backup.exists() {
+        let removed = if backup.is_dir() {
+            std::fs::remove_dir_all(&backup)
+        } else {
+            std::fs::remove_file(&backup)
+        };
+        removed.map_err(|e| format!("Failed to remove old backup: {}", e))?;
+    }
+    let _ = std::fs::remove_file(dir.join(STATE_FILE));
+
+    let files = list_files(&target).map_err(|e| format!("Failed to list installation: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    storage::ensure_free_space(&dir, total_size(&target, &files))?;
+    for file in &files {
+        copy_file(&at(&target, file), &at(&backup, file))
+            .map_err(|e| format!("Failed to back up {}: {}", file.display(), e))?;
+    }
+
+    let previous = PreviousVersion {
+        version: app.package_info().version.to_string(),
+        target,
+    };
+    let contents = serde_json::to_string_pretty(&previous)
+        .map_err(|e| format!("Failed to serialize rollback state: {}", e))?;
+    std::fs::write(dir.join(STATE_FILE), contents)
+        .map_err(|e| format!("Failed to save rollback state: {}", e))
+}
+
+/// `path` with `.suffix` appended
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Remove a file or directory tree, ignoring one that doesn't exist
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Copy the backup next to the installation, reporting progress
+fn stage(app: &AppHandle, previous: &PreviousVersion, backup: &Path, staged: &Path) -> Result<(), String> {
+    let files = list_files(backup).map_err(|e| format!("Failed to list backup: {}", e))?;
+    let total = total_size(backup, &files);
+    let parent = previous.target.parent().ok_or("Installation has no parent directory")?;
+    storage::ensure_free_space(parent, total)?;
+
+    remove_path(staged).map_err(|e| format!("Failed to remove {}: {}", staged.display(), e))?;
+    let mut progress = 0;
+    let mut last_emit: Option<Instant> = None;
+    for file in &files {
+        progress += copy_file(&at(backup, file), &at(staged, file))
+            .map_err(|e| format!("Failed to stage {}: {}", file.display(), e))?;
+
+        if last_emit.is_some_and(|emitted| emitted.elapsed() < PROGRESS_INTERVAL) {
+            continue;
+        }
+        last_emit = Some(Instant::now());
+        let status = UpdateStatus::RollingBack {
+            version: previous.version.clone(),
+            progress,
+            total,
+        };
+        if let Err(e) = updater::emit_status(app, &status) {
+            log::error!("Failed to emit update-status event: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Replace the installation with the staged copy in one rename
+#[cfg(not(windows))]
+fn swap_in(staged: &Path, target: &Path) -> Result<(), String> {
+    let aside = with_suffix(target, ASIDE_SUFFIX);
+    remove_path(&aside).map_err(|e| format!("Failed to remove {}: {}", aside.display(), e))?;
+    std::fs::rename(target, &aside).map_err(|e| format!("Failed to move {} aside: {}", target.display(), e))?;
+    if let Err(e) = std::fs::rename(staged, target) {
+        let _ = std::fs::rename(&aside, target);
+        return Err(format!("Failed to restore {}: {}", target.display(), e));
+    }
+    let _ = remove_path(&aside);
+    Ok(())
+}
+
+/// Replace every installed file with the staged ones; files only the bad
+/// version had are moved aside and not replaced
+#[cfg(windows)]
+fn swap_in(staged: &Path, target: &Path) -> Result<(), String> {
+    let installed = list_files(target).map_err(|e| format!("Failed to list installation: {}", e))?;
+    let staged_files = list_files(staged).map_err(|e| format!("Failed to list staged files: {}", e))?;
+
+    // (original, aside) for installed files, then staged files moved in
+    let mut moved_aside: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut moved_in: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let undo = |moved_aside: &[(PathBuf, PathBuf)], moved_in: &[(PathBuf, PathBuf)]| {
+        for (from, to) in moved_in.iter().rev() {
+            let _ = std::fs::rename(to, from);
+        }
+        for (original, aside) in moved_aside.iter().rev() {
+            let _ = std::fs::rename(aside, original);
+        }
+    };
+
+    for file in &installed {
+        let original = at(target, file);
+        let aside = with_suffix(&original, ASIDE_SUFFIX);
+        let _ = std::fs::remove_file(&aside);
+        if let Err(e) = std::fs::rename(&original, &aside) {
+            undo(&moved_aside, &moved_in);
+            return Err(format!("Failed to replace {}: {}", original.display(), e));
+        }
+        moved_aside.push((original, aside));
+    }
+    for file in &staged_files {
+        let from = at(staged, file);
+        let to = at(target, file);
+        let moved = to
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::rename(&from, &to));
+        if let Err(e) = moved {
+            undo(&moved_aside, &moved_in);
+            return Err(format!("Failed to restore {}: {}", to.display(), e));
+        }
+        moved_in.push((from, to));
+    }
+
+    // Files the running process holds open (its own executable) can't be
+    // deleted yet and are left behind
+    for (_, aside) in moved_aside {
+        let _ = std::fs::remove_file(aside);
+    }
+    let _ = remove_path(staged);
+    Ok(())
+}
+
+/// Stage the backup next to the installation and swap it in
+fn restore(app: &AppHandle, previous: &PreviousVersion, backup: &Path) -> Result<(), String> {
+    let staged = with_suffix(&previous.target, STAGED_SUFFIX);
+    let result = stage(app, previous, backup, &staged).and_then(|()| swap_in(&staged, &previous.target));
+    if result.is_err() {
+        let _ = remove_path(&staged);
+    }
+    result
+}
+
+/// Get the version a rollback would restore, if one is kept
+#[tauri::command]
+pub async fn get_rollback_version(app: AppHandle) -> Result<Option<RollbackInfo>, String> {
+    Ok(load_previous(&app).map(|previous| RollbackInfo {
+        version: previous.version,
+    }))
+}
+
+/// Restore the version that was installed before the last update, then
+/// restart into it
+#[tauri::command]
+pub async fn rollback_update(app: AppHandle) -> Result<(), String> {
+    let previous = load_previous(&app).ok_or("No previous version to roll back to")?;
+    let backup = rollback_dir(&app)?.join(BACKUP_DIR);
+    if !backup.exists() {
+        return Err(format!("Backup of version {} is missing", previous.version));
+    }
+
+    // The backend's files may be among those replaced
+    if let Err(e) = sidecar::terminate_sidecar().await {
+        log::warn!("Failed to stop sidecar before rollback: {}", e);
+    }
+
+    let app_handle = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || restore(&app_handle, &previous, &backup))
+        .await
+        .map_err(|e| format!("Rollback failed: {}", e))?;
+    if let Err(e) = result {
+        let status = UpdateStatus::Error {
+            message: format!("Failed to roll back: {}", e),
+            kind: UpdateErrorKind::Other,
+        };
+        updater::emit_status(&app, &status)?;
+        return Err(e);
+    }
+
+    if let Ok(dir) = rollback_dir(&app) {
+        let _ = std::fs::remove_file(dir.join(STATE_FILE));
+        let backup = dir.join(BACKUP_DIR);
+        let _ = if backup.is_dir() {
+            std::fs::remove_dir_all(backup)
+        } else {
+            std::fs::remove_file(backup)
+        };
+    }
+    app.restart()
+}
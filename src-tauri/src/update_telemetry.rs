@@ -0,0 +1,190 @@
+// Updater telemetry
+//
+// Each updater phase (preflight checks, manifest fetch, download, signature
+// check, quiescing the app, extraction, swap) reports when it starts and how it ended, with a
+// stable error code. Events are emitted as `update-telemetry` and the most
+// recent ones are kept in memory so failed updates can be diagnosed after
+// the fact instead of from a single error string.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::update_proxy;
+
+/// Number of events kept for diagnostics
+const MAX_EVENTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePhase {
+    Preflight,
+    ManifestFetch,
+    Quiesce,
+    Download,
+    SignatureCheck,
+    Extraction,
+    Swap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseOutcome {
+    Started,
+    Succeeded,
+    Failed,
+}
+
+/// Payload of the `update-telemetry` event
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UpdateTelemetryEvent {
+    pub phase: UpdatePhase,
+    pub outcome: PhaseOutcome,
+    pub version: Option<String>,
+    /// Stable machine-readable failure code
+    pub error_code: Option<&'static str>,
+    pub message: Option<String>,
+    /// Time spent in the phase, for finished phases
+    pub elapsed_ms: Option<u64>,
+    pub timestamp_ms: u64,
+}
+
+static EVENTS: Mutex<VecDeque<UpdateTelemetryEvent>> = Mutex::new(VecDeque::new());
+
+fn record(app: &AppHandle, event: UpdateTelemetryEvent) {
+    match event.outcome {
+        PhaseOutcome::Failed => log::warn!(
+            "Update phase {:?} failed ({}): {}",
+            event.phase,
+            event.error_code.unwrap_or("unknown"),
+            event.message.as_deref().unwrap_or("")
+        ),
+        _ => log::debug!("Update phase {:?} {:?}", event.phase, event.outcome),
+    }
+
+    if let Ok(mut events) = EVENTS.lock() {
+        if events.len() == MAX_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+    }
+
+    if let Err(e) = app.emit("update-telemetry", event) {
+        log::error!("Failed to emit update-telemetry event: {}", e);
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Error code and the phase a plugin error actually belongs to
+///
+/// Signature failures surface from download, and archive problems from
+/// install, so the reported phase is refined from the error itself.
+pub fn classify(phase: UpdatePhase, error: &tauri_plugin_updater::Error) -> (UpdatePhase, &'static str) {
+    use tauri_plugin_updater::Error;
+
+    match error {
+        Error::Minisign(_) | Error::Base64(_) | Error::SignatureUtf8(_) => {
+            (UpdatePhase::SignatureCheck, "signature_invalid")
+        }
+        Error::BinaryNotFoundInArchive => (UpdatePhase::Extraction, "binary_not_in_archive"),
+        Error::ReleaseNotFound => (phase, "release_not_found"),
+        Error::UnsupportedOs | Error::UnsupportedArch => (phase, "unsupported_platform"),
+        Error::Reqwest(e) if e.is_timeout() => (phase, "timeout"),
+        Error::Reqwest(_) | Error::Network(_) if update_proxy::is_auth_failure(error) => (phase, "proxy_auth"),
+        Error::Reqwest(_) | Error::Network(_) => (phase, "network"),
+        Error::Json(_) => (phase, "invalid_manifest"),
+        Error::Io(_) => (phase, "io"),
+        _ => (phase, "unknown"),
+    }
+}
+
+/// A running updater phase
+pub struct PhaseSpan {
+    phase: UpdatePhase,
+    version: Option<String>,
+    started: Instant,
+}
+
+impl PhaseSpan {
+    /// Report the start of a phase
+    pub fn start(app: &AppHandle, phase: UpdatePhase, version: Option<String>) -> Self {
+        record(
+            app,
+            UpdateTelemetryEvent {
+                phase,
+                outcome: PhaseOutcome::Started,
+                version: version.clone(),
+                error_code: None,
+                message: None,
+                elapsed_ms: None,
+                timestamp_ms: now_ms(),
+            },
+        );
+
+        Self {
+            phase,
+            version,
+            started: Instant::now(),
+        }
+    }
+
+    fn finish(
+        self,
+        app: &AppHandle,
+        phase: UpdatePhase,
+        outcome: PhaseOutcome,
+        error_code: Option<&'static str>,
+        message: Option<String>,
+    ) {
+        record(
+            app,
+            UpdateTelemetryEvent {
+                phase,
+                outcome,
+                version: self.version,
+                error_code,
+                message: message.map(|message| update_proxy::redact(&message)),
+                elapsed_ms: Some(self.started.elapsed().as_millis() as u64),
+                timestamp_ms: now_ms(),
+            },
+        );
+    }
+
+    pub fn succeed(self, app: &AppHandle) {
+        let phase = self.phase;
+        self.finish(app, phase, PhaseOutcome::Succeeded, None, None);
+    }
+
+    /// Report a failure with an explicit code
+    pub fn fail(self, app: &AppHandle, error_code: &'static str, message: impl Into<String>) {
+        let phase = self.phase;
+        self.finish(app, phase, PhaseOutcome::Failed, Some(error_code), Some(message.into()));
+    }
+
+    /// Report a failure from the updater plugin
+    pub fn fail_with(self, app: &AppHandle, error: &tauri_plugin_updater::Error) {
+        let (phase, code) = classify(self.phase, error);
+        self.finish(app, phase, PhaseOutcome::Failed, Some(code), Some(error.to_string()));
+    }
+}
+
+/// Recent updater telemetry, oldest first
+pub fn recent_events() -> Vec<UpdateTelemetryEvent> {
+    EVENTS
+        .lock()
+        .map(|events| events.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Get recent updater telemetry for diagnostics
+#[tauri::command]
+pub async fn get_update_telemetry() -> Result<Vec<UpdateTelemetryEvent>, String> {
+    Ok(recent_events())
+}
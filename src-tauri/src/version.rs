@@ -0,0 +1,95 @@
+// Version parsing
+//
+// The one parser for every version the app compares: its own, the
+// backend's, update manifests, offline packages and release tags. It takes
+// "1.2.3", "v1.2.3", "1.2.3-beta.1" and build metadata ("1.2.3+abc"), and
+// reads missing minor and patch parts as 0. Versions order by semver
+// precedence, so a release sorts after its own pre-releases.
+
+use std::cmp::Ordering;
+
+/// A parsed version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers, e.g. "beta.1"
+    pub pre: Option<String>,
+}
+
+impl Version {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let version = raw.trim().trim_start_matches('v');
+        let version = version.split('+').next()?;
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (version, None),
+        };
+
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre: pre.filter(|pre| !pre.is_empty()),
+        })
+    }
+
+    /// major.minor.patch, ignoring any pre-release
+    pub fn core(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core().cmp(&other.core()).then_with(|| match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => compare_pre(a, b),
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare pre-releases identifier by identifier: numbers numerically and
+/// below words, and a shorter list first when one is a prefix of the other
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Whether `candidate` is newer than `current`; versions that don't parse
+/// never are
+pub(crate) fn is_newer(candidate: &str, current: &str) -> bool {
+    match (Version::parse(candidate), Version::parse(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
@@ -0,0 +1,92 @@
+// Windows registry access
+//
+// Small typed wrappers over RegGetValueW and RegSetKeyValueW for the
+// string and DWORD values the app reads (policies, proxy and theme
+// settings, protocol registrations) and the per-user keys it writes.
+// Missing keys and values read as None.
+
+use windows_sys::Win32::System::Registry::{
+    RegGetValueW, RegSetKeyValueW, HKEY, REG_SZ, RRF_RT_REG_DWORD, RRF_RT_REG_SZ,
+};
+
+/// ERROR_MORE_DATA: the buffer was too small for the value
+const ERROR_MORE_DATA: u32 = 234;
+
+/// A NUL-terminated UTF-16 copy of `value` for Win32 calls
+pub(crate) fn wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Read a REG_SZ value; `None` as the name reads the key's default value
+pub(crate) fn read_string(root: HKEY, subkey: &str, name: Option<&str>) -> Option<String> {
+    let subkey = wide(subkey);
+    let name = name.map(wide);
+    let name_ptr = name.as_ref().map_or(std::ptr::null(), |name| name.as_ptr());
+
+    let mut buffer = vec![0u16; 256];
+    loop {
+        let mut size = (buffer.len() * std::mem::size_of::<u16>()) as u32;
+        let status = unsafe {
+            RegGetValueW(
+                root,
+                subkey.as_ptr(),
+                name_ptr,
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                buffer.as_mut_ptr() as *mut _,
+                &mut size,
+            )
+        };
+        match status {
+            0 => {
+                let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+                return Some(String::from_utf16_lossy(&buffer[..len]));
+            }
+            // `size` now holds the length the value needs
+            ERROR_MORE_DATA => buffer.resize(size as usize / std::mem::size_of::<u16>() + 1, 0),
+            _ => return None,
+        }
+    }
+}
+
+/// Read a REG_DWORD value
+pub(crate) fn read_dword(root: HKEY, subkey: &str, name: &str) -> Option<u32> {
+    let subkey = wide(subkey);
+    let name = wide(name);
+    let mut value: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            root,
+            subkey.as_ptr(),
+            name.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut value as *mut u32 as *mut _,
+            &mut size,
+        )
+    };
+    (status == 0).then_some(value)
+}
+
+/// Write a REG_SZ value, creating the key if needed; `None` as the name
+/// sets the key's default value
+pub(crate) fn write_string(root: HKEY, subkey: &str, name: Option<&str>, value: &str) -> Result<(), String> {
+    let subkey = wide(subkey);
+    let name = name.map(wide);
+    let value = wide(value);
+    let status = unsafe {
+        RegSetKeyValueW(
+            root,
+            subkey.as_ptr(),
+            name.as_ref().map_or(std::ptr::null(), |name| name.as_ptr()),
+            REG_SZ,
+            value.as_ptr() as *const _,
+            (value.len() * std::mem::size_of::<u16>()) as u32,
+        )
+    };
+    if status != 0 {
+        return Err(std::io::Error::from_raw_os_error(status as i32).to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+// Native window effects
+//
+// Lets the frontend give a window a translucent native background: macOS
+// vibrancy, or Mica, acrylic and blur on Windows. The frontend names the
+// effect it wants and gets back the one applied, since each platform only
+// has some of them: the nearest equivalent is used, and platforms without
+// any (Linux) get none. The background tint's opacity can be adjusted for
+// the selected theme. Effects only show through a transparent webview, so
+// the main window is transparent, but the page keeps painting an opaque
+// background until the frontend applies an effect other than `None`.
+//
+// Transparency on macOS needs `macOSPrivateApi`, which rules out
+// distributing the app through the Mac App Store.
+
+use tauri::window::{Color, Effect, EffectState, EffectsBuilder};
+use tauri::{Theme, WebviewWindow};
+
+/// Background tints (RGB) for each theme
+const LIGHT_TINT: [u8; 3] = [0xfa, 0xfa, 0xfa];
+const DARK_TINT: [u8; 3] = [0x18, 0x18, 0x1b];
+
+/// A window background effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowEffect {
+    None,
+    /// macOS vibrancy
+    Vibrancy,
+    /// Windows 11 Mica
+    Mica,
+    /// Windows acrylic
+    Acrylic,
+    Blur,
+}
+
+/// Theme the effect and tint follow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectTheme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl EffectTheme {
+    fn window_theme(self) -> Option<Theme> {
+        match self {
+            EffectTheme::System => None,
+            EffectTheme::Light => Some(Theme::Light),
+            EffectTheme::Dark => Some(Theme::Dark),
+        }
+    }
+
+    /// Whether the window ends up dark
+    fn is_dark(self, window: &WebviewWindow) -> bool {
+        match self {
+            EffectTheme::Light => false,
+            EffectTheme::Dark => true,
+            EffectTheme::System => window.theme().map(|theme| theme == Theme::Dark).unwrap_or(false),
+        }
+    }
+}
+
+/// The platform effect closest to the one asked for, and which effect
+/// that counts as
+#[allow(unused_variables)]
+fn platform_effect(effect: WindowEffect, dark: bool) -> Option<(Effect, WindowEffect)> {
+    #[cfg(target_os = "macos")]
+    {
+        match effect {
+            WindowEffect::None => None,
+            WindowEffect::Acrylic => Some((Effect::HudWindow, WindowEffect::Vibrancy)),
+            _ => Some((Effect::UnderWindowBackground, WindowEffect::Vibrancy)),
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        match effect {
+            WindowEffect::None => None,
+            WindowEffect::Vibrancy | WindowEffect::Mica => {
+                let mica = if dark { Effect::MicaDark } else { Effect::MicaLight };
+                Some((mica, WindowEffect::Mica))
+            }
+            WindowEffect::Acrylic => Some((Effect::Acrylic, WindowEffect::Acrylic)),
+            WindowEffect::Blur => Some((Effect::Blur, WindowEffect::Blur)),
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    {
+        None
+    }
+}
+
+/// Apply a background effect to the calling window; returns the effect
+/// actually applied
+#[tauri::command]
+pub async fn set_window_effect(
+    window: WebviewWindow,
+    effect: WindowEffect,
+    theme: Option<EffectTheme>,
+) -> Result<WindowEffect, String> {
+    let theme = theme.unwrap_or_default();
+    window
+        .set_theme(theme.window_theme())
+        .map_err(|e| format!("Failed to set window theme: {}", e))?;
+
+    let Some((native, applied)) = platform_effect(effect, theme.is_dark(&window)) else {
+        window
+            .set_effects(None)
+            .map_err(|e| format!("Failed to clear window effects: {}", e))?;
+        return Ok(WindowEffect::None);
+    };
+    let effects = EffectsBuilder::new()
+        .effect(native)
+        .state(EffectState::FollowsWindowActiveState)
+        .build();
+    window
+        .set_effects(effects)
+        .map_err(|e| format!("Failed to apply window effect: {}", e))?;
+    Ok(applied)
+}
+
+/// Set how opaque the calling window's background tint is, from 0 (effect
+/// fully visible) to 1 (solid), in the theme's color
+#[tauri::command]
+pub async fn set_window_opacity(
+    window: WebviewWindow,
+    opacity: f64,
+    theme: Option<EffectTheme>,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&opacity) {
+        return Err(format!("Opacity must be between 0 and 1, got {}", opacity));
+    }
+    let [r, g, b] = if theme.unwrap_or_default().is_dark(&window) {
+        DARK_TINT
+    } else {
+        LIGHT_TINT
+    };
+    let alpha = (opacity * 255.0).round() as u8;
+    window
+        .set_background_color(Some(Color(r, g, b, alpha)))
+        .map_err(|e| format!("Failed to set window opacity: {}", e))
+}
@@ -0,0 +1,75 @@
+// Main window state
+//
+// Saves the main window's position, size and maximized state so it can be
+// put back exactly where it was after the app restarts for an update. The
+// saved state is applied once at the next startup and then discarded.
+
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize};
+
+use crate::{displays, profiles};
+
+/// Saved state, in the profile data directory
+const STATE_FILE: &str = "window-state.json";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct SavedWindow {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    maximized: bool,
+}
+
+/// Save the main window's geometry for the next startup
+pub fn save(app: &AppHandle) -> Result<(), String> {
+    // Nothing to save once the window is gone (e.g. while quitting)
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to read window position: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {}", e))?;
+    let saved = SavedWindow {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+    };
+
+    let path = profiles::data_dir(app)?.join(STATE_FILE);
+    let contents = serde_json::to_string_pretty(&saved)
+        .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save window state: {}", e))
+}
+
+/// Apply and discard window state saved by the previous run
+pub fn restore(app: &AppHandle) {
+    let Ok(path) = profiles::data_dir(app).map(|dir| dir.join(STATE_FILE)) else {
+        return;
+    };
+    let Some(saved) = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<SavedWindow>(&contents).ok())
+    else {
+        return;
+    };
+    let _ = std::fs::remove_file(&path);
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if saved.maximized {
+        let _ = window.maximize();
+        return;
+    }
+
+    // Skip positions on a display that is no longer connected
+    if displays::is_on_screen(app, saved.x, saved.y) {
+        let _ = window.set_position(PhysicalPosition::new(saved.x, saved.y));
+    }
+    let _ = window.set_size(PhysicalSize::new(saved.width, saved.height));
+}
@@ -50,6 +50,7 @@ import {
 import { migrateGatewayModel } from "@/hooks/useGatewayModels";
 import { getDefaultModel } from "@/hooks/useModelsFromSettings";
 import type { BranchListResult } from "@/common/orpc/types";
+import type { MuxDeepLinkPayload } from "@/common/types/deepLink";
 import { useTelemetry } from "./hooks/useTelemetry";
 import { getRuntimeTypeForTelemetry } from "@/common/telemetry";
 import { useStartWorkspaceCreation, getFirstProjectPath } from "./hooks/useStartWorkspaceCreation";
@@ -735,6 +736,19 @@ function AppInner() {
     return () => abortController.abort();
   }, [api, openSettings]);
 
+  // Open settings from mux://settings/<section> links (WorkspaceContext
+  // handles the other deep links)
+  useEffect(() => {
+    const handle = (payload: MuxDeepLinkPayload) => {
+      if (payload.type === "open_settings") {
+        openSettings(payload.section);
+      }
+    };
+    const unsubscribe = window.api?.onDeepLink?.(handle, ["open_settings"]);
+    window.api?.consumePendingDeepLinks?.(["open_settings"]).forEach(handle);
+    return () => unsubscribe?.();
+  }, [openSettings]);
+
   // Handle workspace fork switch event
   useEffect(() => {
     const handleForkSwitch = (e: Event) => {
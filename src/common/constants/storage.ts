@@ -156,6 +156,15 @@ export function getInputKey(workspaceId: string): string {
   return `input:${workspaceId}`;
 }
 
+/**
+ * Get the localStorage key marking a draft whose prompt should be sent without
+ * waiting for the user (set by mux://chat/new?send=1 deep links).
+ * Cleared by ChatInput once the draft is sent.
+ */
+export function getAutoSendKey(scopeId: string): string {
+  return `autoSend:${scopeId}`;
+}
+
 /**
  * Get the localStorage key for persisted workspace name-generation state.
  *
@@ -57,7 +57,7 @@ import { PolicyGetResponseSchema } from "./policy";
 // Experiments
 export const ExperimentValueSchema = z.object({
   value: z.union([z.string(), z.boolean(), z.null()]),
-  source: z.enum(["posthog", "cache", "disabled"]),
+  source: z.enum(["posthog", "cache", "disabled", "env"]),
 });
 
 export const experiments = {
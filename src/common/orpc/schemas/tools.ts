@@ -14,7 +14,7 @@ const ToolOutputUiOnlySchema = z.object({
     .optional(),
   notify: z
     .object({
-      notifiedVia: z.enum(["electron", "browser"]),
+      notifiedVia: z.enum(["electron", "native", "browser"]),
       workspaceId: z.string().optional(),
     })
     .optional(),
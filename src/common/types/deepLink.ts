@@ -5,7 +5,8 @@
  * without importing any Electron-only code.
  */
 
-export interface MuxDeepLinkPayload {
+/** mux://chat/new: start a chat draft, optionally with a prompt */
+export interface MuxNewChatDeepLink {
   type: "new_chat";
 
   /**
@@ -20,4 +21,46 @@ export interface MuxDeepLinkPayload {
 
   prompt?: string;
   sectionId?: string;
+
+  /** Send the prompt without waiting for the user (already confirmed natively) */
+  autoSend?: boolean;
+
+  /** Report the outcome with completeDeepLink so the link's callbacks fire */
+  callbackId?: string;
+}
+
+/** mux://chat/<id>: open an existing chat */
+export interface MuxOpenChatDeepLink {
+  type: "open_chat";
+  chatId: string;
+}
+
+/** mux://project/open: open a project, adding it if it isn't configured yet */
+export interface MuxOpenProjectDeepLink {
+  type: "open_project";
+  projectPath: string;
+  callbackId?: string;
 }
+
+/** mux://terminal/new: open a terminal for the workspace containing `cwd` */
+export interface MuxNewTerminalDeepLink {
+  type: "new_terminal";
+  /** Defaults to the selected workspace */
+  cwd?: string;
+  callbackId?: string;
+}
+
+/** mux://settings/<section>: open settings, optionally at a section */
+export interface MuxOpenSettingsDeepLink {
+  type: "open_settings";
+  section?: string;
+}
+
+export type MuxDeepLinkPayload =
+  | MuxNewChatDeepLink
+  | MuxOpenChatDeepLink
+  | MuxOpenProjectDeepLink
+  | MuxNewTerminalDeepLink
+  | MuxOpenSettingsDeepLink;
+
+export type MuxDeepLinkType = MuxDeepLinkPayload["type"];
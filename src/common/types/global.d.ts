@@ -1,6 +1,6 @@
 import type { RouterClient } from "@orpc/server";
 import type { AppRouter } from "@/node/orpc/router";
-import type { MuxDeepLinkPayload } from "@/common/types/deepLink";
+import type { MuxDeepLinkPayload, MuxDeepLinkType } from "@/common/types/deepLink";
 
 // Our simplified permission modes for UI
 export type UIPermissionMode = "plan" | "edit";
@@ -37,10 +37,17 @@ declare global {
     // Register a callback for notification clicks (navigates to workspace)
     // Returns an unsubscribe function.
     onNotificationClicked?: (callback: (data: { workspaceId: string }) => void) => () => void;
-    // Consume any mux:// deep links received before the renderer subscribed.
-    consumePendingDeepLinks?: () => MuxDeepLinkPayload[];
-    // Subscribe to mux:// deep links as they arrive. Returns an unsubscribe function.
-    onDeepLink?: (callback: (payload: MuxDeepLinkPayload) => void) => () => void;
+    // Consume any mux:// deep links received before the renderer subscribed,
+    // optionally only those of the given types.
+    consumePendingDeepLinks?: (types?: MuxDeepLinkType[]) => MuxDeepLinkPayload[];
+    // Subscribe to mux:// deep links as they arrive, optionally only those of
+    // the given types. Returns an unsubscribe function.
+    onDeepLink?: (
+      callback: (payload: MuxDeepLinkPayload) => void,
+      types?: MuxDeepLinkType[]
+    ) => () => void;
+    // Report what a deep link with a callbackId created (or why it failed).
+    completeDeepLink?: (callbackId: string, resourceId: string | null, error?: string) => Promise<void>;
     // Optional ORPC-backed API surfaces populated in tests/storybook mocks
     tokenizer?: unknown;
     providers?: unknown;
@@ -58,7 +58,7 @@ export interface FileEditUiOnlyPayload {
 }
 
 export interface NotifyUiOnlyPayload {
-  notifiedVia: "electron" | "browser";
+  notifiedVia: "electron" | "native" | "browser";
   workspaceId?: string;
 }
 
@@ -94,7 +94,7 @@ const ToolOutputUiOnlySchema = z.object({
     .optional(),
   notify: z
     .object({
-      notifiedVia: z.enum(["electron", "browser"]),
+      notifiedVia: z.enum(["electron", "native", "browser"]),
       workspaceId: z.string().optional(),
     })
     .optional(),
@@ -90,7 +90,7 @@ function isNotifyUiOnly(value: unknown): value is NotifyUiOnlyPayload {
   }
 
   const notifiedVia = value.notifiedVia;
-  if (notifiedVia !== "electron" && notifiedVia !== "browser") {
+  if (notifiedVia !== "electron" && notifiedVia !== "native" && notifiedVia !== "browser") {
     return false;
   }
 
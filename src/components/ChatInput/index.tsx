@@ -49,6 +49,7 @@ import {
   getDraftScopeId,
   getPendingWorkspaceSendErrorKey,
   getWorkspaceLastReadKey,
+  getAutoSendKey,
 } from "@/common/constants/storage";
 import {
   executeCompaction,
@@ -2111,6 +2112,24 @@ const ChatInputInner: React.FC<ChatInputProps> = (props) => {
   // Keep the imperative API pointing at the latest send handler.
   handleSendRef.current = handleSend;
 
+  // A deep link that asked to send its prompt (confirmed natively before it
+  // reached the app) sends the draft once it is ready, exactly once.
+  const autoSendKey =
+    variant === "creation" && creationDraftId
+      ? getAutoSendKey(getDraftScopeId(creationProjectPath, creationDraftId))
+      : null;
+  const creationReady = variant === "creation" && creationState.branchesLoaded;
+  useEffect(() => {
+    if (!autoSendKey || !creationReady || !canSend) {
+      return;
+    }
+    if (!readPersistedState<boolean>(autoSendKey, false)) {
+      return;
+    }
+    updatePersistedState<boolean | undefined>(autoSendKey, undefined);
+    void handleSendRef.current();
+  }, [autoSendKey, creationReady, canSend]);
+
   // Handler for Escape in vim normal mode - cancels edit if editing
   const handleEscapeInNormalMode = () => {
     if (variant === "workspace" && editingMessage && props.onCancelEdit) {
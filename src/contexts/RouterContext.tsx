@@ -13,6 +13,8 @@ import { MUX_HELP_CHAT_WORKSPACE_ID } from "@/common/constants/muxChat";
 import { SELECTED_WORKSPACE_KEY } from "@/common/constants/storage";
 import { getProjectRouteId } from "@/common/utils/projectRouteId";
 import type { WorkspaceSelection } from "@/components/ProjectSidebar";
+import { isTauriEnv } from "@/tauri-api-shim";
+import { commands } from "@/tauri-bindings";
 
 export interface RouterContext {
   navigateToWorkspace: (workspaceId: string) => void;
@@ -159,6 +161,37 @@ function RouterContextInner(props: { children: ReactNode }) {
     void navigateRef.current("/");
   }, []);
 
+  // Desktop: journal the open project so a crashed session can reopen it
+  useEffect(() => {
+    if (!isTauriEnv() || !currentProjectPathFromState) return;
+    commands.setSessionProject(currentProjectPathFromState).catch((error: unknown) => {
+      console.warn("Failed to record session project:", error);
+    });
+  }, [currentProjectPathFromState]);
+
+  // Desktop: after a crash, reopen the project the session had open unless
+  // a saved workspace is being restored instead
+  const recoveryCheckedRef = useRef(false);
+  useEffect(() => {
+    if (!isTauriEnv() || recoveryCheckedRef.current) return;
+    recoveryCheckedRef.current = true;
+    commands
+      .getRecoveredSession()
+      .then((session) => {
+        const project = session?.state.project;
+        const savedWorkspace = readPersistedState<WorkspaceSelection | null>(
+          SELECTED_WORKSPACE_KEY,
+          null
+        );
+        if (session?.crashed && project && !savedWorkspace?.workspaceId) {
+          navigateToProject(project);
+        }
+      })
+      .catch((error: unknown) => {
+        console.warn("Failed to read recovered session:", error);
+      });
+  }, [navigateToProject]);
+
   const value = useMemo<RouterContext>(
     () => ({
       navigateToWorkspace,
@@ -13,11 +13,17 @@ import type { FrontendWorkspaceMetadata } from "@/common/types/workspace";
 import type { ThinkingLevel } from "@/common/types/thinking";
 import type { WorkspaceSelection } from "@/components/ProjectSidebar";
 import type { RuntimeConfig } from "@/common/types/runtime";
-import type { MuxDeepLinkPayload } from "@/common/types/deepLink";
+import type {
+  MuxDeepLinkPayload,
+  MuxDeepLinkType,
+  MuxNewTerminalDeepLink,
+  MuxOpenProjectDeepLink,
+} from "@/common/types/deepLink";
 import { MUX_HELP_CHAT_WORKSPACE_ID } from "@/common/constants/muxChat";
 import {
   deleteWorkspaceStorage,
   getAgentIdKey,
+  getAutoSendKey,
   getDraftScopeId,
   getInputAttachmentsKey,
   getInputKey,
@@ -53,6 +59,7 @@ import { resolveProjectPathFromProjectQuery } from "@/common/utils/deepLink";
 import { shouldApplyWorkspaceAiSettingsFromBackend } from "@/utils/workspaceAiSettingsSync";
 import { isAbortError } from "@/utils/isAbortError";
 import { useRouter } from "@/contexts/RouterContext";
+import { useOpenTerminal } from "@/hooks/useOpenTerminal";
 import { migrateGatewayModel } from "@/hooks/useGatewayModels";
 import { WORKSPACE_DEFAULTS } from "@/constants/workspaceDefaults";
 import type { APIClient } from "@/contexts/API";
@@ -282,6 +289,14 @@ function normalizeWorkspaceDraftsByProject(value: unknown): WorkspaceDraftsByPro
   return result;
 }
 
+/** Deep links routed here; settings links go to the app shell */
+const WORKSPACE_DEEP_LINK_TYPES: MuxDeepLinkType[] = [
+  "new_chat",
+  "open_chat",
+  "open_project",
+  "new_terminal",
+];
+
 function normalizeProjectPathForComparison(projectPath: string): string {
   let normalized = projectPath.trim();
 
@@ -515,7 +530,7 @@ export function WorkspaceProvider(props: WorkspaceProviderProps) {
       });
   }, [api]);
   // Get project refresh function from ProjectContext
-  const { projects, refreshProjects, loading: projectsLoading } = useProjectContext();
+  const { projects, refreshProjects, addProject, loading: projectsLoading } = useProjectContext();
   // Get router navigation functions and current route state
   const {
     navigateToWorkspace,
@@ -564,9 +579,132 @@ export function WorkspaceProvider(props: WorkspaceProviderProps) {
   );
 
   const pendingDeepLinksRef = useRef<MuxDeepLinkPayload[]>([]);
+  const openTerminal = useOpenTerminal();
+
+  // Startup deep links can arrive before projects and workspaces are loaded;
+  // they wait here and are retried once loading finishes.
+  const bufferDeepLink = useCallback((payload: MuxDeepLinkPayload) => {
+    const queue = pendingDeepLinksRef.current;
+    if (queue.length >= 10) {
+      queue.shift();
+    }
+    queue.push(payload);
+  }, []);
+
+  const openProjectFromDeepLink = useCallback(
+    async (client: APIClient, payload: MuxOpenProjectDeepLink) => {
+      const target = normalizeProjectPathForComparison(payload.projectPath);
+      let projectPath =
+        Array.from(projects.keys()).find(
+          (path) => normalizeProjectPathForComparison(path) === target
+        ) ?? null;
+
+      // The link was confirmed natively, so an unknown project is added
+      if (projectPath === null) {
+        let error: string | null = null;
+        try {
+          const result = await client.projects.create({ projectPath: payload.projectPath });
+          if (result.success) {
+            addProject(result.data.normalizedPath, result.data.projectConfig);
+            projectPath = result.data.normalizedPath;
+          } else {
+            error = typeof result.error === "string" ? result.error : "Failed to add project";
+          }
+        } catch (err) {
+          error = err instanceof Error ? err.message : String(err);
+        }
+        if (projectPath === null) {
+          if (payload.callbackId) {
+            void window.api?.completeDeepLink?.(payload.callbackId, null, error ?? undefined);
+          }
+          return;
+        }
+      }
+
+      navigateToProject(projectPath);
+      if (payload.callbackId) {
+        void window.api?.completeDeepLink?.(payload.callbackId, getProjectRouteId(projectPath));
+      }
+    },
+    [addProject, navigateToProject, projects]
+  );
+
+  const openTerminalFromDeepLink = useCallback(
+    (payload: MuxNewTerminalDeepLink) => {
+      const fail = (error: string) => {
+        if (payload.callbackId) {
+          void window.api?.completeDeepLink?.(payload.callbackId, null, error);
+        }
+      };
+
+      // The workspace whose directory is or contains `cwd`, most specific first
+      let workspace: FrontendWorkspaceMetadata | null = null;
+      if (payload.cwd) {
+        const cwd = normalizeProjectPathForComparison(payload.cwd);
+        let matchLength = -1;
+        for (const metadata of workspaceMetadata.values()) {
+          const root = normalizeProjectPathForComparison(metadata.namedWorkspacePath);
+          const contains =
+            cwd === root || cwd.startsWith(`${root}/`) || cwd.startsWith(`${root}\\`);
+          if (contains && root.length > matchLength) {
+            workspace = metadata;
+            matchLength = root.length;
+          }
+        }
+      } else if (currentWorkspaceId) {
+        workspace = workspaceMetadata.get(currentWorkspaceId) ?? null;
+      }
+
+      if (!workspace) {
+        fail(payload.cwd ? `No workspace contains ${payload.cwd}` : "No workspace selected");
+        return;
+      }
+
+      const workspaceId = workspace.id;
+      openTerminal(workspaceId, workspace.runtimeConfig).then(
+        () => {
+          if (payload.callbackId) {
+            void window.api?.completeDeepLink?.(payload.callbackId, workspaceId);
+          }
+        },
+        (err: unknown) => fail(err instanceof Error ? err.message : String(err))
+      );
+    },
+    [currentWorkspaceId, openTerminal, workspaceMetadata]
+  );
 
   const handleDeepLink = useCallback(
     (payload: MuxDeepLinkPayload) => {
+      if (payload.type === "open_chat") {
+        if (workspaceMetadata.has(payload.chatId)) {
+          navigateToWorkspace(payload.chatId);
+        } else if (loading || !api) {
+          bufferDeepLink(payload);
+        } else {
+          console.warn(`[WorkspaceContext] Deep link to unknown chat: ${payload.chatId}`);
+        }
+        return;
+      }
+
+      if (payload.type === "open_project") {
+        if (projectsLoading || !api) {
+          bufferDeepLink(payload);
+        } else {
+          void openProjectFromDeepLink(api, payload);
+        }
+        return;
+      }
+
+      if (payload.type === "new_terminal") {
+        if (loading || !api) {
+          bufferDeepLink(payload);
+        } else {
+          openTerminalFromDeepLink(payload);
+        }
+        return;
+      }
+
+      // Settings links are handled by the app shell
       if (payload.type !== "new_chat") {
         return;
       }
@@ -640,11 +778,9 @@ export function WorkspaceProvider(props: WorkspaceProviderProps) {
         // In that window, buffer unresolved links in-memory and retry once projects load.
         const shouldBuffer = projectsLoading || !api || projects.size === 0;
         if (shouldBuffer) {
-          const queue = pendingDeepLinksRef.current;
-          if (queue.length >= 10) {
-            queue.shift();
-          }
-          queue.push(payload);
+          bufferDeepLink(payload);
+        } else if (payload.callbackId) {
+          void window.api?.completeDeepLink?.(payload.callbackId, null, "No project to start the chat in");
         }
         return;
       }
@@ -682,12 +818,32 @@ export function WorkspaceProvider(props: WorkspaceProviderProps) {
           : null;
 
       if (prompt) {
-        updatePersistedState(getInputKey(getDraftScopeId(resolvedProjectPath, draftId)), prompt);
+        const scopeId = getDraftScopeId(resolvedProjectPath, draftId);
+        updatePersistedState(getInputKey(scopeId), prompt);
+        if (payload.autoSend) {
+          updatePersistedState(getAutoSendKey(scopeId), true);
+        }
       }
 
       navigateToProject(resolvedProjectPath, normalizedSectionId ?? undefined, draftId);
+
+      if (payload.callbackId) {
+        void window.api?.completeDeepLink?.(payload.callbackId, draftId);
+      }
     },
-    [api, navigateToProject, projects, projectsLoading, setWorkspaceDraftsByProjectState]
+    [
+      api,
+      bufferDeepLink,
+      loading,
+      navigateToProject,
+      navigateToWorkspace,
+      openProjectFromDeepLink,
+      openTerminalFromDeepLink,
+      projects,
+      projectsLoading,
+      setWorkspaceDraftsByProjectState,
+      workspaceMetadata,
+    ]
   );
 
   const deepLinkHandlerRef = useRef(handleDeepLink);
@@ -696,9 +852,9 @@ export function WorkspaceProvider(props: WorkspaceProviderProps) {
   useEffect(() => {
     const unsubscribe = window.api?.onDeepLink?.((payload) => {
       deepLinkHandlerRef.current(payload);
-    });
+    }, WORKSPACE_DEEP_LINK_TYPES);
 
-    const pending = window.api?.consumePendingDeepLinks?.() ?? [];
+    const pending = window.api?.consumePendingDeepLinks?.(WORKSPACE_DEEP_LINK_TYPES) ?? [];
     for (const payload of pending) {
       deepLinkHandlerRef.current(payload);
     }
@@ -719,7 +875,7 @@ export function WorkspaceProvider(props: WorkspaceProviderProps) {
     for (const payload of queued) {
       deepLinkHandlerRef.current(payload);
     }
-  }, [projects, projectsLoading, deepLinkHandlerRef]);
+  }, [projects, projectsLoading, loading, workspaceMetadata, deepLinkHandlerRef]);
 
   // Clean up promotions that point at removed drafts or archived workspaces so
   // promoted entries never hide the real workspace list.
@@ -0,0 +1,56 @@
+/**
+ * Overlay Window Entry Point
+ *
+ * Renders progress toasts in the always-on-top activity overlay. The Tauri
+ * shell owns the toast list, timeouts and window placement; this page only
+ * draws what it is sent.
+ */
+
+import { useEffect, useState } from "react";
+import ReactDOM from "react-dom/client";
+import { listen } from "@tauri-apps/api/event";
+import { commands, type OverlayToast } from "@/tauri-bindings";
+import "./styles/globals.css";
+
+function Toast(props: { toast: OverlayToast }) {
+  const { title, message, progress } = props.toast;
+
+  return (
+    <div className="bg-background/95 text-foreground border-border m-1 flex h-[68px] flex-col justify-center gap-1 rounded-md border px-3 shadow-lg">
+      <div className="truncate text-sm font-medium">{title}</div>
+      {message && <div className="text-muted-foreground truncate text-xs">{message}</div>}
+      {progress != null && (
+        <div className="bg-muted h-1 overflow-hidden rounded">
+          <div
+            className="bg-primary h-full transition-[width]"
+            style={{ width: `${Math.round(Math.min(Math.max(progress, 0), 1) * 100)}%` }}
+          />
+        </div>
+      )}
+    </div>
+  );
+}
+
+function Overlay() {
+  const [toasts, setToasts] = useState<OverlayToast[]>([]);
+
+  useEffect(() => {
+    const unlisten = listen<OverlayToast[]>("overlay-toasts", (event) => setToasts(event.payload));
+    commands.getOverlayToasts().then(setToasts, (err) => {
+      console.warn("[Overlay] Failed to load toasts:", err);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div className="flex h-screen flex-col">
+      {toasts.map((toast) => (
+        <Toast key={toast.id} toast={toast} />
+      ))}
+    </div>
+  );
+}
+
+ReactDOM.createRoot(document.getElementById("root")!).render(<Overlay />);
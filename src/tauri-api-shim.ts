@@ -8,16 +8,15 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
-
-// Deep link payload type (matches the frontend's expected structure)
-interface MuxDeepLinkPayload {
-  type: "new_chat";
-  project?: string;
-  projectPath?: string;
-  projectId?: string;
-  prompt?: string;
-  sectionId?: string;
-}
+import { revealItemInDir } from "@tauri-apps/plugin-opener";
+import type { MuxDeepLinkPayload, MuxDeepLinkType } from "@/common/types/deepLink";
+import {
+  commands,
+  type DeepLinkPayload,
+  type EffectTheme,
+  type OpenFilePayload,
+  type WindowEffect,
+} from "@/tauri-bindings";
 
 // WindowApi interface definition (from global.d.ts)
 interface WindowApi {
@@ -37,8 +36,13 @@ interface WindowApi {
   getIsRosetta?: () => Promise<boolean>;
   getIsWindowsWslShell?: () => Promise<boolean>;
   onNotificationClicked?: (callback: (data: { workspaceId: string }) => void) => () => void;
-  consumePendingDeepLinks?: () => MuxDeepLinkPayload[];
-  onDeepLink?: (callback: (payload: MuxDeepLinkPayload) => void) => () => void;
+  consumePendingDeepLinks?: (types?: MuxDeepLinkType[]) => MuxDeepLinkPayload[];
+  onDeepLink?: (
+    callback: (payload: MuxDeepLinkPayload) => void,
+    types?: MuxDeepLinkType[]
+  ) => () => void;
+  completeDeepLink?: (callbackId: string, resourceId: string | null, error?: string) => Promise<void>;
+  setWindowEffect?: (effect: WindowEffect, theme?: EffectTheme) => Promise<WindowEffect>;
   tokenizer?: unknown;
   providers?: unknown;
   nameGeneration?: unknown;
@@ -75,26 +79,108 @@ interface SystemInfo {
   is_windows_wsl_shell: boolean;
 }
 
-// Deep link payload from Tauri (matches Rust struct)
-interface TauriDeepLinkPayload {
-  payload_type: string;
-  project?: string;
-  project_path?: string;
-  project_id?: string;
-  prompt?: string;
-  section_id?: string;
+// Convert Tauri deep link payload to frontend format
+function convertDeepLinkPayload(tauri: DeepLinkPayload): MuxDeepLinkPayload {
+  switch (tauri.type) {
+    case "new_chat":
+      return {
+        type: tauri.type,
+        project: tauri.project ?? undefined,
+        projectPath: tauri.project_path ?? undefined,
+        projectId: tauri.project_id ?? undefined,
+        prompt: tauri.prompt ?? undefined,
+        sectionId: tauri.section_id ?? undefined,
+        autoSend: tauri.auto_send,
+        callbackId: tauri.callback_id ?? undefined,
+      };
+    case "open_chat":
+      return { type: tauri.type, chatId: tauri.chat_id };
+    case "open_project":
+      return {
+        type: tauri.type,
+        projectPath: tauri.path,
+        callbackId: tauri.callback_id ?? undefined,
+      };
+    case "new_terminal":
+      return {
+        type: tauri.type,
+        cwd: tauri.cwd ?? undefined,
+        callbackId: tauri.callback_id ?? undefined,
+      };
+    case "open_settings":
+      return { type: tauri.type, section: tauri.section ?? undefined };
+  }
+}
+
+// Whether a subscriber (or consumer) given `types` takes this link
+function wantsDeepLink(types: MuxDeepLinkType[] | undefined, payload: MuxDeepLinkPayload): boolean {
+  return !types || types.includes(payload.type);
 }
 
-// Convert Tauri deep link payload to frontend format
-function convertDeepLinkPayload(tauri: TauriDeepLinkPayload): MuxDeepLinkPayload {
-  return {
-    type: tauri.payload_type as "new_chat",
-    project: tauri.project,
-    projectPath: tauri.project_path,
-    projectId: tauri.project_id,
-    prompt: tauri.prompt,
-    sectionId: tauri.section_id,
+// Answer a link's x-callback-url callbacks
+async function completeDeepLink(
+  callbackId: string,
+  resourceId: string | null,
+  error?: string
+): Promise<void> {
+  try {
+    await commands.completeDeepLink(callbackId, resourceId, error ?? null);
+  } catch (err) {
+    console.warn("[TauriShim] Failed to complete deep link:", err);
+  }
+}
+
+// Command call timing. The Rust invoke handler only sees how long a
+// command held the thread, not when an async command's body finishes, so
+// each invoke is timed here until its promise settles and the timings are
+// reported in batches (see command_metrics.rs).
+const TIMING_FLUSH_MS = 5000;
+const MAX_PENDING_TIMINGS = 2000;
+
+interface TauriInternals {
+  invoke: (cmd: string, args?: unknown, options?: unknown) => Promise<unknown>;
+}
+
+function instrumentInvoke(): void {
+  const internals = (window as unknown as { __TAURI_INTERNALS__?: TauriInternals })
+    .__TAURI_INTERNALS__;
+  if (!internals) return;
+  const original = internals.invoke.bind(internals);
+  let pending: { command: string; ms: number }[] = [];
+
+  internals.invoke = (cmd, args, options) => {
+    const started = performance.now();
+    const result = original(cmd, args, options);
+    // Plugin commands never reach the app's invoke handler
+    if (!cmd.startsWith("plugin:")) {
+      const done = () => {
+        if (pending.length < MAX_PENDING_TIMINGS) {
+          pending.push({ command: cmd, ms: performance.now() - started });
+        }
+      };
+      result.then(done, done);
+    }
+    return result;
   };
+
+  window.setInterval(() => {
+    if (pending.length === 0) return;
+    const timings = pending;
+    pending = [];
+    // Sent through the original invoke so the report isn't timed itself
+    original("record_command_timings", { timings }).catch((err) => {
+      console.warn("[TauriShim] Failed to report command timings:", err);
+    });
+  }, TIMING_FLUSH_MS);
+}
+
+// Apply a native window effect. The page keeps its opaque background
+// until an effect is actually applied (see the data-window-effect rule in
+// globals.css), so platforms without effects never render see-through.
+async function setWindowEffect(effect: WindowEffect, theme?: EffectTheme): Promise<WindowEffect> {
+  const applied = await commands.setWindowEffect(effect, theme ?? null);
+  document.documentElement.dataset.windowEffect = applied;
+  return applied;
 }
 
 /**
@@ -114,6 +200,8 @@ export async function initTauriApiShim(): Promise<void> {
     return;
   }
 
+  instrumentInvoke();
+
   // Fetch system info from Tauri backend
   let systemInfo: SystemInfo | null = null;
   try {
@@ -127,26 +215,67 @@ export async function initTauriApiShim(): Promise<void> {
   const isRosetta = systemInfo?.is_rosetta ?? false;
   const isWindowsWslShell = systemInfo?.is_windows_wsl_shell ?? false;
 
-  // Pending deep links that arrived before subscription
+  // Pending deep links that arrived before a subscriber for their type.
+  // Links are routed by type (settings links are handled by the app shell,
+  // the rest by WorkspaceContext), so each subscriber claims its own.
+  interface DeepLinkSubscriber {
+    callback: (payload: MuxDeepLinkPayload) => void;
+    types?: MuxDeepLinkType[];
+  }
   let pendingDeepLinks: MuxDeepLinkPayload[] = [];
-  let deepLinkCallbacks: ((payload: MuxDeepLinkPayload) => void)[] = [];
+  const deepLinkSubscribers: DeepLinkSubscriber[] = [];
+
+  const dispatchDeepLink = (tauriPayload: DeepLinkPayload) => {
+    const payload = convertDeepLinkPayload(tauriPayload);
+    const subscribers = deepLinkSubscribers.filter((sub) => wantsDeepLink(sub.types, payload));
+    if (subscribers.length > 0) {
+      subscribers.forEach((sub) => sub.callback(payload));
+    } else {
+      pendingDeepLinks.push(payload);
+    }
+  };
 
   // Listen for deep link events from Tauri
   let deepLinkUnsubscribe: UnlistenFn | null = null;
   try {
-    deepLinkUnsubscribe = await listen<TauriDeepLinkPayload>("mux:deep-link", (event) => {
-      const payload = convertDeepLinkPayload(event.payload);
-      if (deepLinkCallbacks.length > 0) {
-        deepLinkCallbacks.forEach((cb) => cb(payload));
-      } else {
-        pendingDeepLinks.push(payload);
-      }
+    deepLinkUnsubscribe = await listen<DeepLinkPayload>("mux:deep-link", (event) => {
+      dispatchDeepLink(event.payload);
     });
     console.log("[TauriShim] Deep link listener registered");
+
+    // Links from launch arguments, second launches and notifications wait
+    // in a native queue until the listener is attached; draining it also
+    // tells Tauri to emit later links directly
+    const queued = await commands.drainPendingDeepLinks();
+    queued.forEach(dispatchDeepLink);
   } catch (error) {
     console.error("[TauriShim] Failed to register deep link listener:", error);
   }
 
+  // Import opened .mux session files and show where they were unpacked
+  const importOpenedFile = async (file: OpenFilePayload) => {
+    if (!file.manifest) {
+      console.error(`[TauriShim] Can't open ${file.path}: ${file.error ?? "not a session bundle"}`);
+      return;
+    }
+    try {
+      const result = await commands.importSessionBundle(file.path);
+      await revealItemInDir(result.directory);
+    } catch (error) {
+      console.error(`[TauriShim] Failed to import ${file.path}:`, error);
+    }
+  };
+
+  try {
+    await listen<OpenFilePayload>("mux:open-file", (event) => {
+      void importOpenedFile(event.payload);
+    });
+    const opened = await commands.drainPendingOpenFiles();
+    opened.forEach((file) => void importOpenedFile(file));
+  } catch (error) {
+    console.error("[TauriShim] Failed to register open-file listener:", error);
+  }
+
   // Create the window.api interface
   const api: WindowApi = {
     platform: platform as NodeJS.Platform,
@@ -176,23 +305,29 @@ export async function initTauriApiShim(): Promise<void> {
     },
     
     // Deep link handling
-    consumePendingDeepLinks() {
-      const links = [...pendingDeepLinks];
-      pendingDeepLinks = [];
+    consumePendingDeepLinks(types) {
+      const links = pendingDeepLinks.filter((link) => wantsDeepLink(types, link));
+      pendingDeepLinks = pendingDeepLinks.filter((link) => !wantsDeepLink(types, link));
       return links;
     },
     
-    onDeepLink(callback) {
-      deepLinkCallbacks.push(callback);
+    onDeepLink(callback, types) {
+      const subscriber: DeepLinkSubscriber = { callback, types };
+      deepLinkSubscribers.push(subscriber);
       
       // Return unsubscribe function
       return () => {
-        const index = deepLinkCallbacks.indexOf(callback);
+        const index = deepLinkSubscribers.indexOf(subscriber);
         if (index > -1) {
-          deepLinkCallbacks.splice(index, 1);
+          deepLinkSubscribers.splice(index, 1);
         }
       };
     },
+
+    completeDeepLink,
+
+    // Native window background effects
+    setWindowEffect,
     
     // Notification click handling
     // Note: This would need to be implemented in the Tauri backend
@@ -81,6 +81,7 @@ export default defineConfig(({ mode }) => {
         input: {
           main: path.resolve(__dirname, "index.html"),
           terminal: path.resolve(__dirname, "terminal.html"),
+          overlay: path.resolve(__dirname, "overlay.html"),
         },
         output: {
           format: "es",
@@ -130,7 +131,7 @@ export default defineConfig(({ mode }) => {
       esbuildOptions: {
         target: "esnext",
       },
-      entries: ["index.html", "terminal.html"],
+      entries: ["index.html", "terminal.html", "overlay.html"],
       force: false,
     },
     assetsInclude: ["**/*.wasm"],